@@ -0,0 +1,540 @@
+use crate::Daily;
+use crate::citation::Citation;
+use crate::error::{DailyStoicError::StorageError, Result};
+use crate::date;
+use chrono::NaiveDate;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+/// A reflection written against the entry for `date` in `year`.
+#[derive(Clone, Debug)]
+pub struct JournalEntry {
+    pub year: i32,
+    pub date: String,
+    pub text: String
+}
+
+/// An archived entry matching a search, with the matched terms highlighted in
+/// `snippet`.
+#[derive(Clone, Debug, Serialize)]
+pub struct SearchHit {
+    pub date: String,
+    pub title: String,
+    pub snippet: String
+}
+
+/// Where a day of the last `archive build` or `prefetch` got to.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DayStatus {
+    /// Queued, or interrupted before it finished.
+    Pending,
+    Done,
+    /// Failed with this error.
+    Failed(String),
+}
+
+/// A day in the manifest of an unfinished `archive build` or `prefetch`.
+#[derive(Clone, Debug)]
+pub struct ManifestDay {
+    pub date: String,
+    pub status: DayStatus
+}
+
+/// Local SQLite database holding entries for the whole year.
+///
+/// The connection is behind a mutex so a client with an archive can be shared
+/// between tasks, e.g. by `serve`.
+pub struct Archive {
+    conn: Mutex<Connection>
+}
+
+impl Archive {
+    pub fn open(path: &Path) -> Result<Self> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| StorageError(format!("Failed to create archive dir {}: {}", parent.display(), e)))?;
+        }
+
+        let conn = Connection::open(path)
+            .map_err(|e| StorageError(format!("Failed to open archive {}: {}", path.display(), e)))?;
+
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS entries (
+                date        TEXT PRIMARY KEY,
+                day         INTEGER NOT NULL,
+                title       TEXT NOT NULL,
+                quote       TEXT NOT NULL,
+                quoter      TEXT NOT NULL,
+                explanation TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS favorites (
+                date  TEXT PRIMARY KEY,
+                day   INTEGER NOT NULL,
+                added TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS journal (
+                year    INTEGER NOT NULL,
+                date    TEXT NOT NULL,
+                day     INTEGER NOT NULL,
+                text    TEXT NOT NULL,
+                updated TEXT NOT NULL,
+                PRIMARY KEY (year, date)
+            );
+            CREATE TABLE IF NOT EXISTS embeddings (
+                date   TEXT PRIMARY KEY,
+                vector BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS topics (
+                date  TEXT NOT NULL,
+                topic TEXT NOT NULL,
+                PRIMARY KEY (date, topic)
+            );
+            CREATE TABLE IF NOT EXISTS manifest (
+                date    TEXT PRIMARY KEY,
+                day     INTEGER NOT NULL,
+                status  TEXT NOT NULL,
+                error   TEXT,
+                updated TEXT NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS views (
+                day    TEXT NOT NULL,
+                date   TEXT NOT NULL,
+                viewed TEXT NOT NULL,
+                PRIMARY KEY (day, date)
+            );
+            CREATE TABLE IF NOT EXISTS subscribers (
+                service TEXT NOT NULL,
+                chat    TEXT NOT NULL,
+                added   TEXT NOT NULL,
+                PRIMARY KEY (service, chat)
+            );
+            CREATE VIRTUAL TABLE IF NOT EXISTS entries_fts USING fts5(
+                date UNINDEXED, title, quote, explanation
+            );
+            -- index entries archived before search existed
+            INSERT INTO entries_fts (date, title, quote, explanation)
+                SELECT date, title, quote, explanation FROM entries
+                WHERE date NOT IN (SELECT date FROM entries_fts);"
+        ).map_err(|e| StorageError(format!("Failed to initialize archive: {}", e)))?;
+
+        Ok(Archive { conn: Mutex::new(conn) })
+    }
+
+    // a poisoned lock only means another thread panicked mid-query, sqlite itself is fine
+    fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// `$XDG_DATA_HOME/daily-stoic/archive.db` (or the platform equivalent).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("daily-stoic").join("archive.db"))
+    }
+
+    /// The default path, or `archive-<book>.db` beside it for a book other
+    /// than The Daily Stoic so the two don't mix.
+    pub fn path_for(book: Option<&str>) -> Option<PathBuf> {
+        let path = Archive::default_path()?;
+        Some(match book {
+            Some(book) => path.with_file_name(format!("archive-{}.db", book)),
+            None => path,
+        })
+    }
+
+    pub fn get(&self, date: &str) -> Result<Option<Daily>> {
+        self.conn()
+            .query_row(
+                "SELECT date, title, quote, quoter, explanation FROM entries WHERE date = ?1",
+                params![date],
+                row_to_daily,
+            )
+            .optional()
+            .map_err(|e| StorageError(format!("Failed to read {} from archive: {}", date, e)))
+    }
+
+    pub fn put(&self, daily: &Daily) -> Result<()> {
+        let day = day(&daily.date)?;
+        let write = || -> rusqlite::Result<()> {
+            // the entry and its search index change together or not at all
+            let mut conn = self.conn();
+            let tx = conn.transaction()?;
+            tx.execute(
+                "INSERT OR REPLACE INTO entries (date, day, title, quote, quoter, explanation)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                params![
+                    daily.date,
+                    day,
+                    daily.title,
+                    daily.quote,
+                    daily.quoter,
+                    daily.explanation
+                ],
+            )?;
+            tx.execute("DELETE FROM entries_fts WHERE date = ?1", params![daily.date])?;
+            tx.execute(
+                "INSERT INTO entries_fts (date, title, quote, explanation) VALUES (?1, ?2, ?3, ?4)",
+                params![daily.date, daily.title, daily.quote, daily.explanation],
+            )?;
+            tx.commit()
+        };
+
+        write().map_err(|e| StorageError(format!("Failed to write {} to archive: {}", daily.date, e)))
+    }
+
+    /// Archived entries whose title, quote or explanation contain every word of
+    /// `query`, best matches first, with matches wrapped in `highlight`.
+    pub fn search(&self, query: &str, highlight: (&str, &str), limit: usize) -> Result<Vec<SearchHit>> {
+        // quote each word so punctuation in the query isn't read as FTS5 syntax
+        let terms: Vec<String> = query
+            .split_whitespace()
+            .map(|word| format!("\"{}\"", word.replace('"', "\"\"")))
+            .collect();
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let conn = self.conn();
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT f.date, e.title, snippet(entries_fts, -1, ?2, ?3, '...', 12)
+                 FROM entries_fts f JOIN entries e ON e.date = f.date
+                 WHERE entries_fts MATCH ?1
+                 ORDER BY rank
+                 LIMIT ?4"
+            )
+            .map_err(|e| StorageError(format!("Failed to search the archive: {}", e)))?;
+
+        stmt.query_map(
+            params![terms.join(" "), highlight.0, highlight.1, limit as i64],
+            |row| Ok(SearchHit { date: row.get(0)?, title: row.get(1)?, snippet: row.get(2)? }),
+        )
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to search the archive: {}", e)))
+    }
+
+    /// Every archived entry in calendar order.
+    pub fn all(&self) -> Result<Vec<Daily>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT date, title, quote, quoter, explanation FROM entries ORDER BY day")
+            .map_err(|e| StorageError(format!("Failed to read archive: {}", e)))?;
+
+        stmt.query_map([], row_to_daily)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read archive: {}", e)))
+    }
+
+    /// Date of a random archived entry, if there are any.
+    pub fn random_date(&self) -> Result<Option<String>> {
+        self.conn()
+            .query_row("SELECT date FROM entries ORDER BY RANDOM() LIMIT 1", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| StorageError(format!("Failed to pick a random archive entry: {}", e)))
+    }
+
+    pub fn count(&self) -> Result<usize> {
+        self.conn()
+            .query_row("SELECT COUNT(*) FROM entries", [], |row| row.get::<_, i64>(0))
+            .map(|n| n as usize)
+            .map_err(|e| StorageError(format!("Failed to count archive entries: {}", e)))
+    }
+
+    /// Marks `date` as a favorite, returning false if it already was one.
+    pub fn add_favorite(&self, date: &str) -> Result<bool> {
+        self.conn()
+            .execute(
+                "INSERT OR IGNORE INTO favorites (date, day, added) VALUES (?1, ?2, ?3)",
+                params![date, day(date)?, chrono::Local::now().to_rfc3339()],
+            )
+            .map(|n| n > 0)
+            .map_err(|e| StorageError(format!("Failed to add {} to favorites: {}", date, e)))
+    }
+
+    /// Unmarks `date`, returning false if it wasn't a favorite.
+    pub fn remove_favorite(&self, date: &str) -> Result<bool> {
+        self.conn()
+            .execute("DELETE FROM favorites WHERE date = ?1", params![date])
+            .map(|n| n > 0)
+            .map_err(|e| StorageError(format!("Failed to remove {} from favorites: {}", date, e)))
+    }
+
+    /// Favorite dates in calendar order.
+    pub fn favorites(&self) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT date FROM favorites ORDER BY day")
+            .map_err(|e| StorageError(format!("Failed to read favorites: {}", e)))?;
+
+        stmt.query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read favorites: {}", e)))
+    }
+
+    pub fn random_favorite(&self) -> Result<Option<String>> {
+        self.conn()
+            .query_row("SELECT date FROM favorites ORDER BY RANDOM() LIMIT 1", [], |row| row.get(0))
+            .optional()
+            .map_err(|e| StorageError(format!("Failed to pick a random favorite: {}", e)))
+    }
+
+    pub fn put_embedding(&self, date: &str, vector: &[f32]) -> Result<()> {
+        let bytes: Vec<u8> = vector.iter().flat_map(|f| f.to_le_bytes()).collect();
+        self.conn()
+            .execute("INSERT OR REPLACE INTO embeddings (date, vector) VALUES (?1, ?2)", params![date, bytes])
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("Failed to save the embedding for {}: {}", date, e)))
+    }
+
+    /// Every stored embedding by date, in calendar order.
+    pub fn embeddings(&self) -> Result<Vec<(String, Vec<f32>)>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT m.date, m.vector FROM embeddings m JOIN entries e ON e.date = m.date ORDER BY e.day")
+            .map_err(|e| StorageError(format!("Failed to read embeddings: {}", e)))?;
+
+        stmt.query_map([], |row| {
+            let bytes: Vec<u8> = row.get(1)?;
+            let vector = bytes
+                .chunks_exact(4)
+                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+                .collect();
+            Ok((row.get(0)?, vector))
+        })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read embeddings: {}", e)))
+    }
+
+    /// Replaces the themes `date` is tagged with.
+    pub fn set_topics(&self, date: &str, topics: &[String]) -> Result<()> {
+        let write = || -> rusqlite::Result<()> {
+            let conn = self.conn();
+            conn.execute("DELETE FROM topics WHERE date = ?1", params![date])?;
+            for topic in topics {
+                conn.execute("INSERT INTO topics (date, topic) VALUES (?1, ?2)", params![date, topic])?;
+            }
+            Ok(())
+        };
+
+        write().map_err(|e| StorageError(format!("Failed to tag {}: {}", date, e)))
+    }
+
+    /// Dates with at least one theme.
+    pub fn tagged_dates(&self) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT DISTINCT date FROM topics")
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))?;
+
+        stmt.query_map([], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))
+    }
+
+    /// The themes of every tagged date, each date's in alphabetical order.
+    pub fn topics_by_date(&self) -> Result<HashMap<String, Vec<String>>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT date, topic FROM topics ORDER BY topic")
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))?;
+
+        let rows: Vec<(String, String)> = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))?;
+        let mut topics: HashMap<String, Vec<String>> = HashMap::new();
+        for (date, topic) in rows {
+            topics.entry(date).or_default().push(topic);
+        }
+        Ok(topics)
+    }
+
+    /// Every theme with how many entries carry it, most common first.
+    pub fn topic_counts(&self) -> Result<Vec<(String, usize)>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT topic, COUNT(*) FROM topics GROUP BY topic ORDER BY COUNT(*) DESC, topic")
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))?;
+
+        stmt.query_map([], |row| Ok((row.get(0)?, row.get::<_, i64>(1)? as usize)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))
+    }
+
+    /// Archived entries tagged with `topic`, in calendar order.
+    pub fn by_topic(&self, topic: &str) -> Result<Vec<Daily>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(
+                "SELECT e.date, e.title, e.quote, e.quoter, e.explanation
+                 FROM entries e JOIN topics t ON t.date = e.date
+                 WHERE t.topic = ?1 ORDER BY e.day"
+            )
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))?;
+
+        stmt.query_map(params![topic], row_to_daily)
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read topics: {}", e)))
+    }
+
+    /// Saves (or replaces) the reflection for `date` in `year`.
+    pub fn put_journal(&self, entry: &JournalEntry) -> Result<()> {
+        self.conn()
+            .execute(
+                "INSERT OR REPLACE INTO journal (year, date, day, text, updated) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    entry.year,
+                    entry.date,
+                    day(&entry.date)?,
+                    entry.text,
+                    chrono::Local::now().to_rfc3339()
+                ],
+            )
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("Failed to save the journal for {}: {}", entry.date, e)))
+    }
+
+    /// Every year's reflection on `date`, oldest first.
+    pub fn journal(&self, date: &str) -> Result<Vec<JournalEntry>> {
+        self.query_journal("SELECT year, date, text FROM journal WHERE date = ?1 ORDER BY year", params![date])
+    }
+
+    /// All reflections, in the order they were written against the calendar.
+    pub fn all_journal(&self) -> Result<Vec<JournalEntry>> {
+        self.query_journal("SELECT year, date, text FROM journal ORDER BY year, day", [])
+    }
+
+    /// Records where `date` got to in the running build, kept until
+    /// [`clear_manifest`](Self::clear_manifest) so an interrupted or partly
+    /// failed build can pick up where it left off.
+    pub fn mark(&self, date: &str, status: &DayStatus) -> Result<()> {
+        let (status, error) = match status {
+            DayStatus::Pending => ("pending", None),
+            DayStatus::Done => ("done", None),
+            DayStatus::Failed(error) => ("failed", Some(error.as_str())),
+        };
+        self.conn()
+            .execute(
+                "INSERT OR REPLACE INTO manifest (date, day, status, error, updated) VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![date, day(date)?, status, error, chrono::Local::now().to_rfc3339()],
+            )
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("Failed to record the status of {}: {}", date, e)))
+    }
+
+    /// The days of the unfinished build in calendar order, empty once one has
+    /// finished without failures.
+    pub fn manifest(&self) -> Result<Vec<ManifestDay>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT date, status, error FROM manifest ORDER BY day")
+            .map_err(|e| StorageError(format!("Failed to read the build manifest: {}", e)))?;
+
+        stmt.query_map([], |row| {
+            let status = match (row.get::<_, String>(1)?.as_str(), row.get::<_, Option<String>>(2)?) {
+                ("done", _) => DayStatus::Done,
+                ("failed", error) => DayStatus::Failed(error.unwrap_or_default()),
+                _ => DayStatus::Pending,
+            };
+            Ok(ManifestDay { date: row.get(0)?, status })
+        })
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read the build manifest: {}", e)))
+    }
+
+    pub fn clear_manifest(&self) -> Result<()> {
+        self.conn()
+            .execute("DELETE FROM manifest", [])
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("Failed to clear the build manifest: {}", e)))
+    }
+
+    /// Records that the entry for `date` was read on the calendar `day`.
+    pub fn record_view(&self, day: NaiveDate, date: &str) -> Result<()> {
+        self.conn()
+            .execute(
+                "INSERT OR IGNORE INTO views (day, date, viewed) VALUES (?1, ?2, ?3)",
+                params![day.format("%Y-%m-%d").to_string(), date, chrono::Local::now().to_rfc3339()],
+            )
+            .map(|_| ())
+            .map_err(|e| StorageError(format!("Failed to record reading {}: {}", date, e)))
+    }
+
+    /// How many entries were read on each calendar day anything was.
+    pub fn views_per_day(&self) -> Result<BTreeMap<NaiveDate, usize>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT day, COUNT(*) FROM views GROUP BY day")
+            .map_err(|e| StorageError(format!("Failed to read the reading history: {}", e)))?;
+
+        let rows: Vec<(String, i64)> = stmt
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read the reading history: {}", e)))?;
+        Ok(rows
+            .into_iter()
+            .filter_map(|(day, count)| Some((NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok()?, count as usize)))
+            .collect())
+    }
+
+    /// Subscribes `chat` on `service` (e.g. "telegram") to the daily push,
+    /// returning false if it already was subscribed.
+    pub fn add_subscriber(&self, service: &str, chat: &str) -> Result<bool> {
+        self.conn()
+            .execute(
+                "INSERT OR IGNORE INTO subscribers (service, chat, added) VALUES (?1, ?2, ?3)",
+                params![service, chat, chrono::Local::now().to_rfc3339()],
+            )
+            .map(|n| n > 0)
+            .map_err(|e| StorageError(format!("Failed to subscribe {} chat {}: {}", service, chat, e)))
+    }
+
+    /// Unsubscribes `chat`, returning false if it wasn't subscribed.
+    pub fn remove_subscriber(&self, service: &str, chat: &str) -> Result<bool> {
+        self.conn()
+            .execute("DELETE FROM subscribers WHERE service = ?1 AND chat = ?2", params![service, chat])
+            .map(|n| n > 0)
+            .map_err(|e| StorageError(format!("Failed to unsubscribe {} chat {}: {}", service, chat, e)))
+    }
+
+    pub fn subscribers(&self, service: &str) -> Result<Vec<String>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare("SELECT chat FROM subscribers WHERE service = ?1 ORDER BY added")
+            .map_err(|e| StorageError(format!("Failed to read {} subscribers: {}", service, e)))?;
+
+        stmt.query_map(params![service], |row| row.get(0))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read {} subscribers: {}", service, e)))
+    }
+
+    fn query_journal(&self, sql: &str, params: impl rusqlite::Params) -> Result<Vec<JournalEntry>> {
+        let conn = self.conn();
+        let mut stmt = conn
+            .prepare(sql)
+            .map_err(|e| StorageError(format!("Failed to read the journal: {}", e)))?;
+
+        stmt.query_map(params, |row| Ok(JournalEntry { year: row.get(0)?, date: row.get(1)?, text: row.get(2)? }))
+            .and_then(|rows| rows.collect())
+            .map_err(|e| StorageError(format!("Failed to read the journal: {}", e)))
+    }
+}
+
+fn row_to_daily(row: &rusqlite::Row) -> rusqlite::Result<Daily> {
+    let quoter: String = row.get(3)?;
+    Ok(Daily {
+        date: row.get(0)?,
+        title: row.get(1)?,
+        quote: row.get(2)?,
+        citation: Citation::parse(&quoter),
+        quoter,
+        explanation: row.get(4)?
+    })
+}
+
+// the position of `date` in the year, which keeps entries, favorites and the journal in calendar order
+fn day(date: &str) -> Result<u32> {
+    date::day_of_year(date).ok_or_else(|| StorageError(format!("Invalid date \"{}\", expected e.g. March 5", date)))
+}
@@ -0,0 +1,157 @@
+//! A greeting style rendering: the title in big block letters and the quote in
+//! a box, like figlet and boxes would print them.
+
+use crate::theme::Theme;
+use crate::Daily;
+use unicode_width::UnicodeWidthStr;
+
+const HEIGHT: usize = 5;
+
+// each glyph is HEIGHT rows of '#' (ink) and '.' (paper)
+const FONT: [(char, [&str; HEIGHT]); 46] = [
+    ('A', [".##.", "#..#", "####", "#..#", "#..#"]),
+    ('B', ["###.", "#..#", "###.", "#..#", "###."]),
+    ('C', [".###", "#...", "#...", "#...", ".###"]),
+    ('D', ["###.", "#..#", "#..#", "#..#", "###."]),
+    ('E', ["####", "#...", "###.", "#...", "####"]),
+    ('F', ["####", "#...", "###.", "#...", "#..."]),
+    ('G', [".###", "#...", "#.##", "#..#", ".###"]),
+    ('H', ["#..#", "#..#", "####", "#..#", "#..#"]),
+    ('I', ["###", ".#.", ".#.", ".#.", "###"]),
+    ('J', ["..##", "...#", "...#", "#..#", ".##."]),
+    ('K', ["#..#", "#.#.", "##..", "#.#.", "#..#"]),
+    ('L', ["#...", "#...", "#...", "#...", "####"]),
+    ('M', ["#...#", "##.##", "#.#.#", "#...#", "#...#"]),
+    ('N', ["#...#", "##..#", "#.#.#", "#..##", "#...#"]),
+    ('O', [".##.", "#..#", "#..#", "#..#", ".##."]),
+    ('P', ["###.", "#..#", "###.", "#...", "#..."]),
+    ('Q', [".##.", "#..#", "#..#", "#.##", ".###"]),
+    ('R', ["###.", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', [".###", "#...", ".##.", "...#", "###."]),
+    ('T', ["#####", "..#..", "..#..", "..#..", "..#.."]),
+    ('U', ["#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('V', ["#...#", "#...#", "#...#", ".#.#.", "..#.."]),
+    ('W', ["#...#", "#...#", "#.#.#", "##.##", "#...#"]),
+    ('X', ["#...#", ".#.#.", "..#..", ".#.#.", "#...#"]),
+    ('Y', ["#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', ["####", "...#", ".##.", "#...", "####"]),
+    ('0', [".##.", "#.##", "##.#", "#..#", ".##."]),
+    ('1', [".#.", "##.", ".#.", ".#.", "###"]),
+    ('2', ["###.", "...#", ".##.", "#...", "####"]),
+    ('3', ["###.", "...#", ".##.", "...#", "###."]),
+    ('4', ["#..#", "#..#", "####", "...#", "...#"]),
+    ('5', ["####", "#...", "###.", "...#", "###."]),
+    ('6', [".##.", "#...", "###.", "#..#", ".##."]),
+    ('7', ["####", "...#", "..#.", ".#..", ".#.."]),
+    ('8', [".##.", "#..#", ".##.", "#..#", ".##."]),
+    ('9', [".##.", "#..#", ".###", "...#", ".##."]),
+    (' ', ["..", "..", "..", "..", ".."]),
+    ('!', ["#", "#", "#", ".", "#"]),
+    ('?', ["###.", "...#", ".##.", "....", ".#.."]),
+    ('.', [".", ".", ".", ".", "#"]),
+    (',', [".", ".", ".", "#", "#"]),
+    ('\'', ["#", "#", ".", ".", "."]),
+    ('’', ["#", "#", ".", ".", "."]),
+    ('-', ["...", "...", "###", "...", "..."]),
+    (':', [".", "#", ".", "#", "."]),
+    (';', [".", "#", ".", "#", "#"]),
+];
+
+/// `text` in block letters, one string per row, or `None` when the font has
+/// no glyph for one of its characters. Rows are trimmed at the end, so
+/// measure the widest.
+///
+/// ```
+/// use daily_stoic_rs::banner::big;
+///
+/// assert_eq!(big("Hi").unwrap(), ["█  █ ███", "█  █  █", "████  █", "█  █  █", "█  █ ███"]);
+/// assert_eq!(big("Ω"), None);
+/// ```
+pub fn big(text: &str) -> Option<Vec<String>> {
+    let glyphs = text
+        .chars()
+        .map(|c| FONT.iter().find(|(glyph, _)| *glyph == c.to_ascii_uppercase()).map(|(_, rows)| rows))
+        .collect::<Option<Vec<_>>>()?;
+
+    Some(
+        (0..HEIGHT)
+            .map(|row| {
+                glyphs
+                    .iter()
+                    .map(|rows| rows[row].replace('#', "█").replace('.', " "))
+                    .collect::<Vec<_>>()
+                    .join(" ")
+                    .trim_end()
+                    .to_string()
+            })
+            .collect(),
+    )
+}
+
+/// `text` in a rounded box no wider than `width`, wrapped inside it.
+pub fn boxed(text: &str, width: usize) -> String {
+    let inner = width.saturating_sub(4).max(1);
+    let lines: Vec<String> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .flat_map(|line| textwrap::wrap(line, inner).into_iter().map(|l| l.into_owned()).collect::<Vec<_>>())
+        .collect();
+    let inner = lines.iter().map(|line| line.width()).max().unwrap_or(0);
+
+    let mut out = vec![format!("╭{}╮", "─".repeat(inner + 2))];
+    out.extend(lines.iter().map(|line| format!("│ {}{} │", line, " ".repeat(inner - line.width()))));
+    out.push(format!("╰{}╯", "─".repeat(inner + 2)));
+    out.join("\n")
+}
+
+fn columns(lines: &[String]) -> usize {
+    lines.iter().map(|line| line.width()).max().unwrap_or(0)
+}
+
+// the title's words packed into as few rows of block letters as fit `width`,
+// or the plain title when a row can't be drawn
+fn title(text: &str, width: usize) -> Option<String> {
+    let mut rows: Vec<String> = Vec::new();
+    for word in text.split_whitespace() {
+        let joined = match rows.last() {
+            Some(row) => format!("{} {}", row, word),
+            None => word.to_string(),
+        };
+        if rows.is_empty() || columns(&big(&joined)?) > width {
+            rows.push(word.to_string());
+        } else if let Some(row) = rows.last_mut() {
+            *row = joined;
+        }
+    }
+
+    let mut out = Vec::new();
+    for row in rows {
+        let lines = big(&row)?;
+        if columns(&lines) > width {
+            return None;
+        }
+        out.push(lines.join("\n"));
+    }
+    Some(out.join("\n\n"))
+}
+
+/// The date, the title in block letters, the quote boxed and the attribution
+/// under it, all within `width` columns and styled with `theme`.
+pub fn render(daily: &Daily, width: usize, theme: &Theme) -> String {
+    let title = title(&daily.title, width).unwrap_or_else(|| daily.title.clone());
+    let quote = boxed(&daily.quote, width);
+    let box_width = quote.lines().next().map(|line| line.width()).unwrap_or(0);
+
+    let quoter = daily.quoter.trim();
+    let indent = box_width.saturating_sub(quoter.width());
+
+    format!(
+        "{}\n\n{}\n\n{}\n{}{}",
+        theme.label.paint(&daily.date),
+        theme.title.paint(&title),
+        theme.quote.paint(&quote),
+        " ".repeat(indent),
+        theme.quoter.paint(quoter)
+    )
+}
@@ -0,0 +1,101 @@
+//! Daily readers laid out like The Daily Stoic, with a dated header, a title, a
+//! quote ending in an attribution and an explanation for every day, so one
+//! parser reads them all. Picked with `book` or `--book`.
+
+use std::fmt;
+
+/// The book read when none is picked.
+pub const DEFAULT: &str = "daily-stoic";
+
+/// How to read a built-in book. There's no public copy of any of them, so the
+/// source always comes from the config.
+pub struct Book {
+    pub name: &'static str,
+    pub title: &'static str,
+    /// Lines where an entry's text ends
+    pub footer_markers: &'static [&'static str],
+    /// The header after December 31's entry, empty when it runs to the end of the text
+    pub end_marker: &'static str,
+    /// The topic of each month from January on, empty for books without them
+    pub months: &'static [MonthTheme],
+}
+
+/// What a month of entries is about, within one of the book's disciplines.
+pub struct MonthTheme {
+    pub month: &'static str,
+    pub topic: &'static str,
+    pub discipline: &'static str,
+}
+
+impl fmt::Display for MonthTheme {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} — {} (Discipline of {})", self.month, self.topic, self.discipline)
+    }
+}
+
+impl Book {
+    /// The theme of the month `date` (e.g. "March 5") falls in.
+    ///
+    /// ```
+    /// use daily_stoic_rs::books;
+    ///
+    /// let book = books::built_in(books::DEFAULT).unwrap();
+    /// let theme = book.month_theme("March 5").unwrap();
+    /// assert_eq!(theme.to_string(), "March — Awareness (Discipline of Perception)");
+    /// assert!(books::built_in("daily-dad").unwrap().month_theme("March 5").is_none());
+    /// ```
+    pub fn month_theme(&self, date: &str) -> Option<&'static MonthTheme> {
+        let month = date.split_whitespace().next()?;
+        self.months.iter().find(|theme| theme.month.eq_ignore_ascii_case(month))
+    }
+}
+
+// the year moves through the disciplines of perception, action and will, four months each
+const DAILY_STOIC_MONTHS: [MonthTheme; 12] = [
+    MonthTheme { month: "January", topic: "Clarity", discipline: "Perception" },
+    MonthTheme { month: "February", topic: "Passions and Emotions", discipline: "Perception" },
+    MonthTheme { month: "March", topic: "Awareness", discipline: "Perception" },
+    MonthTheme { month: "April", topic: "Unbiased Thought", discipline: "Perception" },
+    MonthTheme { month: "May", topic: "Right Action", discipline: "Action" },
+    MonthTheme { month: "June", topic: "Problem Solving", discipline: "Action" },
+    MonthTheme { month: "July", topic: "Duty", discipline: "Action" },
+    MonthTheme { month: "August", topic: "Pragmatism", discipline: "Action" },
+    MonthTheme { month: "September", topic: "Fortitude and Resilience", discipline: "Will" },
+    MonthTheme { month: "October", topic: "Virtue and Kindness", discipline: "Will" },
+    MonthTheme { month: "November", topic: "Acceptance (Amor Fati)", discipline: "Will" },
+    MonthTheme { month: "December", topic: "Meditation on Mortality", discipline: "Will" },
+];
+
+pub const BUILT_IN: [Book; 3] = [
+    Book {
+        name: DEFAULT,
+        title: "The Daily Stoic",
+        footer_markers: &["SIGN UP", "DAILY STOIC"],
+        end_marker: "STAYING STOIC",
+        months: &DAILY_STOIC_MONTHS,
+    },
+    Book {
+        name: "daily-dad",
+        title: "The Daily Dad",
+        footer_markers: &["SIGN UP", "DAILY DAD"],
+        end_marker: "",
+        months: &[],
+    },
+    Book {
+        name: "daily-laws",
+        title: "The Daily Laws",
+        footer_markers: &["SIGN UP", "DAILY LAWS"],
+        end_marker: "",
+        months: &[],
+    },
+];
+
+/// ```
+/// use daily_stoic_rs::books;
+///
+/// assert_eq!(books::built_in("daily-dad").map(|book| book.title), Some("The Daily Dad"));
+/// assert!(books::built_in("daily-cat").is_none());
+/// ```
+pub fn built_in(name: &str) -> Option<&'static Book> {
+    BUILT_IN.iter().find(|book| book.name == name)
+}
@@ -0,0 +1,223 @@
+use crate::Daily;
+use crate::citation::Citation;
+use crate::error::{DailyStoicError::StorageError, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+// how long a fetched body is used before asking the server whether it changed
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+// the cache's subdirectories and what they hold
+const SECTIONS: [(&str, &str); 3] = [("bodies", "Pages"), ("entries", "Entries"), ("llm", "LLM replies")];
+
+/// On-disk cache of fetched page bodies and cleaned up entries.
+///
+/// Layout:
+/// - `bodies/<url>.txt` holds the page body for a source url
+/// - `bodies/<url>.json` holds its [`BodyMeta`]
+/// - `entries/<Month>-<day>-<variant>.json` holds the cleaned up `Daily` for a
+///   date, per `variant` of provider, model and cleanup prompt
+/// - `llm/<sha256>.txt` holds an LLM reply, keyed by `llm_key`
+#[derive(Clone)]
+pub struct Cache {
+    dir: PathBuf,
+    max_age: Duration
+}
+
+/// The validators a cached body was served with and when it was last checked,
+/// for a conditional request once it's older than the cache's max age.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BodyMeta {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+    /// Unix time of the last fetch or revalidation.
+    pub checked: i64
+}
+
+/// How much one part of the cache holds, see [`Cache::stats`].
+#[derive(Clone, Debug)]
+pub struct SectionStats {
+    /// What the files are: pages, entries or LLM replies
+    pub name: &'static str,
+    pub files: usize,
+    pub bytes: u64,
+    /// When the least recently written file was written
+    pub oldest: Option<SystemTime>
+}
+
+impl Cache {
+    pub fn new(dir: &Path) -> Self {
+        Cache { dir: dir.to_path_buf(), max_age: DEFAULT_MAX_AGE }
+    }
+
+    /// Revalidates cached bodies once they are older than `max_age` instead of
+    /// after a day. Zero revalidates on every fetch.
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// `$XDG_CACHE_HOME/daily-stoic` (or the platform equivalent).
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("daily-stoic"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    fn body_path(&self, url: &str) -> PathBuf {
+        let name: String = url
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+            .collect();
+        self.dir.join("bodies").join(format!("{}.txt", name))
+    }
+
+    fn body_meta_path(&self, url: &str) -> PathBuf {
+        self.body_path(url).with_extension("json")
+    }
+
+    fn entry_path(&self, date: &str, variant: &str) -> PathBuf {
+        self.dir.join("entries").join(format!("{}-{}.json", date.replace(' ', "-"), variant))
+    }
+
+    fn llm_path(&self, key: &str) -> PathBuf {
+        self.dir.join("llm").join(format!("{}.txt", key))
+    }
+
+    pub fn load_body(&self, url: &str) -> Option<String> {
+        fs::read_to_string(self.body_path(url)).ok()
+    }
+
+    pub fn store_body(&self, url: &str, body: &str) -> Result<()> {
+        write(&self.body_path(url), body)
+    }
+
+    /// Bodies cached before validators were kept have none, and count as stale.
+    pub fn load_body_meta(&self, url: &str) -> Option<BodyMeta> {
+        serde_json::from_str(&fs::read_to_string(self.body_meta_path(url)).ok()?).ok()
+    }
+
+    pub fn store_body_meta(&self, url: &str, meta: &BodyMeta) -> Result<()> {
+        let json = serde_json::to_string_pretty(meta)
+            .map_err(|e| StorageError(format!("Failed to serialize body metadata for the cache: {}", e)))?;
+        write(&self.body_meta_path(url), &json)
+    }
+
+    /// Whether a body checked with `meta` can be used without asking the server.
+    pub fn is_fresh(&self, meta: &BodyMeta) -> bool {
+        let age = Utc::now().timestamp().saturating_sub(meta.checked);
+        age >= 0 && (age as u64) < self.max_age.as_secs()
+    }
+
+    pub fn load_daily(&self, date: &str, variant: &str) -> Option<Daily> {
+        let json = fs::read_to_string(self.entry_path(date, variant)).ok()?;
+        let mut daily: Daily = serde_json::from_str(&json).ok()?;
+        // entries cached before citations were parsed
+        if daily.citation.author.is_empty() {
+            daily.citation = Citation::parse(&daily.quoter);
+        }
+        Some(daily)
+    }
+
+    pub fn store_daily(&self, daily: &Daily, variant: &str) -> Result<()> {
+        let json = serde_json::to_string_pretty(daily)
+            .map_err(|e| StorageError(format!("Failed to serialize entry for the cache: {}", e)))?;
+        write(&self.entry_path(&daily.date, variant), &json)
+    }
+
+    pub fn load_llm(&self, key: &str) -> Option<String> {
+        fs::read_to_string(self.llm_path(key)).ok()
+    }
+
+    pub fn store_llm(&self, key: &str, text: &str) -> Result<()> {
+        write(&self.llm_path(key), text)
+    }
+
+    /// The files and bytes of cached pages, entries and LLM replies.
+    pub fn stats(&self) -> Result<Vec<SectionStats>> {
+        SECTIONS
+            .iter()
+            .map(|(dir, name)| {
+                let mut stats = SectionStats { name, files: 0, bytes: 0, oldest: None };
+                for (_, metadata) in self.files(dir)? {
+                    stats.files += 1;
+                    stats.bytes += metadata.len();
+                    if let Ok(modified) = metadata.modified() {
+                        stats.oldest = Some(stats.oldest.map_or(modified, |oldest| oldest.min(modified)));
+                    }
+                }
+                Ok(stats)
+            })
+            .collect()
+    }
+
+    /// Deletes cached files last written more than `older_than` ago, or all
+    /// of them without it, returning how many files and bytes went.
+    pub fn clean(&self, older_than: Option<Duration>) -> Result<(usize, u64)> {
+        let cutoff = older_than.and_then(|age| SystemTime::now().checked_sub(age));
+        let mut removed = (0, 0);
+        for (dir, _) in SECTIONS {
+            for (path, metadata) in self.files(dir)? {
+                let old = match (cutoff, metadata.modified()) {
+                    (Some(cutoff), Ok(modified)) => modified < cutoff,
+                    (Some(_), Err(_)) => false,
+                    (None, _) => true,
+                };
+                if !old {
+                    continue;
+                }
+                fs::remove_file(&path)
+                    .map_err(|e| StorageError(format!("Failed to remove cache file {}: {}", path.display(), e)))?;
+                removed.0 += 1;
+                removed.1 += metadata.len();
+            }
+        }
+        Ok(removed)
+    }
+
+    // the files directly in the `dir` section, none when it doesn't exist yet
+    fn files(&self, dir: &str) -> Result<Vec<(PathBuf, fs::Metadata)>> {
+        let dir = self.dir.join(dir);
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StorageError(format!("Failed to read cache dir {}: {}", dir.display(), e))),
+        };
+        Ok(entries
+            .filter_map(|entry| entry.ok())
+            .filter_map(|entry| Some((entry.path(), entry.metadata().ok()?)))
+            .filter(|(_, metadata)| metadata.is_file())
+            .collect())
+    }
+}
+
+/// Hash of everything that affects an LLM reply: the provider and model, the kind
+/// of request (including its prompt version) and the input text.
+pub fn llm_key(provider: &str, kind: &str, input: &str) -> String {
+    let mut hasher = Sha256::new();
+    for part in [provider, kind, input] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+
+    hasher.finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn write(path: &Path, contents: &str) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| StorageError(format!("Failed to create cache dir {}: {}", parent.display(), e)))?;
+    }
+
+    fs::write(path, contents)
+        .map_err(|e| StorageError(format!("Failed to write cache file {}: {}", path.display(), e)))
+}
@@ -0,0 +1,233 @@
+//! Rendering the quote and attribution onto an image, for wallpapers and
+//! shareable quote cards.
+
+use crate::config::ImageConfig;
+use crate::error::{DailyStoicError::{ConfigError, StorageError}, Result};
+use crate::Daily;
+use ab_glyph::{Font, FontVec, PxScale};
+use fontdb::{Database, Family, Query, Style as FontStyle, Weight};
+use image::imageops::{self, FilterType};
+use image::{Rgba, RgbaImage};
+use imageproc::drawing::{draw_text_mut, text_size};
+use std::path::PathBuf;
+
+// tried in order after the configured font, so there's a decent serif on most systems
+const SERIF_FAMILIES: [&str; 5] = ["Georgia", "DejaVu Serif", "Liberation Serif", "Noto Serif", "Times New Roman"];
+const DEFAULT_BACKGROUND: &str = "#1d2433,#0b0d12";
+const DEFAULT_COLOR: &str = "#f2efe6";
+
+/// Named looks for quote cards: background and text color.
+pub const THEMES: [(&str, &str, &str); 5] = [
+    ("dark", DEFAULT_BACKGROUND, DEFAULT_COLOR),
+    ("light", "#faf8f3", "#22252b"),
+    ("sepia", "#f1e7d0,#e3d3b0", "#3b2f22"),
+    ("marble", "#e9e9e6,#c9cbc8", "#1f2226"),
+    ("night", "#0f2027,#2c5364", "#e8f1f2"),
+];
+
+/// What goes behind the text.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Background {
+    Solid(Rgba<u8>),
+    /// Top to bottom.
+    Gradient(Rgba<u8>, Rgba<u8>),
+    /// Scaled and cropped to fill the image, and darkened so the text stays readable.
+    Image(PathBuf)
+}
+
+impl Background {
+    /// "#rrggbb", two colors "#rrggbb,#rrggbb" for a gradient, or an image path.
+    pub fn parse(s: &str) -> Result<Self> {
+        let colors: Vec<&str> = s.split(',').map(str::trim).collect();
+        match colors.as_slice() {
+            [color] if color.starts_with('#') => Ok(Background::Solid(parse_color(color)?)),
+            [top, bottom] if top.starts_with('#') => Ok(Background::Gradient(parse_color(top)?, parse_color(bottom)?)),
+            _ => Ok(Background::Image(PathBuf::from(s))),
+        }
+    }
+}
+
+/// "#rrggbb" or "#rgb".
+pub fn parse_color(s: &str) -> Result<Rgba<u8>> {
+    let hex = s.trim().trim_start_matches('#');
+    let hex = match hex.len() {
+        3 => hex.chars().flat_map(|c| [c, c]).collect(),
+        6 => hex.to_string(),
+        _ => return Err(ConfigError(format!("Invalid color \"{}\" (expected #rrggbb)", s))),
+    };
+    let channel = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16)
+        .map_err(|_| ConfigError(format!("Invalid color \"{}\" (expected #rrggbb)", s)));
+    Ok(Rgba([channel(0)?, channel(2)?, channel(4)?, 255]))
+}
+
+/// The fonts and colors to draw with.
+pub struct Style {
+    pub background: Background,
+    pub color: Rgba<u8>,
+    quote_font: FontVec,
+    author_font: FontVec
+}
+
+impl Style {
+    pub fn from_config(config: &ImageConfig) -> Result<Self> {
+        Ok(Style {
+            background: Background::parse(config.background.as_deref().unwrap_or(DEFAULT_BACKGROUND))?,
+            color: parse_color(config.color.as_deref().unwrap_or(DEFAULT_COLOR))?,
+            quote_font: load_font(config.font.as_deref(), FontStyle::Italic)?,
+            author_font: load_font(config.font.as_deref(), FontStyle::Normal)?,
+        })
+    }
+
+    /// The configured font with the colors of one of the `THEMES`.
+    pub fn with_theme(config: &ImageConfig, theme: &str) -> Result<Self> {
+        let (_, background, color) = THEMES
+            .iter()
+            .find(|(name, _, _)| name.eq_ignore_ascii_case(theme))
+            .ok_or_else(|| {
+                let names: Vec<&str> = THEMES.iter().map(|(name, _, _)| *name).collect();
+                ConfigError(format!("Unknown theme \"{}\" (expected {})", theme, names.join(", ")))
+            })?;
+
+        let config = ImageConfig {
+            background: Some(background.to_string()),
+            color: Some(color.to_string()),
+            ..config.clone()
+        };
+        Style::from_config(&config)
+    }
+}
+
+/// A font file, or an installed family (`None` for the first available serif),
+/// in the given style where the family has it.
+pub fn load_font(font: Option<&str>, style: FontStyle) -> Result<FontVec> {
+    let (data, index) = font_data(font, style, Weight::NORMAL)?;
+    FontVec::try_from_vec_and_index(data, index)
+        .map_err(|e| ConfigError(format!("Invalid font {}: {}", font.unwrap_or("(system serif)"), e)))
+}
+
+/// The raw bytes and face index of the font [`load_font`] would pick, for
+/// embedding it elsewhere.
+pub fn font_data(font: Option<&str>, style: FontStyle, weight: Weight) -> Result<(Vec<u8>, u32)> {
+    if let Some(path) = font.map(PathBuf::from).filter(|p| p.is_file()) {
+        let data = std::fs::read(&path).map_err(|e| StorageError(format!("Failed to read {}: {}", path.display(), e)))?;
+        return Ok((data, 0));
+    }
+
+    let mut db = Database::new();
+    db.load_system_fonts();
+
+    let mut families: Vec<Family> = font.into_iter().map(Family::Name).collect();
+    families.extend(SERIF_FAMILIES.iter().map(|name| Family::Name(name)));
+    families.push(Family::Serif);
+
+    let id = db
+        .query(&Query { families: &families, style, weight, ..Query::default() })
+        .or_else(|| db.faces().next().map(|face| face.id))
+        .ok_or_else(|| ConfigError("No fonts found, set image.font to a .ttf or .otf file".to_string()))?;
+
+    db.with_face_data(id, |data, index| (data.to_vec(), index))
+        .ok_or_else(|| ConfigError("Failed to load the system font, set image.font to a .ttf or .otf file".to_string()))
+}
+
+fn fill_background(image: &mut RgbaImage, background: &Background) -> Result<()> {
+    let (width, height) = image.dimensions();
+    match background {
+        Background::Solid(color) => image.pixels_mut().for_each(|p| *p = *color),
+        Background::Gradient(top, bottom) => {
+            for (_, y, pixel) in image.enumerate_pixels_mut() {
+                let t = y as f32 / height.max(2).saturating_sub(1) as f32;
+                let mix = |a: u8, b: u8| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+                *pixel = Rgba([mix(top[0], bottom[0]), mix(top[1], bottom[1]), mix(top[2], bottom[2]), 255]);
+            }
+        }
+        Background::Image(path) => {
+            let source = image::open(path)
+                .map_err(|e| StorageError(format!("Failed to read background {}: {}", path.display(), e)))?;
+            let filled = source.resize_to_fill(width, height, FilterType::Lanczos3).to_rgba8();
+            // darken so light text reads on any photo
+            let dimmed = imageops::colorops::brighten(&filled, -40);
+            imageops::replace(image, &dimmed, 0, 0);
+        }
+    }
+    Ok(())
+}
+
+// greedy word wrap to `max_width` pixels
+fn wrap(text: &str, font: &impl Font, scale: PxScale, max_width: u32) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+            if !line.is_empty() && text_size(scale, font, &candidate).0 > max_width {
+                lines.push(std::mem::replace(&mut line, word.to_string()));
+            } else {
+                line = candidate;
+            }
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// `daily`'s quote, centered and wrapped, with the attribution under it.
+pub fn render(daily: &Daily, width: u32, height: u32, style: &Style) -> Result<RgbaImage> {
+    let mut image = RgbaImage::new(width, height);
+    fill_background(&mut image, &style.background)?;
+    draw_quote(&mut image, daily, style, height * 2 / 3);
+    Ok(image)
+}
+
+/// A square card for sharing: the quote as in `render`, with the date and
+/// title above it and the book's name at the bottom.
+pub fn render_card(daily: &Daily, size: u32, style: &Style) -> Result<RgbaImage> {
+    let mut image = RgbaImage::new(size, size);
+    fill_background(&mut image, &style.background)?;
+    // leave room for the header and footer
+    draw_quote(&mut image, daily, style, size / 2);
+    let scale = PxScale::from(size as f32 / 36.0);
+
+    let centered = |image: &mut RgbaImage, font: &FontVec, text: &str, y: u32| {
+        let x = size.saturating_sub(text_size(scale, font, text).0) / 2;
+        draw_text_mut(image, style.color, x as i32, y as i32, scale, font, text);
+    };
+    centered(&mut image, &style.author_font, &format!("{} · {}", daily.date, daily.title).to_uppercase(), size / 14);
+    centered(&mut image, &style.quote_font, "The Daily Stoic", size - size / 14 - scale.y as u32);
+
+    Ok(image)
+}
+
+// the quote shrinks until its lines fit in `max_height`
+fn draw_quote(image: &mut RgbaImage, daily: &Daily, style: &Style, max_height: u32) {
+    let (width, height) = image.dimensions();
+
+    let max_width = width * 7 / 10;
+    let mut size = (width.min(height) as f32 / 14.0).max(12.0);
+    let (lines, line_height) = loop {
+        let scale = PxScale::from(size);
+        let lines = wrap(&format!("“{}”", daily.quote.trim()), &style.quote_font, scale, max_width);
+        let line_height = (size * 1.35) as u32;
+        if lines.len() as u32 * line_height <= max_height || size <= 12.0 {
+            break (lines, line_height);
+        }
+        size *= 0.9;
+    };
+
+    let author = daily.quoter.trim().to_string();
+    let author_size = PxScale::from(size * 0.6);
+    let gap = line_height;
+    let total = lines.len() as u32 * line_height + gap + author_size.y as u32;
+    let mut y = height.saturating_sub(total) / 2;
+
+    for line in &lines {
+        let line_width = text_size(PxScale::from(size), &style.quote_font, line).0;
+        let x = width.saturating_sub(line_width) / 2;
+        draw_text_mut(image, style.color, x as i32, y as i32, PxScale::from(size), &style.quote_font, line);
+        y += line_height;
+    }
+
+    y += gap;
+    let author_width = text_size(author_size, &style.author_font, &author).0;
+    let x = width.saturating_sub(author_width) / 2;
+    draw_text_mut(image, style.color, x as i32, y as i32, author_size, &style.author_font, &author);
+}
@@ -0,0 +1,144 @@
+//! Structured form of the attribution line under each quote.
+
+use serde::{Deserialize, Serialize};
+
+// lowercase inside titles, e.g. "On the Shortness of Life"
+const SMALL_WORDS: [&str; 9] = ["a", "an", "and", "in", "of", "on", "the", "to", "from"];
+
+/// Who said a quote and where, parsed from a line like
+/// "—MARCUS AURELIUS, MEDITATIONS, 5.1".
+///
+/// ```
+/// use daily_stoic_rs::citation::Citation;
+///
+/// let c = Citation::parse("—MARCUS AURELIUS, MEDITATIONS, 5.1");
+/// assert_eq!(c.author, "Marcus Aurelius");
+/// assert_eq!(c.work.as_deref(), Some("Meditations"));
+/// assert_eq!(c.section.as_deref(), Some("5.1"));
+///
+/// // sections can be ranges or follow the work without a comma
+/// let c = Citation::parse("—EPICTETUS, DISCOURSES, 1.1.21–22");
+/// assert_eq!(c.section.as_deref(), Some("1.1.21–22"));
+/// let c = Citation::parse("—EPICTETUS, ENCHIRIDION 5");
+/// assert_eq!(c.work.as_deref(), Some("Enchiridion"));
+/// assert_eq!(c.section.as_deref(), Some("5"));
+///
+/// // works with commas or no section at all
+/// let c = Citation::parse("—SENECA, ON THE SHORTNESS OF LIFE, 1.3–4");
+/// assert_eq!(c.work.as_deref(), Some("On the Shortness of Life"));
+/// let c = Citation::parse("—CATO, QUOTED IN PLUTARCH'S CATO THE YOUNGER");
+/// assert_eq!(c.work.as_deref(), Some("Quoted in Plutarch's Cato the Younger"));
+/// assert_eq!(c.section, None);
+///
+/// // a bare name
+/// let c = Citation::parse("—ZENO");
+/// assert_eq!((c.author.as_str(), c.work), ("Zeno", None));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Citation {
+    pub author: String,
+    pub work: Option<String>,
+    /// Book, letter or section number(s), e.g. "88.2" or "6.25.4–5".
+    pub section: Option<String>
+}
+
+impl Citation {
+    pub fn parse(quoter: &str) -> Self {
+        let line = quoter.trim().trim_start_matches(['—', '―', '–', '-']).trim();
+        let mut parts: Vec<&str> = line.split(',').map(str::trim).filter(|p| !p.is_empty()).collect();
+        if parts.is_empty() {
+            return Citation::default();
+        }
+
+        let author = title_case(parts.remove(0));
+
+        let mut section = None;
+        if parts.last().is_some_and(|p| is_section(p)) {
+            section = parts.pop().map(str::to_string);
+        }
+
+        let mut work = parts.join(", ");
+        // "ENCHIRIDION 5": the section trails the work without a comma
+        if section.is_none()
+            && let Some((title, last)) = work.rsplit_once(' ')
+            && is_section(last)
+        {
+            section = Some(last.to_string());
+            work = title.to_string();
+        }
+
+        Citation {
+            author,
+            work: if work.is_empty() { None } else { Some(title_case(&work)) },
+            section
+        }
+    }
+}
+
+/// Where the cited passage can be read in full: the letter on Wikisource for
+/// Seneca's letters, the passage on Perseus for Marcus Aurelius and Epictetus,
+/// and a Wikisource search for the author and work otherwise.
+pub fn source_url(citation: &Citation) -> Option<String> {
+    if citation.author.is_empty() {
+        return None;
+    }
+
+    let work = citation.work.as_deref().unwrap_or("");
+    let section = citation.section.as_deref().map(section_start);
+
+    // Perseus CTS ids for the Greek texts
+    let perseus = match (citation.author.as_str(), work) {
+        ("Marcus Aurelius", "Meditations") => Some("tlg0562.tlg001"),
+        ("Epictetus", "Discourses") => Some("tlg0557.tlg001"),
+        ("Epictetus", "Enchiridion") => Some("tlg0557.tlg002"),
+        _ => None,
+    };
+
+    match (perseus, section) {
+        (Some(id), Some(section)) => {
+            return Some(format!(
+                "http://www.perseus.tufts.edu/hopper/text?doc=urn:cts:greekLit:{}.perseus-grc1:{}",
+                id, section
+            ));
+        }
+        (None, Some(section)) if citation.author == "Seneca" && work == "Moral Letters" => {
+            let letter = section.split('.').next().unwrap_or(section);
+            return Some(format!("https://en.wikisource.org/wiki/Moral_letters_to_Lucilius/Letter_{}", letter));
+        }
+        _ => {}
+    }
+
+    let query = format!("{} {}", citation.author, work);
+    reqwest::Url::parse_with_params("https://en.wikisource.org/w/index.php", &[("search", query.trim())])
+        .ok()
+        .map(String::from)
+}
+
+// the first passage of a range, "6.25.4–5" -> "6.25.4"
+fn section_start(section: &str) -> &str {
+    section.split(['–', '-', ' ']).next().unwrap_or(section)
+}
+
+// numbers, possibly dotted or a range: 5, 88.2, 6.25.4–5, 1.3-4
+fn is_section(s: &str) -> bool {
+    s.starts_with(|c: char| c.is_ascii_digit())
+        && s.chars().all(|c| c.is_ascii_digit() || matches!(c, '.' | '–' | '-' | ' '))
+}
+
+fn title_case(s: &str) -> String {
+    s.split_whitespace()
+        .enumerate()
+        .map(|(i, word)| {
+            let lower = word.to_lowercase();
+            if i > 0 && SMALL_WORDS.contains(&lower.as_str()) {
+                return lower;
+            }
+            let mut chars = lower.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
@@ -0,0 +1,248 @@
+//! Rule-based cleanup of extracted text, used when no LLM is available.
+//!
+//! Only fixes artifacts that can be detected without a dictionary: words split
+//! by a hyphen at a line break, line breaks in the middle of a sentence, runs of
+//! spaces, spaces before punctuation, words spelled out letter by letter or cut
+//! before a contraction and trailing ALL-CAPS promo lines.
+
+use crate::Daily;
+
+pub fn clean_daily(mut daily: Daily) -> Daily {
+    daily.quote = clean_text(&daily.quote);
+    daily.explanation = clean_text(&daily.explanation);
+    daily
+}
+
+pub fn clean_text(text: &str) -> String {
+    let text = join_broken_lines(text);
+    let text = dehyphenate(&text);
+    let text = collapse_spaces(&text);
+    let text = join_split_words(&text);
+    strip_trailing_caps(&text)
+}
+
+// a line break not preceded by sentence ending punctuation continues the sentence,
+// blank lines are kept as paragraph breaks
+fn join_broken_lines(text: &str) -> String {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines().map(str::trim) {
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        if !current.is_empty() {
+            let ends_sentence = current.ends_with(['.', '!', '?', '"', '”', ':']);
+            current.push(if ends_sentence { '\n' } else { ' ' });
+        }
+        current.push_str(line);
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+
+    paragraphs.join("\n\n")
+}
+
+// words that usually start a hyphenated compound rather than being split off a longer one
+const COMPOUND_HEADS: [&str; 14] = [
+    "self", "well", "half", "long", "short", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    "hundred",
+];
+
+// endings that only make sense glued onto the fragment before them
+const SUFFIXES: [&str; 10] = ["ish", "less", "ness", "ly", "ing", "er", "est", "ed", "s", "y"];
+
+// "educa- tion" -> "education" and "self- control" -> "self-control", only when
+// the fragment after the hyphen is lowercase
+fn dehyphenate(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut out = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        if chars[i] == '-' && i > 0 && chars[i - 1].is_alphabetic() {
+            let mut j = i + 1;
+            while j < chars.len() && chars[j] == ' ' {
+                j += 1;
+            }
+            if j > i + 1 && j < chars.len() && chars[j].is_lowercase() {
+                let start = chars[..i].iter().rposition(|c| !c.is_alphabetic()).map_or(0, |p| p + 1);
+                let head: String = chars[start..i].iter().collect();
+                let tail: String = chars[j..].iter().take_while(|c| c.is_alphabetic()).collect();
+                if is_compound(&head, &tail) {
+                    out.push('-');
+                }
+                i = j;
+                continue;
+            }
+        }
+        out.push(chars[i]);
+        i += 1;
+    }
+
+    out
+}
+
+fn is_compound(head: &str, tail: &str) -> bool {
+    COMPOUND_HEADS.contains(&head.to_lowercase().as_str()) && !SUFFIXES.contains(&tail)
+}
+
+fn collapse_spaces(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if c == ' ' && out.ends_with(' ') {
+            continue;
+        }
+        if matches!(c, ',' | '.' | ';' | ':' | '!' | '?') && out.ends_with(' ') {
+            out.pop();
+        }
+        out.push(c);
+    }
+
+    out.lines().map(str::trim).collect::<Vec<_>>().join("\n")
+}
+
+const CONTRACTIONS: [&str; 7] = ["s", "t", "re", "ll", "ve", "d", "m"];
+
+// "c o u r a g e" -> "courage" and "don ’t" -> "don’t"
+fn join_split_words(text: &str) -> String {
+    text.lines().map(join_split_words_in_line).collect::<Vec<_>>().join("\n")
+}
+
+fn join_split_words_in_line(line: &str) -> String {
+    let mut words: Vec<String> = Vec::new();
+    let mut letters = String::new();
+    let mut run = 0;
+
+    for word in line.split(' ') {
+        if is_letter(word) {
+            letters.push_str(word);
+            run += 1;
+            continue;
+        }
+        // a letter with punctuation after it can end a spelled out word, "e."
+        let ends_run = run >= 2 && word.chars().count() == 2 && word.starts_with(char::is_alphabetic) && word.ends_with(|c: char| c.is_ascii_punctuation());
+        if ends_run {
+            letters.push_str(word);
+            run += 1;
+        }
+        flush_letters(&mut words, &mut letters, run);
+        run = 0;
+        if ends_run {
+            continue;
+        }
+
+        match words.last_mut() {
+            Some(last) if is_contraction(word) && last.ends_with(char::is_alphabetic) => last.push_str(word),
+            _ => words.push(word.to_string()),
+        }
+    }
+    flush_letters(&mut words, &mut letters, run);
+
+    words.join(" ")
+}
+
+fn is_letter(word: &str) -> bool {
+    let mut chars = word.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if c.is_alphabetic())
+}
+
+// three or more single letters in a row are one word spelled out, fewer are
+// words like "a" and "I"
+fn flush_letters(words: &mut Vec<String>, letters: &mut String, run: usize) {
+    if run >= 3 {
+        words.push(std::mem::take(letters));
+    } else {
+        words.extend(letters.drain(..).map(String::from));
+    }
+}
+
+fn is_contraction(word: &str) -> bool {
+    let Some(rest) = word.strip_prefix(['’', '\'']) else {
+        return false;
+    };
+    CONTRACTIONS.contains(&rest.trim_end_matches(|c: char| !c.is_alphabetic()))
+}
+
+fn is_caps_word(word: &str) -> bool {
+    let letters: Vec<char> = word.chars().filter(|c| c.is_alphabetic()).collect();
+    letters.len() >= 2 && letters.iter().all(|c| c.is_uppercase())
+}
+
+// drops a run of two or more ALL-CAPS words at the very end of the text
+fn strip_trailing_caps(text: &str) -> String {
+    let words: Vec<&str> = text.split(' ').collect();
+    let caps = words.iter().rev().take_while(|word| is_caps_word(word)).count();
+
+    if caps < 2 || caps == words.len() {
+        return text.to_string();
+    }
+
+    words[..words.len() - caps].join(" ").trim_end().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn joins_lines_broken_mid_sentence() {
+        assert_eq!(join_broken_lines("You have power\nover your mind."), "You have power over your mind.");
+        assert_eq!(join_broken_lines("Not outside events.\nRealize this."), "Not outside events.\nRealize this.");
+        assert_eq!(join_broken_lines("One paragraph.\n\n\nAnother."), "One paragraph.\n\nAnother.");
+    }
+
+    #[test]
+    fn dehyphenates_words_split_at_a_line_break() {
+        assert_eq!(dehyphenate("the educa- tion of a Stoic"), "the education of a Stoic");
+        assert_eq!(dehyphenate("a self- ish act"), "a selfish act");
+        assert_eq!(dehyphenate("Epictetus- Discourses"), "Epictetus- Discourses");
+        assert_eq!(dehyphenate("well-being"), "well-being");
+    }
+
+    #[test]
+    fn keeps_hyphenated_compounds() {
+        assert_eq!(dehyphenate("practice self- control"), "practice self-control");
+        assert_eq!(dehyphenate("Twenty- one days, a long- term view"), "Twenty-one days, a long-term view");
+    }
+
+    #[test]
+    fn collapses_runs_of_spaces_and_spaces_before_punctuation() {
+        assert_eq!(collapse_spaces("Stand  firm ,   then act ."), "Stand firm, then act.");
+        assert_eq!(collapse_spaces(" Stand firm. \n Then act. "), "Stand firm.\nThen act.");
+    }
+
+    #[test]
+    fn joins_words_spelled_out_letter_by_letter() {
+        assert_eq!(join_split_words("Find your c o u r a g e today"), "Find your courage today");
+        assert_eq!(join_split_words("This takes c o u r a g e."), "This takes courage.");
+        assert_eq!(join_split_words("I am a student"), "I am a student");
+    }
+
+    #[test]
+    fn joins_contractions_cut_off_their_word() {
+        assert_eq!(join_split_words("You don ’t control it"), "You don’t control it");
+        assert_eq!(join_split_words("it 's what we 're given"), "it's what we're given");
+        assert_eq!(join_split_words("he said ’tis so"), "he said ’tis so");
+    }
+
+    #[test]
+    fn strips_trailing_caps_runs() {
+        assert_eq!(strip_trailing_caps("Act well. SIGN UP TODAY"), "Act well.");
+        assert_eq!(strip_trailing_caps("Read the STOIC"), "Read the STOIC");
+        assert_eq!(strip_trailing_caps("MEMENTO MORI"), "MEMENTO MORI");
+    }
+
+    #[test]
+    fn cleans_text_with_every_rule() {
+        let text = "You have power over your mind — not out-\nside events . Realize this , and you will find s t r e n g t h. DAILY STOIC PRESS";
+        assert_eq!(clean_text(text), "You have power over your mind — not outside events. Realize this, and you will find strength.");
+    }
+}
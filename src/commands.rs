@@ -0,0 +1,26 @@
+//! Implementations of the larger subcommands, kept out of `main.rs`.
+
+pub mod bot;
+pub mod cache;
+pub mod clipboard;
+pub mod completions;
+pub mod digest;
+pub mod doctor;
+pub mod index;
+pub mod init;
+pub mod journal;
+pub mod man;
+pub mod motd;
+pub mod notify;
+pub mod podcast;
+pub mod qr;
+pub mod schedule;
+pub mod serve;
+pub mod speak;
+pub mod stats;
+pub mod streak;
+pub mod tui;
+pub mod update;
+pub mod usage;
+pub mod validate;
+pub mod wallpaper;
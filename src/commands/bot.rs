@@ -0,0 +1,131 @@
+use chrono::{Local, NaiveDateTime, NaiveTime, TimeDelta};
+use daily_stoic_rs::archive::Archive;
+use daily_stoic_rs::render::escape_html;
+use daily_stoic_rs::send::telegram::{Telegram, Update};
+use daily_stoic_rs::{date, DailyStoicClient};
+use std::error::Error;
+use tracing::{info, warn};
+
+const SERVICE: &str = "telegram";
+// longest wait for updates, so a connection problem shows up in the logs reasonably soon
+const POLL_TIMEOUT: u64 = 50;
+
+const HELP: &str = "The Daily Stoic, every day.\n\n\
+    /today - today's meditation\n\
+    /random - a random one\n\
+    /date March 5 - the one for a given day\n\
+    /start - get today's meditation every day\n\
+    /stop - stop the daily meditation";
+
+/// Long-polls Telegram for commands, and sends today's entry to every
+/// subscribed chat at `time` each day.
+pub async fn telegram(
+    client: &DailyStoicClient,
+    bot: &Telegram,
+    time: NaiveTime,
+    use_llm: bool,
+) -> Result<(), Box<dyn Error>> {
+    let archive = client.archive().ok_or("Could not determine the data dir for subscriptions")?;
+    let mut next_push = next_at(time, Local::now().naive_local());
+    let mut offset = 0;
+
+    println!("Listening for Telegram commands, pushing daily at {} (Ctrl-C to stop)", time.format("%H:%M"));
+
+    loop {
+        let now = Local::now().naive_local();
+        if now >= next_push {
+            push(client, bot, archive, use_llm).await;
+            next_push = next_at(time, now);
+        }
+
+        // wake up in time for the next push
+        let until_push = (next_push - now).num_seconds().max(1) as u64;
+        let updates = match bot.get_updates(offset, until_push.min(POLL_TIMEOUT)).await {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!("Polling Telegram failed: {}", e);
+                tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+                continue;
+            }
+        };
+
+        for update in updates {
+            offset = offset.max(update.id + 1);
+            if let Err(e) = answer(client, bot, archive, &update, use_llm).await {
+                warn!("Failed to answer \"{}\" in chat {}: {}", update.text, update.chat, e);
+                let _ = bot.send_message(update.chat, &format!("Sorry, that didn't work: {}", escape_html(&e.to_string()))).await;
+            }
+        }
+    }
+}
+
+// the first `time` after `now`
+fn next_at(time: NaiveTime, now: NaiveDateTime) -> NaiveDateTime {
+    let today = now.date().and_time(time);
+    if today > now { today } else { today + TimeDelta::days(1) }
+}
+
+async fn push(client: &DailyStoicClient, bot: &Telegram, archive: &Archive, use_llm: bool) {
+    let chats = match archive.subscribers(SERVICE) {
+        Ok(chats) => chats,
+        Err(e) => return warn!("{}", e),
+    };
+    let daily = match client.get_entry(&date::today(), use_llm).await {
+        Ok(daily) => daily,
+        Err(e) => return warn!("Failed to get today's entry for the daily push: {}", e),
+    };
+
+    for chat in chats {
+        let Ok(id) = chat.parse() else { continue };
+        match bot.send_daily(id, &daily).await {
+            Ok(()) => info!("Sent {} to chat {}", daily.date, chat),
+            Err(e) => warn!("Failed to send {} to chat {}: {}", daily.date, chat, e),
+        }
+    }
+}
+
+async fn answer(
+    client: &DailyStoicClient,
+    bot: &Telegram,
+    archive: &Archive,
+    update: &Update,
+    use_llm: bool,
+) -> Result<(), Box<dyn Error>> {
+    let text = update.text.trim();
+    let (command, argument) = text.split_once(' ').unwrap_or((text, ""));
+    // commands in groups are addressed as /today@BotName
+    let command = command.split('@').next().unwrap_or(command);
+    let chat = update.chat.to_string();
+
+    match command {
+        "/today" => bot.send_daily(update.chat, &client.get_entry(&date::today(), use_llm).await?).await?,
+        "/random" => {
+            let date = archive.random_date()?.unwrap_or_else(date::random);
+            bot.send_daily(update.chat, &client.get_entry(&date, use_llm).await?).await?;
+        }
+        "/date" if argument.trim().is_empty() => bot.send_message(update.chat, "Which day? E.g. /date March 5").await?,
+        "/date" => {
+            let date = date::parse_date(argument)?;
+            bot.send_daily(update.chat, &client.get_entry(&date, use_llm).await?).await?;
+        }
+        "/start" | "/subscribe" => {
+            let reply = if archive.add_subscriber(SERVICE, &chat)? {
+                format!("Subscribed, you will get the daily meditation every day.\n\n{}", HELP)
+            } else {
+                "You are already subscribed.".to_string()
+            };
+            bot.send_message(update.chat, &reply).await?;
+        }
+        "/stop" | "/unsubscribe" => {
+            let reply = if archive.remove_subscriber(SERVICE, &chat)? {
+                "Unsubscribed, you won't get the daily meditation anymore."
+            } else {
+                "You weren't subscribed."
+            };
+            bot.send_message(update.chat, reply).await?;
+        }
+        _ => bot.send_message(update.chat, HELP).await?,
+    }
+
+    Ok(())
+}
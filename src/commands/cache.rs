@@ -0,0 +1,47 @@
+use chrono::{DateTime, Local};
+use daily_stoic_rs::cache::Cache;
+use std::error::Error;
+use std::time::Duration;
+
+// bytes in the largest unit that keeps them at 1 or more, e.g. "1.2 MiB"
+fn size(bytes: u64) -> String {
+    let mut value = bytes as f64;
+    for unit in ["B", "KiB", "MiB"] {
+        if value < 1024.0 {
+            return if unit == "B" { format!("{} B", bytes) } else { format!("{:.1} {}", value, unit) };
+        }
+        value /= 1024.0;
+    }
+    format!("{:.1} GiB", value)
+}
+
+/// Prints the files, size and oldest file of the cached pages, entries and
+/// LLM replies.
+pub fn stats(cache: &Cache) -> Result<(), Box<dyn Error>> {
+    let sections = cache.stats()?;
+    println!("{:<12} {:>6} {:>10}  Oldest", "", "Files", "Size");
+    for section in &sections {
+        let oldest = section
+            .oldest
+            .map(|oldest| DateTime::<Local>::from(oldest).format("%Y-%m-%d").to_string())
+            .unwrap_or_else(|| "-".to_string());
+        println!("{:<12} {:>6} {:>10}  {}", section.name, section.files, size(section.bytes), oldest);
+    }
+    let files: usize = sections.iter().map(|section| section.files).sum();
+    let bytes: u64 = sections.iter().map(|section| section.bytes).sum();
+    println!("{:<12} {:>6} {:>10}", "Total", files, size(bytes));
+    println!("\nin {}", cache.dir().display());
+    Ok(())
+}
+
+/// Deletes the cached files last written more than `older_than` ago, or all
+/// of them.
+pub fn clean(cache: &Cache, older_than: Option<Duration>) -> Result<(), Box<dyn Error>> {
+    let (files, bytes) = cache.clean(older_than)?;
+    if files == 0 {
+        println!("Nothing to remove from {}", cache.dir().display());
+    } else {
+        println!("Removed {} files ({}) from {}", files, size(bytes), cache.dir().display());
+    }
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use arboard::Clipboard;
+use std::error::Error;
+
+/// Set on the background copy of daily-stoic that keeps the clipboard's
+/// contents available on Linux.
+pub const SERVE_ENV: &str = "DAILY_STOIC_CLIPBOARD_SERVE";
+
+fn clipboard() -> Result<Clipboard, Box<dyn Error>> {
+    Clipboard::new().map_err(|e| format!("Failed to open the clipboard: {}", e).into())
+}
+
+/// Puts `text` on the system clipboard.
+#[cfg(not(target_os = "linux"))]
+pub fn copy(text: &str) -> Result<(), Box<dyn Error>> {
+    clipboard()?.set_text(text).map_err(|e| format!("Failed to copy to the clipboard: {}", e))?;
+    Ok(())
+}
+
+/// Puts `text` on the system clipboard. X11 and Wayland only hold on to it
+/// while the program that copied it is running, so a detached copy of this
+/// binary serves it until something else is copied.
+#[cfg(target_os = "linux")]
+pub fn copy(text: &str) -> Result<(), Box<dyn Error>> {
+    use std::io::Write;
+    use std::os::unix::process::CommandExt;
+    use std::process::{Command, Stdio};
+
+    if std::env::var_os("DISPLAY").is_none() && std::env::var_os("WAYLAND_DISPLAY").is_none() {
+        return Err("No X11 or Wayland display to copy to".into());
+    }
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the daily-stoic binary: {}", e))?;
+    let mut child = Command::new(exe)
+        .env(SERVE_ENV, "1")
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        // out of the terminal's process group, so it outlives a Ctrl-C
+        .process_group(0)
+        .spawn()
+        .map_err(|e| format!("Failed to start the clipboard helper: {}", e))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes()).map_err(|e| format!("Failed to copy to the clipboard: {}", e))?;
+    }
+    Ok(())
+}
+
+/// The helper's side of [`copy`]: takes the clipboard with the text on stdin
+/// and returns once another program replaces it.
+#[cfg(target_os = "linux")]
+pub fn serve() -> Result<(), Box<dyn Error>> {
+    use arboard::SetExtLinux;
+    use std::io::Read;
+
+    let mut text = String::new();
+    std::io::stdin().read_to_string(&mut text)?;
+    clipboard()?.set().wait().text(text).map_err(|e| format!("Failed to copy to the clipboard: {}", e))?;
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn serve() -> Result<(), Box<dyn Error>> {
+    Ok(())
+}
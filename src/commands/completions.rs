@@ -0,0 +1,68 @@
+use chrono::{Month, NaiveDate};
+use clap_complete::env::Shells;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::Shell;
+use std::error::Error;
+use std::ffi::OsStr;
+
+/// The variable the shell scripts set when they call back for completions.
+pub const COMPLETE_ENV: &str = "COMPLETE";
+
+const KEYWORDS: [&str; 3] = ["today", "tomorrow", "yesterday"];
+
+fn month_names() -> impl Iterator<Item = &'static str> {
+    (1..=12u8).filter_map(|n| Month::try_from(n).ok()).map(|month| month.name())
+}
+
+/// Month names for `--month`.
+pub fn months(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy().to_lowercase();
+    month_names()
+        .filter(|name| name.to_lowercase().starts_with(&current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
+
+/// Relative keywords and month names for a date, then the month's days once
+/// the name is followed by a space.
+pub fn dates(current: &OsStr) -> Vec<CompletionCandidate> {
+    let current = current.to_string_lossy();
+    if let Some((month, _)) = current.split_once(' ')
+        && let Some(number) = month_names().position(|name| name.eq_ignore_ascii_case(month))
+    {
+        let name = month_names().nth(number).unwrap_or_default();
+        // a leap year, so February 29 is there
+        let days = NaiveDate::from_ymd_opt(2000, number as u32 + 1, 1)
+            .and_then(|first| first.checked_add_months(chrono::Months::new(1)))
+            .and_then(|next| next.pred_opt())
+            .map(|last| chrono::Datelike::day(&last))
+            .unwrap_or(31);
+        return (1..=days)
+            .map(|day| format!("{} {}", name, day))
+            .filter(|date| date.to_lowercase().starts_with(&current.to_lowercase()))
+            .map(CompletionCandidate::new)
+            .collect();
+    }
+
+    let lowercase = current.to_lowercase();
+    KEYWORDS
+        .into_iter()
+        .filter(|keyword| keyword.starts_with(&lowercase))
+        .map(CompletionCandidate::new)
+        .chain(months(OsStr::new(current.as_ref())))
+        .collect()
+}
+
+/// The script for `shell` that hooks daily-stoic's completions into it.
+pub fn registration(shell: Shell) -> Result<String, Box<dyn Error>> {
+    let name = shell.to_string();
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(&name)
+        .ok_or_else(|| format!("No completions for {}", name))?;
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the daily-stoic binary: {}", e))?;
+
+    let mut script = Vec::new();
+    completer.write_registration(COMPLETE_ENV, "daily-stoic", "daily-stoic", &exe.to_string_lossy(), &mut script)?;
+    Ok(String::from_utf8_lossy(&script).into_owned())
+}
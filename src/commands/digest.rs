@@ -0,0 +1,56 @@
+//! Several days' entries at a glance, to see what's coming.
+
+use super::motd::shorten;
+use daily_stoic_rs::Daily;
+
+// characters of the quote shown under each title
+const EXCERPT: usize = 72;
+
+/// The subject line of the week's digest, for emailing it.
+pub fn week_subject(dailies: &[Daily]) -> String {
+    match dailies.first() {
+        Some(first) => format!("The Daily Stoic, the week of {}", first.date),
+        None => "The Daily Stoic, the week ahead".to_string(),
+    }
+}
+
+/// The week's entries a couple of lines each: the date and title, then the
+/// start of the quote and who said it, indented under the title.
+pub fn week(dailies: &[Daily]) -> String {
+    let width = dailies.iter().map(|daily| daily.date.chars().count()).max().unwrap_or(0);
+    let mut out = String::new();
+    if let (Some(first), Some(last)) = (dailies.first(), dailies.last()) {
+        out.push_str(&format!("The week ahead, {} to {}\n", first.date, last.date));
+    }
+
+    for daily in dailies {
+        let quote = daily.quote.split_whitespace().collect::<Vec<_>>().join(" ");
+        let mut excerpt = shorten(&quote, EXCERPT);
+        if !daily.citation.author.is_empty() {
+            excerpt = format!("{} — {}", excerpt, daily.citation.author);
+        }
+        out.push_str(&format!("\n{:<width$}  {}\n{:width$}  {}\n", daily.date, daily.title.trim(), "", excerpt));
+    }
+    out
+}
+
+/// The subject line of a month's digest.
+pub fn month_subject(month: &str) -> String {
+    format!("The Daily Stoic in {}", month)
+}
+
+/// The LLM's `synthesis` of the month's theme, if there is one, followed by
+/// every entry's date and title, under `heading`.
+pub fn month(heading: &str, dailies: &[Daily], synthesis: Option<&str>) -> String {
+    let width = dailies.iter().map(|daily| daily.date.chars().count()).max().unwrap_or(0);
+    let mut out = format!("{}, {} entries\n", heading, dailies.len());
+    if let Some(synthesis) = synthesis {
+        out.push_str(&format!("\n{}\n", synthesis));
+    }
+
+    out.push('\n');
+    for daily in dailies {
+        out.push_str(&format!("{:<width$}  {}\n", daily.date, daily.title.trim()));
+    }
+    out
+}
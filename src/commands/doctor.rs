@@ -0,0 +1,194 @@
+use crate::commands::init::{check_llm, check_source};
+use daily_stoic_rs::archive::Archive;
+use daily_stoic_rs::cache::Cache;
+use daily_stoic_rs::config::Config;
+use daily_stoic_rs::error::{DailyStoicError, Result};
+use daily_stoic_rs::render::OutputFormat;
+use daily_stoic_rs::theme::Theme;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+enum Status {
+    Ok,
+    Skipped,
+    Failed
+}
+
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    // what to do about a failure, or how to enable a skipped check
+    fix: Option<String>
+}
+
+impl Check {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Check { name, status: Status::Ok, detail: detail.into(), fix: None }
+    }
+
+    fn skipped(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check { name, status: Status::Skipped, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn failed(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Check { name, status: Status::Failed, detail: detail.into(), fix: Some(fix.into()) }
+    }
+
+    fn print(&self) {
+        let label = match self.status {
+            Status::Ok => "ok",
+            Status::Skipped => "skip",
+            Status::Failed => "FAIL",
+        };
+        println!("{:<5} {:<8} {}", label, self.name, self.detail);
+        if let Some(fix) = &self.fix {
+            println!("{:<14} {}", "", fix);
+        }
+    }
+}
+
+fn check_config(path: Option<&Path>, config: &Result<Config>) -> Check {
+    let shown = path.map(|path| path.display().to_string()).unwrap_or_else(|| "no config dir".to_string());
+    match config {
+        Err(e) => Check::failed(
+            "config",
+            format!("{}: {}", shown, e),
+            "Fix the value named above, or move the file aside and run `daily-stoic init` to write a new one",
+        ),
+        Ok(_) if !path.is_some_and(Path::exists) => Check::skipped(
+            "config",
+            format!("{} does not exist, using defaults and env vars", shown),
+            "Run `daily-stoic init` to write one",
+        ),
+        Ok(config) => {
+            if let Some(Err(e)) = config.format.as_deref().map(str::parse::<OutputFormat>) {
+                return Check::failed("config", format!("{}: {}", shown, e), "Set format to one of the formats listed");
+            }
+            if let Err(e) = Theme::from_config(&config.theme) {
+                return Check::failed("config", format!("{}: {}", shown, e), "Fix the [theme] colors, e.g. \"bold cyan\" or \"#88c0d0\"");
+            }
+            Check::ok("config", shown)
+        }
+    }
+}
+
+async fn source(config: &Config) -> Check {
+    let Some(url) = config.source_url.as_deref() else {
+        return Check::failed(
+            "source",
+            "No source url configured",
+            "Set source_url in the config file or the DAILY_STOIC_URL env var",
+        );
+    };
+    match check_source(config).await {
+        Ok(()) => Check::ok("source", url),
+        Err(DailyStoicError::DateNotFound { .. }) => Check::failed(
+            "source",
+            format!("{} has no entry for today", url),
+            "Check that source_url points at the full text of the book",
+        ),
+        Err(e) => Check::failed(
+            "source",
+            format!("{}: {}", url, e),
+            "Check the url in a browser and your network connection, or set source_url to another copy",
+        ),
+    }
+}
+
+async fn llm(config: &Config) -> Check {
+    if config.llm.is_empty() {
+        return Check::skipped(
+            "llm",
+            "No LLM configured, entries are cleaned up by rules",
+            "Run `daily-stoic init` to set one up",
+        );
+    }
+    let provider = config.llm.provider.as_deref().unwrap_or("openai");
+    let model = config.llm.model.as_deref().unwrap_or("default model");
+    match check_llm(config).await {
+        Ok(()) => Check::ok("llm", format!("{} ({})", provider, model)),
+        Err(e @ DailyStoicError::ConfigError(_)) => Check::failed(
+            "llm",
+            e.to_string(),
+            "Add the missing setting to [llm], or store the key with `daily-stoic auth set-key`",
+        ),
+        Err(e) => Check::failed(
+            "llm",
+            format!("{} ({}): {}", provider, model, e),
+            "Check llm.endpoint and llm.model, and that the key is valid (`daily-stoic auth set-key` replaces it)",
+        ),
+    }
+}
+
+fn writable(dir: &Path) -> std::io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let probe = dir.join(".doctor");
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)
+}
+
+fn cache(config: &Config) -> Check {
+    let Some(dir) = config.cache_dir.clone().or_else(Cache::default_dir) else {
+        return Check::failed("cache", "Could not determine the cache dir", "Set cache_dir in the config file");
+    };
+    match writable(&dir) {
+        Ok(()) => Check::ok("cache", dir.display().to_string()),
+        Err(e) => Check::failed(
+            "cache",
+            format!("{} is not writable: {}", dir.display(), e),
+            format!("Fix its permissions (chown -R $USER {}) or set cache_dir to another directory", dir.display()),
+        ),
+    }
+}
+
+fn archive(config: &Config) -> Check {
+    let Some(path) = Archive::path_for(config.other_book()) else {
+        return Check::failed("archive", "Could not determine the data dir", "Set XDG_DATA_HOME to a writable directory");
+    };
+    let dir = path.parent().map(Path::to_path_buf).unwrap_or_else(PathBuf::new);
+    if !path.exists() {
+        return match writable(&dir) {
+            Ok(()) => Check::skipped("archive", format!("{} not built yet", path.display()), "Run `daily-stoic archive build` to read offline"),
+            Err(e) => Check::failed(
+                "archive",
+                format!("{} is not writable: {}", dir.display(), e),
+                format!("Fix its permissions (chown -R $USER {})", dir.display()),
+            ),
+        };
+    }
+    match Archive::open(&path) {
+        Ok(_) => Check::ok("archive", path.display().to_string()),
+        Err(e) => Check::failed(
+            "archive",
+            format!("{}: {}", path.display(), e),
+            "Move the file aside and rebuild it with `daily-stoic archive build`",
+        ),
+    }
+}
+
+/// Checks the config, the source url, the LLM, the cache dir and the archive,
+/// printing a line and a suggested fix for each. Fails if any check did.
+pub async fn run(path: Option<PathBuf>, config: Result<Config>) -> std::result::Result<(), Box<dyn Error>> {
+    let mut checks = Vec::new();
+    let mut report = |check: Check| {
+        check.print();
+        checks.push(check);
+    };
+
+    report(check_config(path.as_deref(), &config));
+    // the remaining checks still say something useful with the defaults
+    let config = config.unwrap_or_default();
+    report(source(&config).await);
+    report(llm(&config).await);
+    report(cache(&config));
+    report(archive(&config));
+
+    let failed = checks.iter().filter(|check| matches!(check.status, Status::Failed)).count();
+    if failed > 0 {
+        return Err(format!("{} of {} checks failed", failed, checks.len()).into());
+    }
+    println!("\nEverything looks fine");
+    Ok(())
+}
@@ -0,0 +1,91 @@
+use daily_stoic_rs::archive::Archive;
+use daily_stoic_rs::llm::LlmProvider;
+use daily_stoic_rs::{semantic, topics};
+use std::collections::HashSet;
+use std::error::Error;
+
+/// Embeds every archived entry that has no embedding yet (or all of them with
+/// `force`), reporting progress on stderr.
+pub async fn embed(archive: &Archive, llm: &dyn LlmProvider, force: bool) -> Result<(), Box<dyn Error>> {
+    let done: HashSet<String> = if force {
+        HashSet::new()
+    } else {
+        archive.embeddings()?.into_iter().map(|(date, _)| date).collect()
+    };
+
+    let todo: Vec<_> = archive.all()?
+        .into_iter()
+        .filter(|daily| !done.contains(&daily.date))
+        .collect();
+    if todo.is_empty() && done.is_empty() {
+        return Err("The archive is empty, build it with `daily-stoic archive build`".into());
+    }
+
+    let total = todo.len();
+    let mut failed = Vec::new();
+
+    for (i, daily) in todo.iter().enumerate() {
+        match llm.embed(&semantic::embedding_text(daily)).await {
+            Ok(vector) => {
+                archive.put_embedding(&daily.date, &vector)?;
+                eprintln!("[{}/{}] {}", i + 1, total, daily.date);
+            }
+            Err(e) => {
+                eprintln!("[{}/{}] {} failed: {}", i + 1, total, daily.date, e);
+                failed.push(daily.date.clone());
+            }
+        }
+    }
+
+    println!("Embedded {} entries", total - failed.len());
+    if !failed.is_empty() {
+        println!("Failed {} entries: {}", failed.len(), failed.join(", "));
+    }
+
+    Ok(())
+}
+
+/// Tags archived entries with themes, with `llm` when given and by keyword
+/// otherwise. Entries that are already tagged are skipped unless `force` is set.
+pub async fn topics(archive: &Archive, llm: Option<&dyn LlmProvider>, force: bool) -> Result<(), Box<dyn Error>> {
+    let done: HashSet<String> = if force {
+        HashSet::new()
+    } else {
+        archive.tagged_dates()?.into_iter().collect()
+    };
+
+    let todo: Vec<_> = archive.all()?
+        .into_iter()
+        .filter(|daily| !done.contains(&daily.date))
+        .collect();
+    if todo.is_empty() && done.is_empty() {
+        return Err("The archive is empty, build it with `daily-stoic archive build`".into());
+    }
+
+    let total = todo.len();
+    let mut failed = Vec::new();
+
+    for (i, daily) in todo.iter().enumerate() {
+        let tags = match llm {
+            Some(llm) => topics::classify_with_llm(llm, daily).await,
+            None => Ok(topics::classify(daily)),
+        };
+        match tags {
+            Ok(tags) => {
+                archive.set_topics(&daily.date, &tags)?;
+                eprintln!("[{}/{}] {}: {}", i + 1, total, daily.date, tags.join(", "));
+            }
+            Err(e) => {
+                eprintln!("[{}/{}] {} failed: {}", i + 1, total, daily.date, e);
+                failed.push(daily.date.clone());
+            }
+        }
+    }
+
+    println!("Tagged {} entries", total - failed.len());
+    if !failed.is_empty() {
+        println!("Failed {} entries: {}", failed.len(), failed.join(", "));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use daily_stoic_rs::config::Config;
+use daily_stoic_rs::error::Result;
+use daily_stoic_rs::http::{self, RetryPolicy};
+use daily_stoic_rs::{credentials, date, llm, DailyStoicClient};
+use std::error::Error;
+use std::io::{self, Write};
+use std::path::Path;
+
+// asks on stderr and reads one line, falling back to `default` on an empty answer
+fn ask(question: &str, default: Option<&str>) -> io::Result<String> {
+    match default {
+        Some(default) if !default.is_empty() => eprint!("{} [{}]: ", question, default),
+        _ => eprint!("{}: ", question),
+    }
+    io::stderr().flush()?;
+
+    let mut line = String::new();
+    io::stdin().read_line(&mut line)?;
+    let answer = line.trim();
+
+    Ok(if answer.is_empty() { default.unwrap_or("").to_string() } else { answer.to_string() })
+}
+
+fn confirm(question: &str, default: bool) -> io::Result<bool> {
+    let hint = if default { "Y/n" } else { "y/N" };
+    let answer = ask(&format!("{} ({})", question, hint), None)?;
+    Ok(match answer.to_lowercase().as_str() {
+        "" => default,
+        a => a.starts_with('y'),
+    })
+}
+
+fn non_empty(s: String) -> Option<String> {
+    if s.is_empty() { None } else { Some(s) }
+}
+
+/// Fetches the source page and finds today's entry on it, without the cache.
+pub async fn check_source(config: &Config) -> Result<()> {
+    let client = DailyStoicClient::from_config(&Config { llm: Default::default(), ..config.clone() })?;
+    let body = client.fetch_body().await?;
+    client.raw_from_body(&body, &date::today())?;
+    Ok(())
+}
+
+/// Sends the configured LLM a one word prompt, succeeding when none is configured.
+pub async fn check_llm(config: &Config) -> Result<()> {
+    let retry = RetryPolicy { attempts: 1, ..RetryPolicy::default() };
+    match llm::provider_from_config(&config.llm, &http::client(&config.http)?, retry)? {
+        Some(provider) => provider.complete("Reply with the single word OK.").await.map(|_| ()),
+        None => Ok(()),
+    }
+}
+
+/// Walks through the source url and LLM settings, checks them with a test request
+/// each and writes the result to `path`.
+pub async fn run(path: &Path) -> std::result::Result<(), Box<dyn Error>> {
+    let mut config = Config::load(path)?;
+
+    eprintln!("Setting up {}\n", path.display());
+
+    config.source_url = non_empty(ask("Source url", config.source_url.as_deref())?);
+
+    let provider = ask(
+        "LLM provider (openai, azure, anthropic, ollama, none)",
+        Some(config.llm.provider.as_deref().unwrap_or("openai")),
+    )?;
+
+    let mut store_key = None;
+    if provider == "none" {
+        config.llm = Default::default();
+        config.llm.provider = Some(provider);
+    } else {
+        let (endpoint_question, model_question) = match provider.as_str() {
+            "ollama" => ("Ollama url (blank for default)", "Model (blank for the provider default)"),
+            "azure" => ("Azure resource url, e.g. https://my-resource.openai.azure.com", "Deployment name"),
+            _ => ("LLM endpoint (blank for the provider default)", "Model (blank for the provider default)"),
+        };
+        config.llm.endpoint = non_empty(ask(endpoint_question, config.llm.endpoint.as_deref())?);
+        config.llm.model = non_empty(ask(model_question, config.llm.model.as_deref())?);
+
+        if provider != "ollama" {
+            let key = ask("API key", None)?;
+            if !key.is_empty() {
+                config.llm.api_key = Some(key);
+            }
+        }
+        config.llm.provider = Some(provider);
+    }
+
+    eprint!("\nChecking the source url... ");
+    match check_source(&config).await {
+        Ok(()) => eprintln!("ok"),
+        Err(e) => eprintln!("failed: {}", e),
+    }
+
+    eprint!("Checking the LLM... ");
+    match check_llm(&config).await {
+        Ok(()) => eprintln!("ok"),
+        Err(e) => eprintln!("failed: {}", e),
+    }
+
+    if config.llm.api_key.is_some() && confirm("\nStore the API key in the OS credential store instead of the config file?", true)? {
+        store_key = config.llm.api_key.take();
+    }
+
+    if !confirm(&format!("\nWrite {}?", path.display()), true)? {
+        eprintln!("Nothing written");
+        return Ok(());
+    }
+
+    if let Some(key) = store_key {
+        credentials::set_api_key(&key)?;
+    }
+    config.save(path)?;
+    eprintln!("Wrote {}", path.display());
+
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use crate::pager;
+use chrono::{Datelike, Local};
+use daily_stoic_rs::archive::{Archive, JournalEntry};
+use daily_stoic_rs::Daily;
+use std::env;
+use std::error::Error;
+use std::fs;
+
+// lines starting with this at the top of the file are the entry, not the reflection
+const HEADER_PREFIX: &str = "#";
+
+fn editor() -> String {
+    env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| "vi".to_string())
+}
+
+fn header(daily: &Daily) -> String {
+    let mut header = format!("{} {} - {}\n{}\n", HEADER_PREFIX, daily.date, daily.title, HEADER_PREFIX);
+    for line in daily.quote.lines() {
+        header.push_str(&format!("{} {}\n", HEADER_PREFIX, line));
+    }
+    header.push_str(&format!("{} {}\n", HEADER_PREFIX, daily.quoter));
+    header.push_str(&format!("{}\n{} Write your reflection below, lines starting with {} up here are dropped.\n\n",
+        HEADER_PREFIX, HEADER_PREFIX, HEADER_PREFIX));
+    header
+}
+
+fn strip_header(text: &str) -> String {
+    text.lines()
+        .skip_while(|line| line.starts_with(HEADER_PREFIX) || line.trim().is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+        .trim()
+        .to_string()
+}
+
+/// Opens `$VISUAL`/`$EDITOR` on this year's reflection for `daily`, headed by its
+/// quote, and saves whatever is written below the header.
+pub fn write(archive: &Archive, daily: &Daily) -> Result<(), Box<dyn Error>> {
+    let year = Local::now().year();
+    let existing = archive.journal(&daily.date)?
+        .into_iter()
+        .find(|entry| entry.year == year)
+        .map(|entry| entry.text)
+        .unwrap_or_default();
+
+    let path = env::temp_dir().join(format!("daily-stoic-journal-{}.md", std::process::id()));
+    fs::write(&path, format!("{}{}\n", header(daily), existing))?;
+
+    let status = pager::shell(&format!("{} \"{}\"", editor(), path.display())).status();
+    let text = fs::read_to_string(&path);
+    let _ = fs::remove_file(&path);
+
+    if !status?.success() {
+        return Err("The editor exited with an error, nothing was saved".into());
+    }
+
+    let text = strip_header(&text?);
+    if text.is_empty() {
+        println!("Empty reflection, nothing was saved");
+        return Ok(());
+    }
+
+    archive.put_journal(&JournalEntry { year, date: daily.date.clone(), text })?;
+    println!("Saved your reflection on {} {}", daily.date, year);
+    Ok(())
+}
+
+/// Prints every year's reflection on `date`.
+pub fn show(archive: &Archive, date: &str, paged: bool) -> Result<(), Box<dyn Error>> {
+    let entries = archive.journal(date)?;
+    if entries.is_empty() {
+        return Err(format!("No reflections on {} yet", date).into());
+    }
+
+    let rendered: Vec<String> = entries
+        .iter()
+        .map(|entry| format!("## {} {}\n\n{}", entry.date, entry.year, entry.text))
+        .collect();
+    pager::print(&rendered.join("\n\n"), paged);
+    Ok(())
+}
+
+/// One line per reflection with its first line as a preview.
+pub fn list(archive: &Archive) -> Result<(), Box<dyn Error>> {
+    for entry in archive.all_journal()? {
+        let preview = entry.text.lines().next().unwrap_or("");
+        println!("{} {:<12} {}", entry.year, entry.date, preview);
+    }
+    Ok(())
+}
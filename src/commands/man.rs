@@ -0,0 +1,103 @@
+use clap::Command;
+use clap_mangen::Man;
+use daily_stoic_rs::config;
+use std::error::Error;
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+// variables read outside of the config file
+const ENVIRONMENT: [(&str, &str); 6] = [
+    ("NO_COLOR", "Any non-empty value turns off styled output."),
+    ("LC_ALL, LC_MESSAGES, LANG", "The language of labels and error headings, unless --locale is given."),
+    ("VISUAL, EDITOR", "The editor journal write opens."),
+    ("XDG_CONFIG_HOME, XDG_DATA_HOME, XDG_CACHE_HOME", "Where the config, the archive and the cache live on Linux."),
+    ("DAILY_STOIC_RELEASES_URL", "The GitHub API endpoint self-update asks for the latest release."),
+    ("COMPLETE", "Set by the scripts of daily-stoic completions when they ask for suggestions."),
+];
+
+const FILES: [(&str, &str); 4] = [
+    ("~/.config/daily-stoic/config.toml", "The config file, see CONFIGURATION."),
+    ("~/.local/share/daily-stoic/archive.db", "The archive of the whole year, favorites, journal entries and indexes."),
+    ("~/.local/share/daily-stoic/usage.jsonl", "The tokens of every LLM call, for usage and llm.monthly_budget."),
+    ("~/.cache/daily-stoic", "Fetched pages and cleaned up entries."),
+];
+
+// plain text as roff, which gives backslashes, hyphens and leading dots a meaning
+fn escape(text: &str) -> String {
+    let text = text.replace('\\', "\\e").replace('-', "\\-");
+    if text.starts_with(['.', '\'']) { format!("\\&{}", text) } else { text }
+}
+
+fn item(w: &mut dyn Write, term: &str, text: &str) -> std::io::Result<()> {
+    writeln!(w, ".TP\n\\fB{}\\fR\n{}", escape(term), escape(text))
+}
+
+/// The daily-stoic(1) page: the generated synopsis, options and commands,
+/// plus the config keys, the environment and the files it uses.
+pub fn page(cmd: Command, w: &mut dyn Write) -> Result<(), Box<dyn Error>> {
+    let man = Man::new(cmd);
+    man.render_title(w)?;
+    man.render_name_section(w)?;
+    man.render_synopsis_section(w)?;
+    man.render_description_section(w)?;
+    man.render_options_section(w)?;
+    man.render_subcommands_section(w)?;
+
+    writeln!(w, ".SH CONFIGURATION")?;
+    writeln!(
+        w,
+        "{}",
+        escape("Settings are read from the config file (or --config), then overridden by the environment variables listed with each key, then by flags.")
+    )?;
+    for (key, vars, description) in config::KEYS {
+        let text = if vars.is_empty() { description.to_string() } else { format!("{} ({})", description, vars.join(", ")) };
+        item(w, key, &text)?;
+    }
+
+    writeln!(w, ".SH ENVIRONMENT")?;
+    for (key, vars, _) in config::KEYS {
+        for var in vars.iter().filter(|var| var.starts_with("DAILY_STOIC_")) {
+            item(w, var, &format!("Overrides {}.", key))?;
+        }
+    }
+    for (vars, text) in ENVIRONMENT {
+        item(w, vars, text)?;
+    }
+
+    writeln!(w, ".SH FILES")?;
+    for (path, text) in FILES {
+        item(w, path, text)?;
+    }
+
+    man.render_version_section(w)?;
+    Ok(())
+}
+
+/// Writes daily-stoic.1 and a page per subcommand, e.g. daily-stoic-export-pdf.1,
+/// into `dir` and returns their paths.
+pub fn write_all(mut cmd: Command, dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+    fn subcommands(cmd: &Command, dir: &Path, paths: &mut Vec<PathBuf>) -> Result<(), Box<dyn Error>> {
+        for sub in cmd.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+            subcommands(sub, dir, paths)?;
+            let path = Man::new(sub.clone())
+                .generate_to(dir)
+                .map_err(|e| format!("Failed to write a page into {}: {}", dir.display(), e))?;
+            paths.push(path);
+        }
+        Ok(())
+    }
+
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    cmd = cmd.disable_help_subcommand(true);
+    cmd.build();
+
+    let mut paths = Vec::new();
+    subcommands(&cmd, dir, &mut paths)?;
+
+    let path = dir.join(Man::new(cmd.clone()).get_filename());
+    let mut file = fs::File::create(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    page(cmd, &mut file)?;
+    paths.push(path);
+    Ok(paths)
+}
@@ -0,0 +1,96 @@
+use daily_stoic_rs::Daily;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+// fetched text is shown to everyone logging in, so no escape sequences get through
+fn plain(text: &str) -> String {
+    text.split_whitespace()
+        .map(|word| word.chars().filter(|c| !c.is_control()).collect::<String>())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    if width == 0 {
+        return vec![text.to_string()];
+    }
+    textwrap::wrap(text, width).into_iter().map(|line| line.into_owned()).collect()
+}
+
+/// The words of `text` that fit in `budget` characters, with an ellipsis if
+/// any were dropped.
+pub fn shorten(text: &str, budget: usize) -> String {
+    if text.chars().count() <= budget {
+        return text.to_string();
+    }
+    let mut out = String::new();
+    for word in text.split(' ') {
+        let next = if out.is_empty() { word.to_string() } else { format!("{} {}", out, word) };
+        if next.chars().count() + 1 > budget {
+            break;
+        }
+        out = next;
+    }
+    if out.is_empty() {
+        out = text.chars().take(budget.saturating_sub(1)).collect();
+    }
+    format!("{}…", out)
+}
+
+/// The entry as a few plain lines for a login message: date and title, the
+/// quote and who said it, wrapped at `width` (0 for no wrapping). With
+/// `max_chars` the title line goes first and then the end of the quote, so
+/// the whole message stays within the budget.
+pub fn render(daily: &Daily, width: usize, max_chars: Option<usize>) -> String {
+    let citation = &daily.citation;
+    let source = match &citation.work {
+        Some(work) => format!("{}, {}", citation.author, work),
+        None => citation.author.clone(),
+    };
+    let header = plain(&format!("{} · {}", daily.date, daily.title));
+    let quote = plain(&daily.quote);
+    let attribution = format!("  — {}", plain(&source));
+
+    let lines = |header: Option<&str>, quote: &str| {
+        let mut lines: Vec<String> = header.map(|header| wrap(header, width)).unwrap_or_default();
+        lines.extend(wrap(quote, width));
+        lines.push(attribution.clone());
+        lines.join("\n")
+    };
+
+    let full = lines(Some(&header), &quote);
+    let Some(budget) = max_chars else { return full };
+    if full.chars().count() <= budget {
+        return full;
+    }
+
+    // wrapping only turns spaces into line breaks, unless a word is longer than
+    // the width, so the cut at the end is a last resort
+    let room = budget.saturating_sub(lines(None, "").chars().count());
+    lines(None, &shorten(&quote, room)).chars().take(budget).collect()
+}
+
+/// Writes an update-motd(5) script at `path` that prints the day's message
+/// through `exe` with `args`, and makes it executable.
+pub fn install(path: &Path, exe: &Path, args: &[String]) -> Result<(), Box<dyn Error>> {
+    let quote = |arg: &str| format!("'{}'", arg.replace('\'', "'\\''"));
+    let mut command = vec![quote(&exe.to_string_lossy())];
+    command.extend(args.iter().map(|arg| quote(arg)));
+
+    let script = format!(
+        "#!/bin/sh\n# The Daily Stoic, written by `daily-stoic motd --out`\n\
+        # a failed fetch shouldn't get in the way of logging in\n{} 2>/dev/null || true\n",
+        command.join(" ")
+    );
+    fs::write(path, script).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", path.display(), e))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,72 @@
+use daily_stoic_rs::Daily;
+#[cfg(all(unix, not(target_os = "macos")))]
+use daily_stoic_rs::render::{self, OutputFormat};
+use notify_rust::Notification;
+use std::error::Error;
+#[cfg(all(unix, not(target_os = "macos")))]
+use std::fs;
+
+const APP_NAME: &str = "The Daily Stoic";
+// notifications are short, the rest is one click away
+const MAX_BODY: usize = 300;
+
+fn body(daily: &Daily) -> String {
+    let quote = daily.quote.trim();
+    let quote = if quote.chars().count() > MAX_BODY {
+        format!("{}…", quote.chars().take(MAX_BODY).collect::<String>())
+    } else {
+        quote.to_string()
+    };
+    format!("{}\n{}", quote, daily.quoter)
+}
+
+// the entry as a page in the temp dir, for the browser to show
+#[cfg(all(unix, not(target_os = "macos")))]
+fn open_entry(daily: &Daily) -> Result<(), Box<dyn Error>> {
+    let path = std::env::temp_dir().join(format!("daily-stoic-{}.html", daily.date.to_lowercase().replace(' ', "-")));
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{}</title></head>\n\
+        <body style=\"font-family: Georgia, serif; max-width: 40em; margin: 2em auto;\">\n{}\n</body>\n</html>",
+        render::escape_html(&daily.title),
+        render::render(daily, OutputFormat::Html)
+    );
+    fs::write(&path, html).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    webbrowser::open(&path.to_string_lossy()).map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    Ok(())
+}
+
+/// Shows the title and quote as a desktop notification. Where the
+/// notification server supports actions, clicking it (or its "Read" button)
+/// opens the full entry in the browser, unless `wait` is false.
+pub fn show(daily: &Daily, wait: bool) -> Result<(), Box<dyn Error>> {
+    let mut notification = Notification::new();
+    notification
+        .appname(APP_NAME)
+        .summary(&format!("{}: {}", daily.date, daily.title))
+        .body(&body(daily));
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        if wait {
+            notification.action("default", "Read").action("read", "Read");
+        }
+        let handle = notification.show().map_err(|e| format!("Failed to show the notification: {}", e))?;
+        if wait {
+            let mut clicked = false;
+            handle.wait_for_action(|action| clicked = matches!(action, "default" | "read"));
+            if clicked {
+                open_entry(daily)?;
+            }
+        }
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        // no click callbacks here, so point at the command instead
+        let _ = wait;
+        notification.body(&format!("{}\n\nRun `daily-stoic` for the full entry.", body(daily)));
+        notification.show().map_err(|e| format!("Failed to show the notification: {}", e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use chrono::NaiveDate;
+use daily_stoic_rs::feed::{self, Enclosure};
+use daily_stoic_rs::tts::{self, TtsProvider};
+use daily_stoic_rs::Daily;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+const FEED_FILE: &str = "feed.xml";
+
+fn mime_type(format: &str) -> &'static str {
+    match format {
+        "mp3" => "audio/mpeg",
+        "wav" => "audio/wav",
+        "opus" => "audio/ogg",
+        "aac" => "audio/aac",
+        "flac" => "audio/flac",
+        _ => "application/octet-stream",
+    }
+}
+
+// episode files are named by day, e.g. 2024-03-05.mp3
+fn is_episode(name: &str) -> bool {
+    name.split_once('.')
+        .is_some_and(|(stem, _)| NaiveDate::parse_from_str(stem, "%Y-%m-%d").is_ok())
+}
+
+/// Synthesizes an episode for each of `entries` into `dir` (skipping days that
+/// already have one), removes episodes that dropped out of the window, and
+/// writes the feed with enclosures under `url`. Returns how many episodes were
+/// new and the feed's path.
+pub async fn build(
+    entries: &[(NaiveDate, Daily)],
+    voice: &dyn TtsProvider,
+    dir: &Path,
+    url: &str,
+) -> Result<(usize, PathBuf), Box<dyn Error>> {
+    fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let format = voice.formats()[0];
+
+    let mut created = 0;
+    let mut episodes = Vec::new();
+    for (day, daily) in entries {
+        let name = format!("{}.{}", day.format("%Y-%m-%d"), format);
+        let path = dir.join(&name);
+
+        if !path.exists() {
+            info!("Reading {} with {}", daily.date, voice.name());
+            let audio = voice.synthesize(&tts::script(daily), format).await?;
+            fs::write(&path, audio).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            created += 1;
+        }
+
+        let length = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        let enclosure = Enclosure {
+            url: format!("{}/{}", url.trim_end_matches('/'), name),
+            length,
+            mime_type: mime_type(format).to_string()
+        };
+        episodes.push((*day, daily.clone(), enclosure));
+    }
+
+    // older episodes would otherwise pile up on every run
+    let keep: Vec<String> = episodes.iter().filter_map(|(_, _, e)| e.url.rsplit('/').next().map(str::to_string)).collect();
+    for entry in fs::read_dir(dir).map_err(|e| format!("Failed to read {}: {}", dir.display(), e))?.flatten() {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if is_episode(&name) && !keep.contains(&name) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+
+    let path = dir.join(FEED_FILE);
+    fs::write(&path, feed::podcast(&episodes, url)).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok((created, path))
+}
@@ -0,0 +1,40 @@
+use image::{GrayImage, Luma};
+use qrcode::render::unicode::Dense1x2;
+use qrcode::{Color, QrCode};
+use std::error::Error;
+use std::path::Path;
+
+// the blank border scanners need around the code, in modules
+const QUIET_ZONE: usize = 4;
+
+pub fn encode(data: &str) -> Result<QrCode, Box<dyn Error>> {
+    QrCode::new(data.as_bytes()).map_err(|e| format!("Failed to make a QR code: {}", e).into())
+}
+
+/// `code` in half blocks, two modules per character. Light modules are drawn
+/// and dark ones left blank, which scans on the usual dark terminal.
+pub fn terminal(code: &QrCode) -> String {
+    code.render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .quiet_zone(true)
+        .build()
+}
+
+/// Writes `code` as a black on white PNG about `size` pixels wide.
+pub fn png(code: &QrCode, path: &Path, size: u32) -> Result<(), Box<dyn Error>> {
+    let width = code.width();
+    let colors = code.to_colors();
+    let modules = (width + 2 * QUIET_ZONE) as u32;
+    let scale = (size / modules).max(1);
+
+    let image = GrayImage::from_fn(modules * scale, modules * scale, |x, y| {
+        let (x, y) = ((x / scale) as usize, (y / scale) as usize);
+        let dark = (QUIET_ZONE..QUIET_ZONE + width).contains(&x)
+            && (QUIET_ZONE..QUIET_ZONE + width).contains(&y)
+            && colors[(y - QUIET_ZONE) * width + x - QUIET_ZONE] == Color::Dark;
+        Luma([if dark { 0 } else { 255 }])
+    });
+    image.save(path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,97 @@
+use chrono::NaiveTime;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::warn;
+
+const UNIT: &str = "daily-stoic";
+
+fn unit_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let dir = dirs::config_dir().ok_or("Could not determine the config dir for systemd user units")?;
+    Ok(dir.join("systemd").join("user"))
+}
+
+fn systemctl(args: &[&str]) -> Result<(), Box<dyn Error>> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .status()
+        .map_err(|e| format!("Failed to run systemctl: {}", e))?;
+    if !status.success() {
+        return Err(format!("systemctl --user {} failed ({})", args.join(" "), status).into());
+    }
+    Ok(())
+}
+
+// systemd splits ExecStart on whitespace, quoted words stay together
+fn quote(arg: &str) -> String {
+    if arg.is_empty() || arg.contains(char::is_whitespace) || arg.contains('"') {
+        format!("\"{}\"", arg.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        arg.to_string()
+    }
+}
+
+/// Writes a user service running `daily-stoic <action>` and a timer starting
+/// it daily at `time`, then enables the timer unless `enable` is false.
+pub fn install(time: NaiveTime, action: &str, config: Option<&Path>, enable: bool) -> Result<(), Box<dyn Error>> {
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the daily-stoic binary: {}", e))?;
+
+    let mut command = vec![quote(&exe.to_string_lossy())];
+    if let Some(config) = config {
+        let config = fs::canonicalize(config).unwrap_or_else(|_| config.to_path_buf());
+        command.extend(["--config".to_string(), quote(&config.to_string_lossy())]);
+    }
+    command.extend(action.split_whitespace().map(quote));
+
+    let service = format!(
+        "[Unit]\nDescription=The Daily Stoic: {action}\nAfter=network-online.target\n\n\
+        [Service]\nType=oneshot\nExecStart={}\n",
+        command.join(" ")
+    );
+    let timer = format!(
+        "[Unit]\nDescription=Run daily-stoic {action} every day at {}\n\n\
+        [Timer]\nOnCalendar=*-*-* {}\n# catch up on runs missed while the machine was off\nPersistent=true\n\n\
+        [Install]\nWantedBy=timers.target\n",
+        time.format("%H:%M"),
+        time.format("%H:%M:00")
+    );
+
+    let dir = unit_dir()?;
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    for (name, contents) in [(format!("{}.service", UNIT), service), (format!("{}.timer", UNIT), timer)] {
+        let path = dir.join(&name);
+        fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+        println!("Wrote {}", path.display());
+    }
+
+    if enable {
+        systemctl(&["daemon-reload"])?;
+        systemctl(&["enable", "--now", &format!("{}.timer", UNIT)])?;
+        println!("Enabled {}.timer, `daily-stoic {}` will run daily at {}", UNIT, action, time.format("%H:%M"));
+    }
+    Ok(())
+}
+
+/// Stops and disables the timer and removes both units.
+pub fn uninstall() -> Result<(), Box<dyn Error>> {
+    let dir = unit_dir()?;
+    let timer = dir.join(format!("{}.timer", UNIT));
+    if !timer.exists() {
+        return Err(format!("No daily-stoic timer is installed ({} does not exist)", timer.display()).into());
+    }
+
+    // a timer that was written with --no-enable isn't enabled, removing it is still fine
+    if let Err(e) = systemctl(&["disable", "--now", &format!("{}.timer", UNIT)]) {
+        warn!("{}", e);
+    }
+    for path in [timer, dir.join(format!("{}.service", UNIT))] {
+        match fs::remove_file(&path) {
+            Ok(()) => println!("Removed {}", path.display()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to remove {}: {}", path.display(), e).into()),
+        }
+    }
+    systemctl(&["daemon-reload"])
+}
@@ -0,0 +1,100 @@
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::{Json, Router};
+use daily_stoic_rs::error::DailyStoicError;
+use daily_stoic_rs::{date, Daily, DailyStoicClient};
+use serde::Deserialize;
+use serde_json::json;
+use std::error::Error;
+use std::sync::Arc;
+use tracing::info;
+
+struct AppState {
+    client: DailyStoicClient,
+    use_llm: bool,
+}
+
+/// Library errors as JSON `{"error": ...}` with a matching status code.
+struct ApiError(StatusCode, String);
+
+impl From<DailyStoicError> for ApiError {
+    fn from(e: DailyStoicError) -> Self {
+        let status = match e {
+            DailyStoicError::DateNotFound { .. } => StatusCode::NOT_FOUND,
+            DailyStoicError::ParseError(_) => StatusCode::BAD_REQUEST,
+            DailyStoicError::NetworkError(_) | DailyStoicError::LlmError(_) => StatusCode::BAD_GATEWAY,
+            DailyStoicError::ConfigError(_) | DailyStoicError::StorageError(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        ApiError(status, e.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.0, Json(json!({ "error": self.1 }))).into_response()
+    }
+}
+
+type ApiResult<T> = Result<Json<T>, ApiError>;
+
+#[derive(Deserialize)]
+struct SearchParams {
+    q: String,
+    limit: Option<usize>,
+}
+
+/// Serves the JSON API on `addr` until interrupted.
+pub async fn run(client: DailyStoicClient, addr: &str, use_llm: bool) -> Result<(), Box<dyn Error>> {
+    let state = Arc::new(AppState { client, use_llm });
+    let app = Router::new()
+        .route("/today", get(today))
+        .route("/date/{date}", get(by_date))
+        .route("/random", get(random))
+        .route("/search", get(search))
+        .fallback(|| async { ApiError(StatusCode::NOT_FOUND, "No such endpoint".to_string()) })
+        .with_state(state);
+
+    let listener = tokio::net::TcpListener::bind(addr)
+        .await
+        .map_err(|e| format!("Failed to listen on {}: {}", addr, e))?;
+    println!("Serving on http://{} (Ctrl-C to stop)", listener.local_addr()?);
+
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn entry(state: &AppState, date: &str) -> ApiResult<Daily> {
+    info!("GET {}", date);
+    let daily = state.client.get_entry(date, state.use_llm).await?;
+    Ok(Json(daily))
+}
+
+async fn today(State(state): State<Arc<AppState>>) -> ApiResult<Daily> {
+    entry(&state, &date::today()).await
+}
+
+// "March-5" in urls, or any other form `parse_date` accepts
+async fn by_date(State(state): State<Arc<AppState>>, Path(date): Path<String>) -> ApiResult<Daily> {
+    let date = date::parse_date(&date).or_else(|_| date::parse_date(&date.replace('-', " ")))?;
+    entry(&state, &date).await
+}
+
+async fn random(State(state): State<Arc<AppState>>) -> ApiResult<Daily> {
+    let archived = match state.client.archive() {
+        Some(archive) => archive.random_date()?,
+        None => None,
+    };
+    entry(&state, &archived.unwrap_or_else(date::random)).await
+}
+
+async fn search(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<SearchParams>,
+) -> ApiResult<Vec<daily_stoic_rs::archive::SearchHit>> {
+    let archive = state.client
+        .archive()
+        .ok_or_else(|| ApiError(StatusCode::SERVICE_UNAVAILABLE, "Search needs an archive, run `daily-stoic archive` first".to_string()))?;
+    Ok(Json(archive.search(&params.q, ("<mark>", "</mark>"), params.limit.unwrap_or(10))?))
+}
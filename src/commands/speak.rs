@@ -0,0 +1,90 @@
+use daily_stoic_rs::tts::{self, TtsProvider};
+use daily_stoic_rs::Daily;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// Reads `daily` aloud with `voice`, or saves the audio to `out` in the format
+/// its extension names.
+pub async fn run(daily: &Daily, voice: &dyn TtsProvider, out: Option<&Path>) -> Result<(), Box<dyn Error>> {
+    let script = tts::script(daily);
+
+    if let Some(out) = out {
+        let format = out
+            .extension()
+            .map(|ext| ext.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| voice.formats()[0].to_string());
+        if !voice.formats().contains(&format.as_str()) {
+            return Err(format!(
+                "The {} voice can't write .{} files (expected .{})",
+                voice.name(), format, voice.formats().join(", .")
+            ).into());
+        }
+
+        let audio = voice.synthesize(&script, &format).await?;
+        fs::write(out, audio).map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+        println!("Wrote {}", out.display());
+        return Ok(());
+    }
+
+    if voice.speak(&script)? {
+        return Ok(());
+    }
+
+    let format = voice.formats()[0];
+    let audio = voice.synthesize(&script, format).await?;
+    let path = std::env::temp_dir().join(format!("daily-stoic-speech-{}.{}", std::process::id(), format));
+    fs::write(&path, audio).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let result = play(&path);
+    let _ = fs::remove_file(&path);
+    result
+}
+
+fn try_run(program: &str, args: &[&str]) -> bool {
+    debug!("{} {}", program, args.join(" "));
+    Command::new(program).args(args).status().is_ok_and(|status| status.success())
+}
+
+#[cfg(target_os = "macos")]
+fn play(path: &Path) -> Result<(), Box<dyn Error>> {
+    let file = path.to_string_lossy();
+    if try_run("afplay", &[&file]) { Ok(()) } else { Err("Failed to play the audio with afplay".into()) }
+}
+
+#[cfg(windows)]
+fn play(path: &Path) -> Result<(), Box<dyn Error>> {
+    // Windows Media Player's COM object plays mp3 as well as wav
+    let script = format!(
+        "$p = New-Object -ComObject WMPlayer.OCX; $p.URL = '{}'; $p.controls.play(); \
+        Start-Sleep -Milliseconds 500; while ($p.playState -eq 3) {{ Start-Sleep -Milliseconds 200 }}",
+        path.display().to_string().replace('\'', "''")
+    );
+    if try_run("powershell", &["-NoProfile", "-Command", &script]) {
+        Ok(())
+    } else {
+        Err("Failed to play the audio with PowerShell".into())
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn play(path: &Path) -> Result<(), Box<dyn Error>> {
+    let file = path.to_string_lossy();
+    let players: [(&str, &[&str]); 4] = [
+        ("mpv", &["--really-quiet", "--no-video"]),
+        ("ffplay", &["-nodisp", "-autoexit", "-loglevel", "quiet"]),
+        ("paplay", &[]),
+        ("aplay", &["-q"]),
+    ];
+
+    for (program, args) in players {
+        let mut args = args.to_vec();
+        args.push(&file);
+        if try_run(program, &args) {
+            return Ok(());
+        }
+    }
+    Err("No audio player found, install mpv or ffmpeg, or save the audio with --out".into())
+}
@@ -0,0 +1,150 @@
+//! Figures about the archived entries: who is quoted, how long entries run and
+//! which words come up most.
+
+use daily_stoic_rs::Daily;
+use serde::Serialize;
+use std::collections::HashMap;
+
+// words too common to say anything about an entry
+const STOPWORDS: [&str; 128] = [
+    "a", "about", "above", "after", "again", "against", "all", "also", "am", "an", "and", "any", "are", "as", "at",
+    "be", "because", "been", "before", "being", "below", "between", "both", "but", "by", "can", "could", "did", "do",
+    "does", "doing", "down", "during", "each", "even", "every", "few", "for", "from", "further", "had", "has", "have",
+    "having", "he", "her", "here", "hers", "herself", "him", "himself", "his", "how", "if", "in", "into", "is", "it",
+    "its", "itself", "just", "let", "may", "me", "might", "more", "most", "much", "must", "my", "myself", "no", "nor",
+    "not", "now", "of", "off", "on", "once", "one", "only", "or", "other", "our", "ours", "ourselves", "out", "over",
+    "own", "same", "she", "should", "so", "some", "such", "than", "that", "the", "their", "them", "themselves", "then",
+    "there", "these", "they", "this", "those", "through", "to", "too", "under", "until", "up", "us", "very", "was",
+    "we", "were", "what", "when", "where", "which", "while", "who", "will", "with", "would", "you",
+];
+
+#[derive(Serialize)]
+pub struct AuthorCount {
+    pub author: String,
+    pub quotes: usize,
+}
+
+#[derive(Serialize)]
+pub struct WordCount {
+    pub word: String,
+    pub count: usize,
+}
+
+/// An entry's length in words, its quote and explanation together.
+#[derive(Clone, Serialize)]
+pub struct EntryLength {
+    pub date: String,
+    pub title: String,
+    pub words: usize,
+}
+
+#[derive(Serialize)]
+pub struct Stats {
+    pub entries: usize,
+    pub authors: Vec<AuthorCount>,
+    pub average_quote_words: f64,
+    pub average_explanation_words: f64,
+    pub top_words: Vec<WordCount>,
+    pub longest: Vec<EntryLength>,
+    pub shortest: Vec<EntryLength>,
+}
+
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphabetic() && c != '\'' && c != '’')
+        .map(|word| word.trim_matches(['\'', '’']).to_lowercase())
+        .map(|word| word.strip_suffix("'s").or_else(|| word.strip_suffix("’s")).unwrap_or(&word).to_string())
+        .filter(|word| !word.is_empty())
+}
+
+fn average(total: usize, count: usize) -> f64 {
+    if count == 0 { 0.0 } else { total as f64 / count as f64 }
+}
+
+/// Counts over `dailies`, keeping the `limit` most quoted authors, most
+/// frequent words and longest and shortest entries.
+pub fn compute(dailies: &[Daily], limit: usize) -> Stats {
+    let mut authors: HashMap<&str, usize> = HashMap::new();
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    let mut lengths = Vec::new();
+    let (mut quote_words, mut explanation_words) = (0, 0);
+
+    for daily in dailies {
+        let author = if daily.citation.author.is_empty() { "Unknown" } else { daily.citation.author.as_str() };
+        *authors.entry(author).or_default() += 1;
+
+        let quote = words(&daily.quote).count();
+        let explanation = words(&daily.explanation).count();
+        quote_words += quote;
+        explanation_words += explanation;
+        lengths.push(EntryLength { date: daily.date.clone(), title: daily.title.trim().to_string(), words: quote + explanation });
+
+        for word in words(&daily.quote).chain(words(&daily.explanation)) {
+            if word.chars().count() > 2 && !STOPWORDS.contains(&word.as_str()) {
+                *frequencies.entry(word).or_default() += 1;
+            }
+        }
+    }
+
+    let mut authors: Vec<AuthorCount> = authors
+        .into_iter()
+        .map(|(author, quotes)| AuthorCount { author: author.to_string(), quotes })
+        .collect();
+    authors.sort_by(|a, b| b.quotes.cmp(&a.quotes).then_with(|| a.author.cmp(&b.author)));
+    authors.truncate(limit);
+
+    let mut top_words: Vec<WordCount> = frequencies.into_iter().map(|(word, count)| WordCount { word, count }).collect();
+    top_words.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.word.cmp(&b.word)));
+    top_words.truncate(limit);
+
+    // the sorts are stable, so calendar order breaks ties as entries come from the archive in it
+    let mut longest = lengths.clone();
+    longest.sort_by_key(|entry| std::cmp::Reverse(entry.words));
+    longest.truncate(limit);
+    let mut shortest = lengths;
+    shortest.sort_by_key(|entry| entry.words);
+    shortest.truncate(limit);
+
+    Stats {
+        entries: dailies.len(),
+        authors,
+        average_quote_words: average(quote_words, dailies.len()),
+        average_explanation_words: average(explanation_words, dailies.len()),
+        top_words,
+        longest,
+        shortest,
+    }
+}
+
+fn entry_table(title: &str, entries: &[EntryLength]) -> Vec<String> {
+    let width = entries.iter().map(|entry| entry.date.chars().count()).max().unwrap_or(0);
+    let mut lines = vec![format!("{:<width$}  {:>5}  Title", title, "Words", width = width.max(title.len()))];
+    for entry in entries {
+        lines.push(format!("{:<width$}  {:>5}  {}", entry.date, entry.words, entry.title, width = width.max(title.len())));
+    }
+    lines
+}
+
+/// The stats as tables for the terminal.
+pub fn render(stats: &Stats) -> String {
+    let mut lines = vec![
+        format!("{} entries", stats.entries),
+        format!("Average quote: {:.1} words", stats.average_quote_words),
+        format!("Average explanation: {:.1} words", stats.average_explanation_words),
+        String::new(),
+    ];
+
+    let width = stats.authors.iter().map(|count| count.author.chars().count()).max().unwrap_or(0).max(6);
+    lines.push(format!("{:<width$}  {:>6}", "Author", "Quotes"));
+    lines.extend(stats.authors.iter().map(|count| format!("{:<width$}  {:>6}", count.author, count.quotes)));
+    lines.push(String::new());
+
+    let width = stats.top_words.iter().map(|count| count.word.chars().count()).max().unwrap_or(0).max(4);
+    lines.push(format!("{:<width$}  {:>5}", "Word", "Count"));
+    lines.extend(stats.top_words.iter().map(|count| format!("{:<width$}  {:>5}", count.word, count.count)));
+    lines.push(String::new());
+
+    lines.extend(entry_table("Longest", &stats.longest));
+    lines.push(String::new());
+    lines.extend(entry_table("Shortest", &stats.shortest));
+    lines.join("\n")
+}
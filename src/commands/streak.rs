@@ -0,0 +1,133 @@
+//! Reading streaks and a year of reading history as a heatmap, like the
+//! contribution graph on a GitHub profile.
+
+use chrono::{Datelike, Days, NaiveDate};
+use daily_stoic_rs::theme::Style;
+use std::collections::BTreeMap;
+
+// how many weeks the heatmap goes back, a year and the week so far
+const WEEKS: u64 = 53;
+
+// darkest to brightest, for days with one, two and three or more entries read
+const SHADES: [&str; 3] = ["#0e4429", "#26a641", "#39d353"];
+const BLOCKS: [&str; 3] = ["░", "▓", "█"];
+
+/// A run of consecutive days with something read.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Streak {
+    pub first: NaiveDate,
+    pub last: NaiveDate,
+}
+
+impl Streak {
+    pub fn days(&self) -> u64 {
+        (self.last - self.first).num_days() as u64 + 1
+    }
+}
+
+/// Every run of consecutive days in `days`, oldest first.
+pub fn streaks(days: &BTreeMap<NaiveDate, usize>) -> Vec<Streak> {
+    let mut streaks: Vec<Streak> = Vec::new();
+    for &day in days.keys() {
+        match streaks.last_mut() {
+            Some(streak) if streak.last.succ_opt() == Some(day) => streak.last = day,
+            _ => streaks.push(Streak { first: day, last: day }),
+        }
+    }
+    streaks
+}
+
+/// The streak still going on `today`: one that reached yesterday counts until
+/// today is over.
+pub fn current(streaks: &[Streak], today: NaiveDate) -> Option<Streak> {
+    streaks
+        .last()
+        .copied()
+        .filter(|streak| streak.last == today || streak.last.succ_opt() == Some(today))
+}
+
+/// The longest streak, the most recent of equally long ones.
+pub fn longest(streaks: &[Streak]) -> Option<Streak> {
+    streaks.iter().rev().max_by_key(|streak| streak.days()).copied()
+}
+
+/// The last year up to `today` as a grid of weeks, a row per weekday from
+/// Monday, with month names above the week each month starts in. Days read
+/// are shaded by how many entries were read, in green with `color`.
+pub fn heatmap(days: &BTreeMap<NaiveDate, usize>, today: NaiveDate, color: bool) -> String {
+    let monday = today - Days::new(today.weekday().num_days_from_monday() as u64);
+    let start = monday - Days::new(7 * (WEEKS - 1));
+    let empty = if color { Style::parse("dim").unwrap().paint("·") } else { "·".to_string() }; // a known style
+
+    // a month's name goes over its first full week, when there is room for it
+    let mut months = String::new();
+    for week in 0..WEEKS {
+        let day = start + Days::new(7 * week);
+        let width = months.chars().count();
+        if day.day() <= 7 && width <= 4 + week as usize {
+            months.push_str(&" ".repeat(4 + week as usize - width));
+            months.push_str(&day.format("%b").to_string());
+        }
+    }
+
+    let mut rows = vec![months.trim_end().to_string()];
+    for weekday in 0..7 {
+        let label = match weekday {
+            0 => "Mon",
+            2 => "Wed",
+            4 => "Fri",
+            _ => "",
+        };
+        let mut row = format!("{:<4}", label);
+        for week in 0..WEEKS {
+            let day = start + Days::new(7 * week + weekday);
+            if day > today {
+                break;
+            }
+            let cell = match days.get(&day).copied().unwrap_or(0) {
+                0 => empty.clone(),
+                read => {
+                    let level = read.min(3) - 1;
+                    if color { Style::parse(SHADES[level]).unwrap().paint("■") } else { BLOCKS[level].to_string() } // known colors
+                }
+            };
+            row.push_str(&cell);
+        }
+        rows.push(row);
+    }
+    rows.join("\n")
+}
+
+/// The current and longest streaks and how many days of the last year had
+/// something read, above the heatmap.
+pub fn report(days: &BTreeMap<NaiveDate, usize>, today: NaiveDate, color: bool) -> String {
+    let streaks = streaks(days);
+    let plural = |n: u64| if n == 1 { "day" } else { "days" };
+    let span = |streak: Streak| {
+        if streak.first == streak.last {
+            streak.first.format("%B %-d, %Y").to_string()
+        } else {
+            format!("{} to {}", streak.first.format("%B %-d, %Y"), streak.last.format("%B %-d, %Y"))
+        }
+    };
+
+    let current = match current(&streaks, today) {
+        Some(streak) => format!("Current streak: {} {}, since {}", streak.days(), plural(streak.days()), streak.first.format("%B %-d")),
+        None => "Current streak: none, read today's entry to start one".to_string(),
+    };
+    let longest = match longest(&streaks) {
+        Some(streak) => format!("Longest streak: {} {}, {}", streak.days(), plural(streak.days()), span(streak)),
+        None => "Longest streak: none yet".to_string(),
+    };
+    let year_ago = today - Days::new(7 * WEEKS);
+    let read = days.keys().filter(|day| **day > year_ago && **day <= today).count() as u64;
+
+    format!(
+        "{}\n{}\nRead on {} {} in the last year\n\n{}",
+        current,
+        longest,
+        read,
+        plural(read),
+        heatmap(days, today, color)
+    )
+}
@@ -0,0 +1,204 @@
+use chrono::{Datelike, Local, NaiveDate};
+use daily_stoic_rs::{date, Daily, DailyStoicClient};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout, Rect};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, Paragraph, Wrap};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::HashMap;
+use std::error::Error;
+
+const WEEKDAYS: &str = "Mo Tu We Th Fr Sa Su";
+
+struct App {
+    dates: Vec<String>,
+    // index into `dates`
+    selected: usize,
+    entries: HashMap<String, Result<Daily, String>>,
+    scroll: u16,
+}
+
+impl App {
+    fn date(&self) -> &str {
+        &self.dates[self.selected]
+    }
+
+    fn select(&mut self, index: usize) {
+        self.selected = index;
+        self.scroll = 0;
+    }
+
+    fn step(&mut self, days: i64) {
+        let len = self.dates.len() as i64;
+        self.select((self.selected as i64 + days).rem_euclid(len) as usize);
+    }
+
+    // same day in the next or previous month, clamped to that month's length
+    fn step_month(&mut self, months: i32) {
+        let current = book_date(self.selected);
+        let month = (current.month0() as i32 + months).rem_euclid(12) as u32 + 1;
+        let day = current.day().min(month_len(month));
+        let target = NaiveDate::from_ymd_opt(2000, month, day).unwrap();
+        self.select(target.ordinal0() as usize);
+    }
+}
+
+// the (leap) year the book's dates are pinned to
+fn book_date(index: usize) -> NaiveDate {
+    NaiveDate::from_yo_opt(2000, index as u32 + 1).unwrap()
+}
+
+fn month_len(month: u32) -> u32 {
+    let next = NaiveDate::from_ymd_opt(2000 + month as i32 / 12, month % 12 + 1, 1).unwrap();
+    next.pred_opt().unwrap().day()
+}
+
+/// Browses the year on a month calendar, loading archived entries up front and
+/// fetching any other day when it is first selected.
+pub async fn run(client: &DailyStoicClient, use_llm: bool) -> Result<(), Box<dyn Error>> {
+    let dates = date::all_dates();
+    let today = date::today();
+
+    let mut entries = HashMap::new();
+    if let Some(archive) = client.archive() {
+        for daily in archive.all()? {
+            entries.insert(daily.date.clone(), Ok(daily));
+        }
+    }
+
+    let mut app = App {
+        selected: dates.iter().position(|d| *d == today).unwrap_or(0),
+        dates,
+        entries,
+        scroll: 0,
+    };
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, &mut app, client, use_llm).await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    client: &DailyStoicClient,
+    use_llm: bool,
+) -> Result<(), Box<dyn Error>> {
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+
+        // show the loading state first, then fetch and redraw
+        if !app.entries.contains_key(app.date()) {
+            let date = app.date().to_string();
+            let daily = client.get_entry(&date, use_llm).await;
+            app.entries.insert(date, daily.map_err(|e| e.to_string()));
+            continue;
+        }
+
+        let Event::Key(key) = event::read()? else { continue };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+            KeyCode::Left | KeyCode::Char('h') => app.step(-1),
+            KeyCode::Right | KeyCode::Char('l') => app.step(1),
+            KeyCode::Up => app.step(-7),
+            KeyCode::Down => app.step(7),
+            KeyCode::PageUp | KeyCode::Char('[') => app.step_month(-1),
+            KeyCode::PageDown | KeyCode::Char(']') => app.step_month(1),
+            KeyCode::Char('t') => {
+                let today = date::today();
+                if let Some(i) = app.dates.iter().position(|d| *d == today) {
+                    app.select(i);
+                }
+            }
+            KeyCode::Char('k') => app.scroll = app.scroll.saturating_sub(1),
+            KeyCode::Char('j') => app.scroll = app.scroll.saturating_add(1),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [calendar, entry] = Layout::horizontal([Constraint::Length(24), Constraint::Min(0)])
+        .areas(frame.area());
+    let [calendar, help] = Layout::vertical([Constraint::Length(10), Constraint::Min(0)])
+        .areas(calendar);
+
+    draw_calendar(frame, app, calendar);
+    frame.render_widget(
+        Paragraph::new("←→ day\n↑↓ week\n[ ] month\nj k scroll\nt today\nq quit")
+            .block(Block::bordered().title("Keys")),
+        help,
+    );
+    draw_entry(frame, app, entry);
+}
+
+fn draw_calendar(frame: &mut Frame, app: &App, area: Rect) {
+    let selected = book_date(app.selected);
+    let month = selected.month();
+
+    // lay the month out on this year's weekdays, Feb 29 simply follows Feb 28
+    let year = Local::now().year();
+    let offset = NaiveDate::from_ymd_opt(year, month, 1)
+        .unwrap()
+        .weekday()
+        .num_days_from_monday() as usize;
+
+    let first = NaiveDate::from_ymd_opt(2000, month, 1).unwrap().ordinal0() as usize;
+
+    let mut lines = vec![Line::from(WEEKDAYS)];
+    let mut spans = vec![Span::raw("   ".repeat(offset))];
+
+    for day in 1..=month_len(month) {
+        let index = first + day as usize - 1;
+        let mut style = Style::default();
+        if app.entries.contains_key(&app.dates[index]) {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if index == app.selected {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        spans.push(Span::styled(format!("{:>2}", day), style));
+        spans.push(Span::raw(" "));
+
+        if (offset + day as usize).is_multiple_of(7) {
+            lines.push(Line::from(std::mem::take(&mut spans)));
+        }
+    }
+    if !spans.is_empty() {
+        lines.push(Line::from(spans));
+    }
+
+    let title = selected.format("%B").to_string();
+    frame.render_widget(Paragraph::new(lines).block(Block::bordered().title(title)), area);
+}
+
+fn draw_entry(frame: &mut Frame, app: &App, area: Rect) {
+    let text = match app.entries.get(app.date()) {
+        None => Text::from("Loading..."),
+        Some(Err(e)) => Text::from(e.as_str()),
+        Some(Ok(daily)) => {
+            let bold = Style::default().add_modifier(Modifier::BOLD);
+            let mut lines = vec![
+                Line::styled(daily.title.clone(), bold),
+                Line::raw(""),
+                Line::styled(daily.quote.clone(), Style::default().add_modifier(Modifier::ITALIC)),
+                Line::raw(daily.quoter.clone()),
+                Line::raw(""),
+            ];
+            lines.extend(daily.explanation.lines().map(|l| Line::raw(l.to_string())));
+            Text::from(lines)
+        }
+    };
+
+    let paragraph = Paragraph::new(text)
+        .block(Block::bordered().title(app.date().to_string()))
+        .wrap(Wrap { trim: false })
+        .scroll((app.scroll, 0));
+    frame.render_widget(paragraph, area);
+}
@@ -0,0 +1,135 @@
+use daily_stoic_rs::http::{self, RetryPolicy};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use std::env::consts::{ARCH, EXE_SUFFIX, OS};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+const RELEASES_URL: &str = "https://api.github.com/repos/aig77/daily-stoic-rs/releases/latest";
+// lists "<sha256>  <asset name>" for every binary of a release
+const CHECKSUMS: &str = "SHA256SUMS";
+const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+#[derive(Deserialize)]
+struct Release {
+    tag_name: String,
+    assets: Vec<Asset>
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    name: String,
+    browser_download_url: String
+}
+
+/// The release binary for this platform, e.g. daily-stoic-linux-x86_64 or
+/// daily-stoic-windows-x86_64.exe.
+fn asset_name() -> String {
+    format!("daily-stoic-{}-{}{}", OS, ARCH, EXE_SUFFIX)
+}
+
+// "v1.10.0" -> [1, 10, 0], with anything after a '-' or '+' ignored
+fn version(tag: &str) -> Vec<u64> {
+    tag.trim_start_matches('v')
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+async fn get(client: &reqwest::Client, retry: &RetryPolicy, url: &str) -> Result<reqwest::Response, Box<dyn Error>> {
+    let response = http::send_with_retry(retry, || {
+        // GitHub's API turns away requests without a user agent
+        client.get(url).header("User-Agent", format!("daily-stoic/{}", VERSION))
+    }).await?;
+    if !response.status().is_success() {
+        return Err(format!("{} returned {}", url, response.status()).into());
+    }
+    Ok(response)
+}
+
+async fn download(client: &reqwest::Client, retry: &RetryPolicy, asset: &Asset) -> Result<Vec<u8>, Box<dyn Error>> {
+    let bytes = get(client, retry, &asset.browser_download_url)
+        .await?
+        .bytes()
+        .await
+        .map_err(|e| format!("Failed to download {}: {}", asset.name, e))?;
+    Ok(bytes.to_vec())
+}
+
+/// Checks the latest GitHub release and, unless `check_only`, replaces the
+/// running binary with it after verifying its SHA-256 against the release's
+/// checksum file. `DAILY_STOIC_RELEASES_URL` points it at another API endpoint.
+pub async fn run(retry: &RetryPolicy, check_only: bool) -> Result<(), Box<dyn Error>> {
+    let url = std::env::var("DAILY_STOIC_RELEASES_URL").unwrap_or_else(|_| RELEASES_URL.to_string());
+    let client = reqwest::Client::new();
+    let release: Release = get(&client, retry, &url)
+        .await?
+        .json()
+        .await
+        .map_err(|e| format!("Failed to read the release from {}: {}", url, e))?;
+
+    let latest = release.tag_name.trim_start_matches('v');
+    if version(latest) <= version(VERSION) {
+        println!("daily-stoic {} is up to date", VERSION);
+        return Ok(());
+    }
+    if check_only {
+        println!("daily-stoic {} is available (this is {}), install it with `daily-stoic self-update`", latest, VERSION);
+        return Ok(());
+    }
+
+    let find = |name: &str| release.assets.iter().find(|asset| asset.name == name);
+    let name = asset_name();
+    let binary = find(&name).ok_or_else(|| format!("Release {} has no binary for {}-{} ({})", latest, OS, ARCH, name))?;
+    let checksums = find(CHECKSUMS).ok_or_else(|| format!("Release {} has no {} to verify the download with", latest, CHECKSUMS))?;
+
+    let checksums = String::from_utf8_lossy(&download(&client, retry, checksums).await?).into_owned();
+    let expected = checksums
+        .lines()
+        .filter_map(|line| line.split_once(char::is_whitespace))
+        .find(|(_, file)| file.trim().trim_start_matches('*') == name)
+        .map(|(hash, _)| hash.to_lowercase())
+        .ok_or_else(|| format!("{} of release {} has no checksum for {}", CHECKSUMS, latest, name))?;
+
+    let bytes = download(&client, retry, binary).await?;
+    let actual: String = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+    if actual != expected {
+        return Err(format!("Checksum mismatch for {} (expected {}, got {}), not installing it", name, expected, actual).into());
+    }
+
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the daily-stoic binary: {}", e))?;
+    replace(&exe, &bytes)?;
+    println!("Updated daily-stoic {} to {} at {}", VERSION, latest, exe.display());
+    Ok(())
+}
+
+// writes next to `exe` and renames over it, so a failed write leaves the old binary
+fn replace(exe: &Path, bytes: &[u8]) -> Result<(), Box<dyn Error>> {
+    let dir = exe.parent().ok_or("The daily-stoic binary has no parent directory")?;
+    let staged = dir.join(format!(".daily-stoic-update{}", EXE_SUFFIX));
+    fs::write(&staged, bytes).map_err(|e| {
+        format!("Failed to write to {}: {} (installed system-wide? try again with sudo)", dir.display(), e)
+    })?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&staged, fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("Failed to make {} executable: {}", staged.display(), e))?;
+    }
+
+    // Windows won't replace a running executable, but will rename it
+    #[cfg(windows)]
+    {
+        let old = exe.with_extension("old.exe");
+        let _ = fs::remove_file(&old);
+        fs::rename(exe, &old).map_err(|e| format!("Failed to move {} aside: {}", exe.display(), e))?;
+    }
+
+    fs::rename(&staged, exe).map_err(|e| format!("Failed to replace {}: {}", exe.display(), e))?;
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use daily_stoic_rs::config::LlmConfig;
+use daily_stoic_rs::usage::Ledger;
+use std::collections::BTreeMap;
+use std::error::Error;
+
+#[derive(Default)]
+struct Total {
+    calls: u64,
+    prompt_tokens: u64,
+    completion_tokens: u64,
+    // None once a call of a model without a known price is in
+    cost: Option<f64>
+}
+
+fn cost(cost: Option<f64>) -> String {
+    cost.map(|cost| format!("${:.4}", cost)).unwrap_or_else(|| "?".to_string())
+}
+
+/// Prints the tokens and estimated cost per month, or per day with `by_day`,
+/// and model, then this month's spending against the budget.
+pub fn report(ledger: &Ledger, config: &LlmConfig, by_day: bool) -> Result<(), Box<dyn Error>> {
+    let records = ledger.records()?;
+    if records.is_empty() {
+        println!("No LLM calls recorded in {} yet", ledger.path().display());
+        return Ok(());
+    }
+
+    let mut totals: BTreeMap<(String, String), Total> = BTreeMap::new();
+    for record in &records {
+        // dates are YYYY-MM-DD, so the month is the first 7 characters
+        let period = if by_day { record.date.clone() } else { record.date.chars().take(7).collect() };
+        let total = totals.entry((period, record.model.clone())).or_insert_with(|| Total { cost: Some(0.0), ..Total::default() });
+        total.calls += 1;
+        total.prompt_tokens += record.prompt_tokens;
+        total.completion_tokens += record.completion_tokens;
+        total.cost = total.cost.zip(record.cost(&config.prices)).map(|(a, b)| a + b);
+    }
+
+    let width = totals.keys().map(|(_, model)| model.len()).max().unwrap_or(0).max(5);
+    println!("{:<10} {:<width$} {:>6} {:>10} {:>10} {:>10}", if by_day { "Day" } else { "Month" }, "Model", "Calls", "Input", "Output", "Cost");
+    for ((period, model), total) in &totals {
+        println!(
+            "{:<10} {:<width$} {:>6} {:>10} {:>10} {:>10}",
+            period, model, total.calls, total.prompt_tokens, total.completion_tokens, cost(total.cost)
+        );
+    }
+
+    let spent = ledger.spent_this_month(&config.prices)?;
+    match config.monthly_budget {
+        Some(budget) => println!("\n{} of the ${:.2} monthly budget spent this month", cost(Some(spent)), budget),
+        None => println!("\n{} spent this month", cost(Some(spent))),
+    }
+    if totals.values().any(|total| total.cost.is_none()) {
+        println!("Costs marked ? are for models without a known price, add them to [llm.prices]");
+    }
+    Ok(())
+}
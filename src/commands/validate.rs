@@ -0,0 +1,53 @@
+use daily_stoic_rs::{date, DailyStoicClient};
+use std::error::Error;
+
+/// What is wrong with `date`'s section of `body`, empty when it parses into a
+/// complete entry.
+fn problems(client: &DailyStoicClient, body: &str, date: &str) -> Vec<String> {
+    let raw = match client.raw_from_body(body, date) {
+        Ok(raw) => raw,
+        Err(_) => {
+            let end = client.section_end(date);
+            return vec![if end.is_empty() {
+                format!("no \"{}\" header", date)
+            } else {
+                format!("no \"{}\" header followed by a \"{}\" one", date, end)
+            }];
+        }
+    };
+    let daily = match client.parse_raw(&raw) {
+        Ok(daily) => daily,
+        Err(e) => return vec![e.to_string()],
+    };
+
+    [
+        ("title", &daily.title),
+        ("quote", &daily.quote),
+        ("author", &daily.citation.author),
+        ("explanation", &daily.explanation),
+    ]
+    .into_iter()
+    .filter(|(_, text)| text.trim().is_empty())
+    .map(|(field, _)| format!("empty {}", field))
+    .collect()
+}
+
+/// Parses every date of the year out of `body` without the LLM and lists the
+/// days that don't come out whole. Fails if any don't.
+pub fn run(client: &DailyStoicClient, body: &str) -> Result<(), Box<dyn Error>> {
+    let dates = date::all_dates();
+    let mut failed = 0;
+    for date in &dates {
+        let problems = problems(client, body, date);
+        if !problems.is_empty() {
+            failed += 1;
+            println!("{:<12} {}", date, problems.join(", "));
+        }
+    }
+
+    if failed > 0 {
+        return Err(format!("{} of {} days failed to parse", failed, dates.len()).into());
+    }
+    println!("All {} days parse", dates.len());
+    Ok(())
+}
@@ -0,0 +1,118 @@
+use daily_stoic_rs::card::{self, Style};
+use daily_stoic_rs::config::ImageConfig;
+use daily_stoic_rs::Daily;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::debug;
+
+/// "2560x1440" as width and height.
+pub fn parse_resolution(s: &str) -> Result<(u32, u32), String> {
+    let (width, height) = s.split_once(['x', 'X'])
+        .ok_or_else(|| format!("Invalid resolution \"{}\" (expected e.g. 2560x1440)", s))?;
+    let parse = |n: &str| n.trim().parse::<u32>().ok().filter(|n| (64..=16384).contains(n));
+    match (parse(width), parse(height)) {
+        (Some(width), Some(height)) => Ok((width, height)),
+        _ => Err(format!("Invalid resolution \"{}\" (expected e.g. 2560x1440)", s)),
+    }
+}
+
+// one file per day, since some desktops don't notice a changed file under the same name
+fn default_path(daily: &Daily) -> Result<PathBuf, Box<dyn Error>> {
+    let dir = dirs::data_dir().ok_or("Could not determine the data dir, pass --out")?.join("daily-stoic");
+    fs::create_dir_all(&dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+
+    // yesterday's wallpaper is no longer needed
+    if let Ok(entries) = fs::read_dir(&dir) {
+        for entry in entries.flatten() {
+            if entry.file_name().to_string_lossy().starts_with("wallpaper-") {
+                let _ = fs::remove_file(entry.path());
+            }
+        }
+    }
+    Ok(dir.join(format!("wallpaper-{}.png", daily.date.to_lowercase().replace(' ', "-"))))
+}
+
+/// Renders `daily` at `resolution` to `out` (or the data dir), sets it as the
+/// desktop wallpaper if `set` is true, and returns the path.
+pub fn run(
+    daily: &Daily,
+    config: &ImageConfig,
+    resolution: (u32, u32),
+    out: Option<&Path>,
+    set: bool,
+) -> Result<PathBuf, Box<dyn Error>> {
+    let style = Style::from_config(config)?;
+    let image = card::render(daily, resolution.0, resolution.1, &style)?;
+
+    let path = match out {
+        Some(path) => path.to_path_buf(),
+        None => default_path(daily)?,
+    };
+    image.save(&path).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    if set {
+        let path = fs::canonicalize(&path).unwrap_or(path.clone());
+        set_wallpaper(&path)?;
+    }
+    Ok(path)
+}
+
+fn try_run(program: &str, args: &[&str]) -> bool {
+    debug!("{} {}", program, args.join(" "));
+    Command::new(program).args(args).status().is_ok_and(|status| status.success())
+}
+
+#[cfg(target_os = "macos")]
+fn set_wallpaper(path: &Path) -> Result<(), Box<dyn Error>> {
+    let script = format!(
+        "tell application \"System Events\" to tell every desktop to set picture to \"{}\"",
+        path.display().to_string().replace('"', "\\\"")
+    );
+    if try_run("osascript", &["-e", &script]) { Ok(()) } else { Err("Failed to set the wallpaper with osascript".into()) }
+}
+
+#[cfg(target_os = "windows")]
+fn set_wallpaper(path: &Path) -> Result<(), Box<dyn Error>> {
+    // SPI_SETDESKWALLPAPER with SPIF_UPDATEINIFILE | SPIF_SENDCHANGE
+    let script = format!(
+        "Add-Type -TypeDefinition 'using System.Runtime.InteropServices; public class W {{ \
+        [DllImport(\"user32.dll\")] public static extern int SystemParametersInfo(int a, int b, string c, int d); }}'; \
+        [W]::SystemParametersInfo(20, 0, '{}', 3)",
+        path.display().to_string().replace('\'', "''")
+    );
+    if try_run("powershell", &["-NoProfile", "-Command", &script]) {
+        Ok(())
+    } else {
+        Err("Failed to set the wallpaper with PowerShell".into())
+    }
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+fn set_wallpaper(path: &Path) -> Result<(), Box<dyn Error>> {
+    let file = path.display().to_string();
+    let uri = format!("file://{}", file);
+    let desktop = std::env::var("XDG_CURRENT_DESKTOP").unwrap_or_default().to_lowercase();
+
+    let done = if desktop.contains("gnome") || desktop.contains("unity") || desktop.contains("budgie") {
+        // the dark variant is what GNOME shows in dark mode
+        try_run("gsettings", &["set", "org.gnome.desktop.background", "picture-uri", &uri])
+            && try_run("gsettings", &["set", "org.gnome.desktop.background", "picture-uri-dark", &uri])
+    } else if desktop.contains("kde") {
+        try_run("plasma-apply-wallpaperimage", &[&file])
+    } else if desktop.contains("xfce") {
+        try_run("xfconf-query", &["-c", "xfce4-desktop", "-p", "/backdrop/screen0/monitor0/workspace0/last-image", "-s", &file])
+    } else if std::env::var_os("SWAYSOCK").is_some() {
+        try_run("swaymsg", &["output", "*", "bg", &file, "fill"])
+    } else {
+        false
+    };
+
+    // feh works for most plain X11 window managers
+    if done || try_run("feh", &["--bg-fill", &file]) {
+        Ok(())
+    } else {
+        Err(format!("Could not set the wallpaper on this desktop ({}), set {} by hand", desktop, file).into())
+    }
+}
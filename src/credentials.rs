@@ -0,0 +1,32 @@
+//! The LLM API key in the platform credential store (Keychain, Windows
+//! Credential Manager or the Secret Service), as an alternative to keeping it in
+//! plaintext in `.env` or the config file.
+
+use crate::error::{DailyStoicError::ConfigError, Result};
+use keyring::Entry;
+
+const SERVICE: &str = "daily-stoic";
+const USER: &str = "api_key";
+
+fn entry() -> Result<Entry> {
+    Entry::new(SERVICE, USER)
+        .map_err(|e| ConfigError(format!("Failed to access the credential store: {}", e)))
+}
+
+/// The stored key, `None` if there is none or no credential store is available.
+pub fn api_key() -> Option<String> {
+    entry().ok()?.get_password().ok()
+}
+
+pub fn set_api_key(key: &str) -> Result<()> {
+    entry()?
+        .set_password(key)
+        .map_err(|e| ConfigError(format!("Failed to store the API key in the credential store: {}", e)))
+}
+
+pub fn delete_api_key() -> Result<()> {
+    match entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(ConfigError(format!("Failed to delete the API key from the credential store: {}", e))),
+    }
+}
@@ -0,0 +1,193 @@
+use chrono::{Days, NaiveDate, TimeDelta};
+use chrono::prelude::*;
+use crate::error::{DailyStoicError::ParseError, Result};
+
+// dates are handled as "%B %-d" strings, e.g. "March 5"
+const DATE_FORMAT: &str = "%B %-d";
+
+/// Today's date in book form.
+pub fn today() -> String {
+    to_book_date(Local::now().date_naive())
+}
+
+fn to_book_date(dt: NaiveDate) -> String {
+    dt.with_year(2000)
+        .unwrap() // fixed to force leap year
+        .format(DATE_FORMAT)
+        .to_string()
+}
+
+// today, tomorrow, yesterday, +N or -N days from today
+fn parse_relative(input: &str) -> Result<Option<String>> {
+    let offset: i64 = match input.to_lowercase().as_str() {
+        "today" => 0,
+        "tomorrow" => 1,
+        "yesterday" => -1,
+        s if s.starts_with('+') || s.starts_with('-') => s.parse()
+            .map_err(|e| ParseError(format!("Invalid day offset \"{}\": {}", input, e)))?,
+        _ => return Ok(None),
+    };
+
+    let out_of_range = || ParseError(format!("Day offset \"{}\" is out of range", input));
+    let offset = TimeDelta::try_days(offset).ok_or_else(out_of_range)?;
+    let dt = Local::now().date_naive().checked_add_signed(offset).ok_or_else(out_of_range)?;
+
+    Ok(Some(to_book_date(dt)))
+}
+
+// accepted absolute date formats, each parsed with the year pinned to 2000
+const INPUT_FORMATS: [&str; 4] = [
+    "%B %d %Y", // March 5, Mar 5
+    "%d %B %Y", // 5 March, 5 Mar
+    "%m/%d %Y", // 03/05
+    "%m-%d %Y", // 03-05
+];
+
+fn parse_absolute(input: &str) -> Option<NaiveDate> {
+    // full ISO dates carry their own year, which is irrelevant for the book
+    if let Ok(dt) = NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+        return dt.with_year(2000);
+    }
+
+    let full_date = format!("{} 2000", input); // assume a leap year to get all possible days
+    INPUT_FORMATS
+        .iter()
+        .find_map(|format| NaiveDate::parse_from_str(&full_date, format).ok())
+}
+
+/// Validates a date in any of the accepted forms ("March 5", "Mar 5", "5 March",
+/// "03/05", "2024-03-05") or a relative keyword (today, tomorrow, yesterday, +N, -N)
+/// and returns it normalized to "%B %-d".
+pub fn parse_date(input: &str) -> Result<String> {
+    let input = input.trim();
+
+    if let Some(date) = parse_relative(input)? {
+        return Ok(date);
+    }
+
+    let dt = parse_absolute(input)
+        .ok_or_else(|| ParseError(format!(
+            "Invalid date \"{}\" (expected e.g. March 5, Mar 5, 5 March, 03/05, 2024-03-05, today, tomorrow, yesterday, +N or -N)",
+            input
+        )))?;
+
+    Ok(dt.format(DATE_FORMAT).to_string())
+}
+
+pub fn increment_date(date: &str) -> String {
+    let full_date = format!("{} 2000", date); // assume a leap year to get all possible days
+    let dt = NaiveDate::parse_from_str(&full_date, "%B %-d %Y").unwrap(); // date is already validated 
+    let plus_one = dt + Days::new(1);
+    plus_one.format(DATE_FORMAT).to_string()
+}
+
+/// A uniformly random date out of all 366 possible days.
+pub fn random() -> String {
+    let jan_1 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    let dt = jan_1 + Days::new(rand::random_range(0..366));
+    dt.format(DATE_FORMAT).to_string()
+}
+
+/// All 366 dates in calendar order.
+pub fn all_dates() -> Vec<String> {
+    let jan_1 = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap();
+    (0..366)
+        .map(|i| (jan_1 + Days::new(i)).format(DATE_FORMAT).to_string())
+        .collect()
+}
+
+/// 1-based position of a date like "March 5" within the (leap) year, `None`
+/// when it isn't one.
+///
+/// ```
+/// use daily_stoic_rs::date::day_of_year;
+///
+/// assert_eq!(day_of_year("March 1"), Some(61));
+/// assert_eq!(day_of_year("March 5th"), None);
+/// ```
+pub fn day_of_year(date: &str) -> Option<u32> {
+    NaiveDate::parse_from_str(&format!("{} 2000", date), "%B %-d %Y").ok().map(|dt| dt.ordinal())
+}
+
+/// A validated date in a specific calendar year, `None` for February 29 outside
+/// leap years.
+pub fn in_year(date: &str, year: i32) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(&format!("{} {}", date, year), "%B %-d %Y").ok()
+}
+
+/// Validated dates from `from` through `to` inclusive, wrapping past December 31.
+pub fn range(from: &str, to: &str) -> Vec<String> {
+    let dates = all_dates();
+    let (Some(start), Some(end)) = (day_of_year(from), day_of_year(to)) else { return Vec::new() };
+    let (start, end) = (start as usize - 1, end as usize - 1);
+
+    let len = if end >= start { end - start + 1 } else { dates.len() - start + end + 1 };
+
+    dates.iter().cycle().skip(start).take(len).cloned().collect()
+}
+
+/// The next `n` dates starting today, wrapping past December 31.
+pub fn upcoming(n: usize) -> Vec<String> {
+    following(&today(), n)
+}
+
+/// The `n` dates starting with a validated `date`, wrapping past December 31.
+///
+/// ```
+/// use daily_stoic_rs::date::following;
+///
+/// assert_eq!(following("December 30", 3), ["December 30", "December 31", "January 1"]);
+/// ```
+pub fn following(date: &str, n: usize) -> Vec<String> {
+    let dates = all_dates();
+    let Some(start) = day_of_year(date) else { return Vec::new() };
+    let start = start as usize - 1;
+    dates.iter().cycle().skip(start).take(n.min(dates.len())).cloned().collect()
+}
+
+/// The validated dates of a month given by name or abbreviation, e.g. "March" or "mar".
+pub fn month(input: &str) -> Result<Vec<String>> {
+    let month: Month = input.trim().parse()
+        .map_err(|_| ParseError(format!("Invalid month \"{}\" (expected e.g. March or Mar)", input)))?;
+    let name = format!("{} ", month.name());
+    Ok(all_dates().into_iter().filter(|date| date.starts_with(&name)).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_every_input_format() {
+        for input in ["March 5", "Mar 5", "5 March", "5 Mar", "03/05", "3/5", "03-05", "2024-03-05", "1999-03-05"] {
+            assert_eq!(parse_date(input).unwrap(), "March 5", "{}", input);
+        }
+    }
+
+    #[test]
+    fn accepts_february_29() {
+        assert_eq!(parse_date("February 29").unwrap(), "February 29");
+        assert_eq!(parse_date("02/29").unwrap(), "February 29");
+        assert_eq!(parse_date("2024-02-29").unwrap(), "February 29");
+    }
+
+    #[test]
+    fn rejects_days_that_do_not_exist() {
+        for input in ["February 30", "April 31", "13/01", "00/10", "March 0", "2023-02-29", "Smarch 5", ""] {
+            assert!(parse_date(input).is_err(), "{}", input);
+        }
+    }
+
+    #[test]
+    fn ignores_case_and_surrounding_space() {
+        for input in ["march 5", "MARCH 5", "mAr 5", "5 MAR", "  March 5  "] {
+            assert_eq!(parse_date(input).unwrap(), "March 5", "{}", input);
+        }
+    }
+
+    #[test]
+    fn rejects_day_offsets_out_of_range() {
+        assert!(parse_date("+999999999999999").is_err());
+        assert!(parse_date("+1x").is_err());
+    }
+}
@@ -0,0 +1,59 @@
+//! Word-level diffs between an entry as extracted and as cleaned up, to see
+//! what the LLM changed.
+
+use crate::Daily;
+use crate::theme::Style;
+use similar::{ChangeTag, TextDiff};
+
+/// `old` and `new` interleaved word by word. Removed words are red and added
+/// ones green with `color`, and marked git style without it.
+///
+/// ```
+/// use daily_stoic_rs::diff;
+///
+/// assert_eq!(diff::words("the quick fox", "the slow fox", false), "the [-quick-]{+slow+} fox");
+/// ```
+pub fn words(old: &str, new: &str, color: bool) -> String {
+    let removed = Style::parse("red").unwrap_or_default();
+    let added = Style::parse("green").unwrap_or_default();
+
+    // runs of the same tag, so a rewritten phrase gets one marker instead of one per word
+    let mut runs: Vec<(ChangeTag, String)> = Vec::new();
+    for change in TextDiff::from_words(old, new).iter_all_changes() {
+        match runs.last_mut() {
+            Some((tag, text)) if *tag == change.tag() => text.push_str(change.value()),
+            _ => runs.push((change.tag(), change.value().to_string())),
+        }
+    }
+
+    runs.into_iter()
+        .map(|(tag, text)| match (tag, color) {
+            (ChangeTag::Equal, _) => text,
+            (ChangeTag::Delete, true) => removed.paint(&text),
+            (ChangeTag::Insert, true) => added.paint(&text),
+            (ChangeTag::Delete, false) => format!("[-{}-]", text),
+            (ChangeTag::Insert, false) => format!("{{+{}+}}", text),
+        })
+        .collect()
+}
+
+/// The title, quote, quoter and explanation of `raw` diffed against `cleaned`,
+/// with unchanged fields noted as such.
+pub fn render(raw: &Daily, cleaned: &Daily, color: bool) -> String {
+    let fields = [
+        ("Title", &raw.title, &cleaned.title),
+        ("Quote", &raw.quote, &cleaned.quote),
+        ("Quoter", &raw.quoter, &cleaned.quoter),
+        ("Explanation", &raw.explanation, &cleaned.explanation),
+    ];
+
+    let mut out = vec![raw.date.clone()];
+    for (label, old, new) in fields {
+        if old == new {
+            out.push(format!("\n{} (unchanged)", label));
+        } else {
+            out.push(format!("\n{}\n{}", label, words(old, new, color)));
+        }
+    }
+    out.join("\n")
+}
@@ -0,0 +1,45 @@
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, DailyStoicError>;
+
+#[derive(Debug, Error)]
+pub enum DailyStoicError {
+    /// The source page or an API could not be reached.
+    #[error("{0}")]
+    NetworkError(String),
+
+    /// The body was fetched but holds no section for the date.
+    #[error("No match found for {date}")]
+    DateNotFound { date: String },
+
+    /// A date argument or an entry's text could not be parsed.
+    #[error("{0}")]
+    ParseError(String),
+
+    /// The LLM rejected the request or replied with something unusable.
+    #[error("{0}")]
+    LlmError(String),
+
+    /// Missing or invalid settings.
+    #[error("{0}")]
+    ConfigError(String),
+
+    /// Reading or writing the cache, archive or other local files failed.
+    #[error("{0}")]
+    StorageError(String),
+}
+
+impl DailyStoicError {
+    /// Process exit code for scripts and cron jobs to branch on (2 is left to
+    /// clap's usage errors).
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            DailyStoicError::NetworkError(_) => 3,
+            DailyStoicError::DateNotFound { .. } => 4,
+            DailyStoicError::ParseError(_) => 5,
+            DailyStoicError::LlmError(_) => 6,
+            DailyStoicError::ConfigError(_) => 7,
+            DailyStoicError::StorageError(_) => 8,
+        }
+    }
+}
@@ -0,0 +1,10 @@
+//! Writing entries out for other tools.
+
+pub mod anki;
+pub mod csv;
+pub mod epub;
+pub mod fortune;
+pub mod ics;
+pub mod notion;
+pub mod obsidian;
+pub mod pdf;
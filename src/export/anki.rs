@@ -0,0 +1,216 @@
+//! Anki decks with the quote on the front and the attribution, title and
+//! explanation on the back, as an `.apkg` package or a TSV file for Anki's
+//! plain text import.
+
+use crate::Daily;
+use crate::error::{DailyStoicError::StorageError, Result};
+use rusqlite::{params, Connection};
+use serde_json::json;
+use sha1::{Digest, Sha1};
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use zip::write::SimpleFileOptions;
+
+// fixed so importing again updates the same deck and note type instead of adding new ones
+const DECK_ID: i64 = 1_716_000_000_001;
+const MODEL_ID: i64 = 1_716_000_000_002;
+const DECK_NAME: &str = "The Daily Stoic";
+
+const FIELDS: [&str; 5] = ["Quote", "Author", "Title", "Date", "Explanation"];
+const FRONT: &str = "<div class=quote>{{Quote}}</div>";
+const BACK: &str = "{{FrontSide}}<hr id=answer><div class=author>{{Author}}</div>\
+    <div class=title>{{Title}} &middot; {{Date}}</div><div class=explanation>{{Explanation}}</div>";
+const CSS: &str = ".card { font-family: Georgia, serif; font-size: 20px; text-align: center; }\n\
+    .quote { font-style: italic; }\n\
+    .title { font-weight: bold; margin-top: 1em; }\n\
+    .explanation { text-align: left; font-size: 16px; margin-top: 1em; }";
+
+/// Writes `dailies` to `path`, as a package if it ends in `.apkg` and as TSV otherwise.
+pub fn export(dailies: &[Daily], path: &Path) -> Result<()> {
+    if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("apkg")) {
+        write_apkg(dailies, path)
+    } else {
+        write_tsv(dailies, path)
+    }
+}
+
+fn fields(daily: &Daily) -> [String; 5] {
+    [
+        html(&daily.quote),
+        html(daily.quoter.trim_start_matches('—')),
+        html(&daily.title),
+        html(&daily.date),
+        html(&daily.explanation),
+    ]
+}
+
+fn tags(daily: &Daily) -> String {
+    let author = daily.citation.author.replace(' ', "_");
+    if author.is_empty() { "daily-stoic".to_string() } else { format!("daily-stoic {}", author) }
+}
+
+// fields are HTML, with paragraphs kept as line breaks
+fn html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('\t', " ")
+        .replace('\n', "<br>")
+}
+
+/// One note per line, using the header directives Anki 2.1.54+ reads on import.
+pub fn write_tsv(dailies: &[Daily], path: &Path) -> Result<()> {
+    let mut tsv = String::from("#separator:tab\n#html:true\n#tags column:3\n");
+    for daily in dailies {
+        let [quote, author, title, date, explanation] = fields(daily);
+        let back = format!("{}<br><b>{}</b> &middot; {}<br><br>{}", author, title, date, explanation);
+        tsv.push_str(&format!("{}\t{}\t{}\n", quote, back, tags(daily)));
+    }
+
+    fs::write(path, tsv).map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// An `.apkg` package: a zipped Anki collection holding one deck and note type.
+pub fn write_apkg(dailies: &[Daily], path: &Path) -> Result<()> {
+    let collection = std::env::temp_dir().join(format!("daily-stoic-anki-{}.anki2", std::process::id()));
+    let _ = fs::remove_file(&collection);
+
+    let result = write_collection(dailies, &collection)
+        .map_err(|e| StorageError(format!("Failed to build the Anki collection: {}", e)))
+        .and_then(|()| zip_package(&collection, path));
+
+    let _ = fs::remove_file(&collection);
+    result
+}
+
+fn zip_package(collection: &Path, path: &Path) -> Result<()> {
+    let error = |e: &dyn std::fmt::Display| StorageError(format!("Failed to write {}: {}", path.display(), e));
+
+    let data = fs::read(collection).map_err(|e| error(&e))?;
+    let file = File::create(path).map_err(|e| error(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    zip.start_file("collection.anki2", options).map_err(|e| error(&e))?;
+    zip.write_all(&data).map_err(|e| error(&e))?;
+    // no media files
+    zip.start_file("media", options).map_err(|e| error(&e))?;
+    zip.write_all(b"{}").map_err(|e| error(&e))?;
+    zip.finish().map_err(|e| error(&e))?;
+    Ok(())
+}
+
+fn write_collection(dailies: &[Daily], path: &Path) -> rusqlite::Result<()> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default();
+    let (secs, millis) = (now.as_secs() as i64, now.as_millis() as i64);
+
+    let conn = Connection::open(path)?;
+    conn.execute_batch(SCHEMA)?;
+
+    let field_defs: Vec<_> = FIELDS
+        .iter()
+        .enumerate()
+        .map(|(i, name)| json!({
+            "name": name, "ord": i, "sticky": false, "rtl": false,
+            "font": "Arial", "size": 20, "media": []
+        }))
+        .collect();
+    let models = json!({ MODEL_ID.to_string(): {
+        "id": MODEL_ID, "name": "Daily Stoic", "type": 0, "mod": secs, "usn": -1,
+        "sortf": 0, "did": DECK_ID, "flds": field_defs, "css": CSS,
+        "tmpls": [{
+            "name": "Card 1", "ord": 0, "qfmt": FRONT, "afmt": BACK,
+            "did": null, "bqfmt": "", "bafmt": ""
+        }],
+        "latexPre": "", "latexPost": "", "tags": [], "vers": [], "req": [[0, "all", [0]]]
+    }});
+    let deck = |id: i64, name: &str| json!({
+        "id": id, "name": name, "desc": "", "mod": secs, "usn": -1, "dyn": 0, "conf": 1,
+        "collapsed": false, "extendNew": 10, "extendRev": 50,
+        "newToday": [0, 0], "revToday": [0, 0], "lrnToday": [0, 0], "timeToday": [0, 0]
+    });
+    let decks = json!({ "1": deck(1, "Default"), DECK_ID.to_string(): deck(DECK_ID, DECK_NAME) });
+    let dconf = json!({ "1": {
+        "id": 1, "name": "Default", "mod": 0, "usn": 0, "maxTaken": 60, "autoplay": true,
+        "timer": 0, "replayq": true, "dyn": false,
+        "new": { "delays": [1, 10], "ints": [1, 4, 7], "initialFactor": 2500, "order": 1, "perDay": 20 },
+        "rev": { "perDay": 200, "ease4": 1.3, "fuzz": 0.05, "maxIvl": 36500, "ivlFct": 1 },
+        "lapse": { "delays": [10], "mult": 0, "minInt": 1, "leechFails": 8, "leechAction": 0 }
+    }});
+    let conf = json!({
+        "activeDecks": [1], "curDeck": 1, "newSpread": 0, "collapseTime": 1200,
+        "timeLim": 0, "estTimes": true, "dueCounts": true, "curModel": MODEL_ID.to_string(),
+        "nextPos": dailies.len() + 1, "sortType": "noteFld", "sortBackwards": false
+    });
+
+    conn.execute(
+        "INSERT INTO col VALUES (1, ?1, ?2, ?2, 11, 0, 0, 0, ?3, ?4, ?5, ?6, '{}')",
+        params![secs, millis, conf.to_string(), models.to_string(), decks.to_string(), dconf.to_string()],
+    )?;
+
+    for (i, daily) in dailies.iter().enumerate() {
+        let note = fields(daily);
+        let id = millis + i as i64;
+        let guid = format!("daily-stoic-{}", daily.date.replace(' ', "-"));
+
+        conn.execute(
+            "INSERT INTO notes VALUES (?1, ?2, ?3, ?4, -1, ?5, ?6, ?7, ?8, 0, '')",
+            params![
+                id, guid, MODEL_ID, secs,
+                format!(" {} ", tags(daily)),
+                note.join("\x1f"),
+                note[0],
+                checksum(&note[0])
+            ],
+        )?;
+        conn.execute(
+            "INSERT INTO cards VALUES (?1, ?2, ?3, 0, ?4, -1, 0, 0, ?5, 0, 0, 0, 0, 0, 0, 0, 0, '')",
+            params![id, id, DECK_ID, secs, i as i64 + 1],
+        )?;
+    }
+
+    Ok(())
+}
+
+// first 8 hex digits of the sort field's sha1, used by Anki to spot duplicates
+fn checksum(field: &str) -> i64 {
+    let digest = Sha1::digest(field.as_bytes());
+    u32::from_be_bytes([digest[0], digest[1], digest[2], digest[3]]) as i64
+}
+
+// the schema of an Anki 2.1 (schema 11) collection
+const SCHEMA: &str = "
+CREATE TABLE col (
+    id integer primary key, crt integer not null, mod integer not null, scm integer not null,
+    ver integer not null, dty integer not null, usn integer not null, ls integer not null,
+    conf text not null, models text not null, decks text not null, dconf text not null,
+    tags text not null
+);
+CREATE TABLE notes (
+    id integer primary key, guid text not null, mid integer not null, mod integer not null,
+    usn integer not null, tags text not null, flds text not null, sfld integer not null,
+    csum integer not null, flags integer not null, data text not null
+);
+CREATE TABLE cards (
+    id integer primary key, nid integer not null, did integer not null, ord integer not null,
+    mod integer not null, usn integer not null, type integer not null, queue integer not null,
+    due integer not null, ivl integer not null, factor integer not null, reps integer not null,
+    lapses integer not null, left integer not null, odue integer not null, odid integer not null,
+    flags integer not null, data text not null
+);
+CREATE TABLE revlog (
+    id integer primary key, cid integer not null, usn integer not null, ease integer not null,
+    ivl integer not null, lastIvl integer not null, factor integer not null, time integer not null,
+    type integer not null
+);
+CREATE TABLE graves (usn integer not null, oid integer not null, type integer not null);
+CREATE INDEX ix_notes_usn ON notes (usn);
+CREATE INDEX ix_cards_usn ON cards (usn);
+CREATE INDEX ix_revlog_usn ON revlog (usn);
+CREATE INDEX ix_cards_nid ON cards (nid);
+CREATE INDEX ix_cards_sched ON cards (did, queue, due);
+CREATE INDEX ix_revlog_cid ON revlog (cid);
+CREATE INDEX ix_notes_csum ON notes (csum);
+";
@@ -0,0 +1,30 @@
+//! A CSV file of the year with a row per entry, for spreadsheets and other tools.
+
+use crate::Daily;
+use crate::error::{DailyStoicError::StorageError, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+const HEADER: [&str; 6] = ["date", "title", "quote", "quoter", "explanation", "tags"];
+
+/// Writes `dailies` to `path`, each with its themes from `tags` comma
+/// separated in the last column.
+pub fn export(dailies: &[Daily], tags: &HashMap<String, Vec<String>>, path: &Path) -> Result<()> {
+    let failed = |e: csv::Error| StorageError(format!("Failed to write {}: {}", path.display(), e));
+    let mut writer = csv::Writer::from_path(path).map_err(failed)?;
+    writer.write_record(HEADER).map_err(failed)?;
+    for daily in dailies {
+        let tags = tags.get(&daily.date).map(|tags| tags.join(", ")).unwrap_or_default();
+        writer
+            .write_record([
+                daily.date.as_str(),
+                daily.title.as_str(),
+                daily.quote.trim(),
+                daily.quoter.as_str(),
+                daily.explanation.trim(),
+                tags.as_str(),
+            ])
+            .map_err(failed)?;
+    }
+    writer.flush().map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+}
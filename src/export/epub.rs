@@ -0,0 +1,200 @@
+//! An EPUB 3 book of entries for e-readers, with a chapter per month and a
+//! section per day, plus an NCX table of contents for older readers.
+
+use crate::error::{DailyStoicError::StorageError, Result};
+use crate::render::escape_html;
+use crate::Daily;
+use chrono::Utc;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use zip::write::SimpleFileOptions;
+use zip::CompressionMethod;
+
+const TITLE: &str = "The Daily Stoic";
+// stable so readers treat a re-export as the same book
+const ID: &str = "urn:daily-stoic:epub";
+
+const CONTAINER: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<container version="1.0" xmlns="urn:oasis:names:tc:opendocument:xmlns:container">
+  <rootfiles>
+    <rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/>
+  </rootfiles>
+</container>
+"#;
+
+const CSS: &str = "body { font-family: Georgia, serif; line-height: 1.5; }
+h1 { text-align: center; margin: 2em 0; }
+section { page-break-before: always; }
+.date { font-variant: small-caps; color: #666; margin-bottom: 0; }
+h2 { margin-top: 0.2em; }
+blockquote { font-style: italic; margin: 1.5em 2em; }
+.quoter { text-align: right; font-style: normal; font-size: 0.9em; }
+";
+
+// entries grouped by month in the order they come
+struct Chapter<'a> {
+    month: &'a str,
+    file: String,
+    dailies: Vec<&'a Daily>
+}
+
+fn chapters(dailies: &[Daily]) -> Vec<Chapter<'_>> {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    for daily in dailies {
+        let month = daily.date.split(' ').next().unwrap_or(&daily.date);
+        match chapters.last_mut() {
+            Some(chapter) if chapter.month == month => chapter.dailies.push(daily),
+            _ => chapters.push(Chapter {
+                month,
+                file: format!("month-{:02}.xhtml", chapters.len() + 1),
+                dailies: vec![daily]
+            }),
+        }
+    }
+    chapters
+}
+
+// "March 5" -> "march-5", the section's id
+fn anchor(daily: &Daily) -> String {
+    daily.date.to_lowercase().replace(' ', "-")
+}
+
+fn xhtml(title: &str, body: &str) -> String {
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<!DOCTYPE html>\n\
+        <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" lang=\"en\" xml:lang=\"en\">\n\
+        <head>\n  <title>{}</title>\n  <link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n</head>\n\
+        <body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        body
+    )
+}
+
+fn chapter_xhtml(chapter: &Chapter) -> String {
+    let mut body = format!("<h1>{}</h1>\n", escape_html(chapter.month));
+    for daily in &chapter.dailies {
+        let paragraphs: String = daily.explanation
+            .split("\n\n")
+            .filter(|p| !p.trim().is_empty())
+            .map(|p| format!("  <p>{}</p>\n", escape_html(p.trim())))
+            .collect();
+        body.push_str(&format!(
+            "<section id=\"{}\">\n  <p class=\"date\">{}</p>\n  <h2>{}</h2>\n  \
+            <blockquote>\n    <p>{}</p>\n    <p class=\"quoter\">{}</p>\n  </blockquote>\n{}</section>\n",
+            anchor(daily),
+            escape_html(&daily.date),
+            escape_html(&daily.title),
+            escape_html(&daily.quote).replace('\n', "<br/>\n    "),
+            escape_html(&daily.quoter),
+            paragraphs
+        ));
+    }
+    xhtml(chapter.month, &body)
+}
+
+fn nav_xhtml(chapters: &[Chapter]) -> String {
+    let mut body = String::from("<nav epub:type=\"toc\" id=\"toc\">\n  <h1>Contents</h1>\n  <ol>\n");
+    for chapter in chapters {
+        body.push_str(&format!("    <li><a href=\"{}\">{}</a>\n      <ol>\n", chapter.file, escape_html(chapter.month)));
+        for daily in &chapter.dailies {
+            body.push_str(&format!(
+                "        <li><a href=\"{}#{}\">{}: {}</a></li>\n",
+                chapter.file,
+                anchor(daily),
+                escape_html(&daily.date),
+                escape_html(&daily.title)
+            ));
+        }
+        body.push_str("      </ol>\n    </li>\n");
+    }
+    body.push_str("  </ol>\n</nav>\n");
+    xhtml("Contents", &body)
+}
+
+fn toc_ncx(chapters: &[Chapter]) -> String {
+    let mut order = 0;
+    let mut points = String::new();
+    for chapter in chapters {
+        order += 1;
+        points.push_str(&format!(
+            "    <navPoint id=\"nav-{}\" playOrder=\"{}\">\n      <navLabel><text>{}</text></navLabel>\n      <content src=\"{}\"/>\n",
+            order, order, escape_html(chapter.month), chapter.file
+        ));
+        for daily in &chapter.dailies {
+            order += 1;
+            points.push_str(&format!(
+                "      <navPoint id=\"nav-{}\" playOrder=\"{}\">\n        <navLabel><text>{}: {}</text></navLabel>\n        \
+                <content src=\"{}#{}\"/>\n      </navPoint>\n",
+                order, order, escape_html(&daily.date), escape_html(&daily.title), chapter.file, anchor(daily)
+            ));
+        }
+        points.push_str("    </navPoint>\n");
+    }
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<ncx xmlns=\"http://www.daisy.org/z3986/2005/ncx/\" version=\"2005-1\">\n  \
+        <head>\n    <meta name=\"dtb:uid\" content=\"{}\"/>\n  </head>\n  <docTitle><text>{}</text></docTitle>\n  \
+        <navMap>\n{}  </navMap>\n</ncx>\n",
+        ID, TITLE, points
+    )
+}
+
+fn content_opf(chapters: &[Chapter]) -> String {
+    let manifest: String = chapters
+        .iter()
+        .enumerate()
+        .map(|(i, c)| format!("    <item id=\"chapter-{}\" href=\"{}\" media-type=\"application/xhtml+xml\"/>\n", i + 1, c.file))
+        .collect();
+    let spine: String = (1..=chapters.len())
+        .map(|i| format!("    <itemref idref=\"chapter-{}\"/>\n", i))
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+        <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" unique-identifier=\"book-id\">\n  \
+        <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n    \
+        <dc:identifier id=\"book-id\">{}</dc:identifier>\n    <dc:title>{}</dc:title>\n    \
+        <dc:creator>Ryan Holiday and Stephen Hanselman</dc:creator>\n    <dc:language>en</dc:language>\n    \
+        <meta property=\"dcterms:modified\">{}</meta>\n  </metadata>\n  <manifest>\n    \
+        <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n    \
+        <item id=\"ncx\" href=\"toc.ncx\" media-type=\"application/x-dtbncx+xml\"/>\n    \
+        <item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>\n{}  </manifest>\n  \
+        <spine toc=\"ncx\">\n{}  </spine>\n</package>\n",
+        ID,
+        TITLE,
+        Utc::now().format("%Y-%m-%dT%H:%M:%SZ"),
+        manifest,
+        spine
+    )
+}
+
+/// Writes `dailies` to `path` as an EPUB, a chapter for each month they span.
+pub fn export(dailies: &[Daily], path: &Path) -> Result<()> {
+    let error = |e: &dyn std::fmt::Display| StorageError(format!("Failed to write {}: {}", path.display(), e));
+    let chapters = chapters(dailies);
+
+    let file = File::create(path).map_err(|e| error(&e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    // the mimetype comes first and uncompressed so readers can sniff it
+    zip.start_file("mimetype", options.compression_method(CompressionMethod::Stored)).map_err(|e| error(&e))?;
+    zip.write_all(b"application/epub+zip").map_err(|e| error(&e))?;
+
+    let mut files = vec![
+        ("META-INF/container.xml".to_string(), CONTAINER.to_string()),
+        ("OEBPS/content.opf".to_string(), content_opf(&chapters)),
+        ("OEBPS/nav.xhtml".to_string(), nav_xhtml(&chapters)),
+        ("OEBPS/toc.ncx".to_string(), toc_ncx(&chapters)),
+        ("OEBPS/style.css".to_string(), CSS.to_string()),
+    ];
+    files.extend(chapters.iter().map(|c| (format!("OEBPS/{}", c.file), chapter_xhtml(c))));
+
+    for (name, content) in files {
+        zip.start_file(name, options).map_err(|e| error(&e))?;
+        zip.write_all(content.as_bytes()).map_err(|e| error(&e))?;
+    }
+    zip.finish().map_err(|e| error(&e))?;
+    Ok(())
+}
@@ -0,0 +1,75 @@
+//! A fortune(6) database: the quotes separated by `%` lines, with the `.dat`
+//! index strfile(1) would build next to them.
+
+use crate::Daily;
+use crate::error::{DailyStoicError::StorageError, Result};
+use std::fs;
+use std::path::Path;
+
+// strfile's header version and the width classic cookies are filled to
+const VERSION: u32 = 2;
+const WIDTH: usize = 72;
+
+/// Writes the quotes of `dailies` to `path` and their index to `path.dat`.
+pub fn export(dailies: &[Daily], path: &Path) -> Result<()> {
+    let cookies: Vec<String> = dailies.iter().map(cookie).collect();
+    let mut dat = path.as_os_str().to_owned();
+    dat.push(".dat");
+
+    let write = |path: &Path, bytes: &[u8]| {
+        fs::write(path, bytes).map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+    };
+    write(path, database(&cookies).as_bytes())?;
+    write(Path::new(&dat), &index(&cookies))
+}
+
+/// One quote with its attribution indented below it, like the classic
+/// databases have them.
+pub fn cookie(daily: &Daily) -> String {
+    let quote: Vec<String> = daily
+        .quote
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .flat_map(|line| textwrap::wrap(line, WIDTH).into_iter().map(|l| l.into_owned()).collect::<Vec<_>>())
+        // a lone % would end the cookie early
+        .map(|line| if line == "%" { " %".to_string() } else { line })
+        .collect();
+
+    let citation = &daily.citation;
+    let source: Vec<&str> = [Some(citation.author.as_str()), citation.work.as_deref(), citation.section.as_deref()]
+        .into_iter()
+        .flatten()
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    format!("{}\n\t\t-- {}\n", quote.join("\n"), source.join(", "))
+}
+
+fn database(cookies: &[String]) -> String {
+    cookies.iter().map(|cookie| format!("{}%\n", cookie)).collect()
+}
+
+// the header (version, count, longest, shortest, flags, delimiter) and each
+// cookie's offset plus the end of the file, all big-endian as strfile writes them
+fn index(cookies: &[String]) -> Vec<u8> {
+    let lengths: Vec<u32> = cookies.iter().map(|cookie| cookie.len() as u32).collect();
+    let header = [
+        VERSION,
+        cookies.len() as u32,
+        lengths.iter().copied().max().unwrap_or(0),
+        lengths.iter().copied().min().unwrap_or(0),
+        0,
+    ];
+
+    let mut bytes: Vec<u8> = header.iter().flat_map(|n| n.to_be_bytes()).collect();
+    bytes.extend([b'%', 0, 0, 0]);
+
+    let mut offset = 0u32;
+    bytes.extend(offset.to_be_bytes());
+    for length in lengths {
+        offset += length + "%\n".len() as u32;
+        bytes.extend(offset.to_be_bytes());
+    }
+    bytes
+}
@@ -0,0 +1,75 @@
+//! An iCalendar file with a yearly all-day event per entry, for calendar apps.
+
+use crate::error::{DailyStoicError::StorageError, Result};
+use crate::{date, Daily};
+use chrono::{Datelike, Utc};
+use std::fs;
+use std::path::Path;
+
+// lines longer than this many octets are folded, as RFC 5545 requires
+const MAX_LINE: usize = 75;
+
+/// Writes `dailies` to `path` as events recurring every year from `year`,
+/// with February 29 starting in the next leap year.
+pub fn export(dailies: &[Daily], path: &Path, year: i32) -> Result<()> {
+    fs::write(path, calendar(dailies, year))
+        .map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+pub fn calendar(dailies: &[Daily], year: i32) -> String {
+    let stamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//daily-stoic-rs//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "X-WR-CALNAME:The Daily Stoic".to_string(),
+    ];
+
+    for daily in dailies {
+        let Some(start) = (year..year + 8).find_map(|y| date::in_year(&daily.date, y)) else { continue };
+        let end = start.succ_opt().unwrap_or(start);
+        let description = format!("{}\n{}", daily.quote.trim(), daily.quoter);
+
+        lines.extend([
+            "BEGIN:VEVENT".to_string(),
+            format!("UID:daily-stoic-{}@daily-stoic-rs", daily.date.to_lowercase().replace(' ', "-")),
+            format!("DTSTAMP:{}", stamp),
+            format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")),
+            format!("DTEND;VALUE=DATE:{}", end.format("%Y%m%d")),
+            // keeps February 29 on leap years instead of moving it
+            format!("RRULE:FREQ=YEARLY;BYMONTH={};BYMONTHDAY={}", start.month(), start.day()),
+            format!("SUMMARY:{}", escape(&daily.title)),
+            format!("DESCRIPTION:{}", escape(&description)),
+            "TRANSP:TRANSPARENT".to_string(),
+            "END:VEVENT".to_string(),
+        ]);
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    lines.iter().map(|line| fold(line)).collect()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\r', "")
+        .replace('\n', "\\n")
+}
+
+// splits at char boundaries into CRLF-terminated lines, continuations starting with a space
+fn fold(line: &str) -> String {
+    let mut folded = String::new();
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > MAX_LINE {
+            folded.push_str("\r\n ");
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded.push_str("\r\n");
+    folded
+}
@@ -0,0 +1,119 @@
+//! Adding entries as pages of a Notion database.
+
+use crate::config::NotionConfig;
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::Daily;
+use chrono::NaiveDate;
+use serde_json::{json, Value};
+
+const API: &str = "https://api.notion.com/v1";
+const NOTION_VERSION: &str = "2022-06-28";
+// Notion rejects rich text objects longer than this
+const MAX_TEXT: usize = 2000;
+
+/// Where and how to create pages, with the property names of the database.
+pub struct Notion<'a> {
+    token: &'a str,
+    database_id: &'a str,
+    config: &'a NotionConfig,
+    retry: RetryPolicy
+}
+
+impl<'a> Notion<'a> {
+    pub fn new(config: &'a NotionConfig, retry: RetryPolicy) -> Result<Self> {
+        let token = config.token
+            .as_deref()
+            .ok_or_else(|| ConfigError("No Notion token configured (notion.token)".to_string()))?;
+        let database_id = config.database_id
+            .as_deref()
+            .ok_or_else(|| ConfigError("No Notion database configured (notion.database_id)".to_string()))?;
+
+        Ok(Notion { token, database_id, config, retry })
+    }
+
+    fn property(&self, name: &'a Option<String>, default: &'a str) -> &'a str {
+        name.as_deref().unwrap_or(default)
+    }
+
+    async fn post(&self, path: &str, body: &Value) -> Result<Value> {
+        let client = reqwest::Client::new();
+        let url = format!("{}/{}", API, path);
+        let response = http::send_with_retry(&self.retry, || {
+            client
+                .post(&url)
+                .bearer_auth(self.token)
+                .header("Notion-Version", NOTION_VERSION)
+                .json(body)
+        }).await?;
+
+        let status = response.status();
+        let json: Value = response.json()
+            .await
+            .map_err(|e| NetworkError(format!("Failed to parse the Notion response: {}", e)))?;
+
+        if !status.is_success() {
+            let message = json["message"].as_str().unwrap_or("no message");
+            return Err(NetworkError(format!("Notion API request failed with status {}: {}", status, message)));
+        }
+        Ok(json)
+    }
+
+    /// Whether the database already has a page dated `day`.
+    pub async fn exists(&self, day: NaiveDate) -> Result<bool> {
+        let body = json!({
+            "filter": {
+                "property": self.property(&self.config.date_property, "Date"),
+                "date": { "equals": day.format("%Y-%m-%d").to_string() }
+            },
+            "page_size": 1
+        });
+        let json = self.post(&format!("databases/{}/query", self.database_id), &body).await?;
+        Ok(json["results"].as_array().is_some_and(|results| !results.is_empty()))
+    }
+
+    /// Creates a page for `daily` dated `day` and returns its url.
+    pub async fn create_page(&self, daily: &Daily, day: NaiveDate) -> Result<String> {
+        let mut properties = serde_json::Map::new();
+        properties.insert(
+            self.property(&self.config.title_property, "Name").to_string(),
+            json!({ "title": rich_text(&daily.title) }),
+        );
+        properties.insert(
+            self.property(&self.config.date_property, "Date").to_string(),
+            json!({ "date": { "start": day.format("%Y-%m-%d").to_string() } }),
+        );
+        properties.insert(
+            self.property(&self.config.author_property, "Author").to_string(),
+            json!({ "rich_text": rich_text(&daily.citation.author) }),
+        );
+
+        let mut children = vec![
+            json!({ "object": "block", "type": "quote", "quote": { "rich_text": rich_text(&daily.quote) } }),
+            json!({ "object": "block", "type": "paragraph", "paragraph": { "rich_text": rich_text(&daily.quoter) } }),
+        ];
+        for paragraph in daily.explanation.split("\n\n").filter(|p| !p.trim().is_empty()) {
+            children.push(json!({
+                "object": "block", "type": "paragraph", "paragraph": { "rich_text": rich_text(paragraph) }
+            }));
+        }
+
+        let body = json!({
+            "parent": { "database_id": self.database_id },
+            "properties": properties,
+            "children": children
+        });
+        let json = self.post("pages", &body).await?;
+        Ok(json["url"].as_str().unwrap_or_default().to_string())
+    }
+}
+
+// plain text split into as many rich text objects as the length limit requires
+fn rich_text(text: &str) -> Value {
+    let chars: Vec<char> = text.chars().collect();
+    let parts: Vec<Value> = chars
+        .chunks(MAX_TEXT)
+        .map(|chunk| json!({ "type": "text", "text": { "content": chunk.iter().collect::<String>() } }))
+        .collect();
+    Value::Array(parts)
+}
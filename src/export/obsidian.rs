@@ -0,0 +1,104 @@
+//! Adding entries to Obsidian daily notes.
+//!
+//! The entry is written between marker comments so running the export again
+//! replaces the section instead of appending a second copy, and a few keys are
+//! set in the note's YAML frontmatter for Dataview style queries.
+
+use crate::config::ObsidianConfig;
+use crate::error::{DailyStoicError::{ConfigError, StorageError}, Result};
+use crate::Daily;
+use chrono::NaiveDate;
+use std::fs;
+use std::path::PathBuf;
+
+const START_MARKER: &str = "<!-- daily-stoic:start -->";
+const END_MARKER: &str = "<!-- daily-stoic:end -->";
+
+const DEFAULT_NOTE_FORMAT: &str = "%Y-%m-%d";
+const DEFAULT_TEMPLATE: &str = "## The Daily Stoic: {title}\n\n> {quote}\n>\n> {quoter}\n\n{explanation}";
+
+/// Fills the template's placeholders from `daily`.
+pub fn render_section(daily: &Daily, template: &str) -> String {
+    let quote = daily.quote.lines().collect::<Vec<_>>().join("\n> ");
+    template
+        .replace("{date}", &daily.date)
+        .replace("{title}", &daily.title)
+        .replace("{quote}", &quote)
+        .replace("{quoter}", &daily.quoter)
+        .replace("{author}", &daily.citation.author)
+        .replace("{explanation}", &daily.explanation)
+}
+
+/// `note` with the daily stoic section and frontmatter keys set to `section`
+/// and `daily`, replacing any earlier ones.
+pub fn update_note(note: &str, daily: &Daily, section: &str) -> String {
+    let block = format!("{}\n{}\n{}", START_MARKER, section.trim_end(), END_MARKER);
+
+    let body = match (note.find(START_MARKER), note.find(END_MARKER)) {
+        (Some(start), Some(end)) if end > start => {
+            format!("{}{}{}", &note[..start], block, &note[end + END_MARKER.len()..])
+        }
+        _ if note.trim().is_empty() => format!("{}\n", block),
+        _ => format!("{}\n\n{}\n", note.trim_end(), block),
+    };
+
+    set_frontmatter(&body, &[
+        ("stoic_title", yaml_string(&daily.title)),
+        ("stoic_author", yaml_string(&daily.citation.author)),
+    ])
+}
+
+fn yaml_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+// sets top level `key: value` lines, adding a frontmatter block if there is none
+fn set_frontmatter(note: &str, fields: &[(&str, String)]) -> String {
+    let (mut lines, rest) = match note.strip_prefix("---\n").and_then(|n| n.split_once("\n---")) {
+        Some((yaml, rest)) => (yaml.lines().map(str::to_string).collect::<Vec<_>>(), rest.trim_start_matches('\n').to_string()),
+        None => (Vec::new(), note.to_string()),
+    };
+
+    for (key, value) in fields {
+        let line = format!("{}: {}", key, value);
+        let prefix = format!("{}:", key);
+        match lines.iter_mut().find(|l| l.starts_with(&prefix)) {
+            Some(existing) => *existing = line,
+            None => lines.push(line),
+        }
+    }
+
+    format!("---\n{}\n---\n{}", lines.join("\n"), rest)
+}
+
+/// Writes `daily` into the vault's daily note for `day`, creating the note if
+/// needed, and returns its path.
+pub fn export(daily: &Daily, config: &ObsidianConfig, day: NaiveDate) -> Result<PathBuf> {
+    let vault = config.vault
+        .as_ref()
+        .ok_or_else(|| ConfigError("No Obsidian vault configured (obsidian.vault or --vault)".to_string()))?;
+    if !vault.is_dir() {
+        return Err(ConfigError(format!("Obsidian vault {} does not exist", vault.display())));
+    }
+
+    let folder = vault.join(config.daily_folder.as_deref().unwrap_or(""));
+    let name = day.format(config.note_format.as_deref().unwrap_or(DEFAULT_NOTE_FORMAT)).to_string();
+    let path = folder.join(format!("{}.md", name));
+
+    let existing = match fs::read_to_string(&path) {
+        Ok(note) => note,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(StorageError(format!("Failed to read {}: {}", path.display(), e))),
+    };
+
+    let section = render_section(daily, config.template.as_deref().unwrap_or(DEFAULT_TEMPLATE));
+    let note = update_note(&existing, daily, &section);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(&path, note).map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    Ok(path)
+}
@@ -0,0 +1,146 @@
+//! Typeset A4 PDFs for printing and offline reading, one entry per page.
+//!
+//! The serif used for images is embedded so dashes and curly quotes print,
+//! which the standard PDF fonts can't do, and lines are wrapped by measuring
+//! the text with the same font.
+
+use crate::card::font_data;
+use crate::error::{DailyStoicError::{ConfigError, StorageError}, Result};
+use crate::Daily;
+use ab_glyph::{Font, FontVec};
+use fontdb::{Style, Weight};
+use printpdf::{Color, Greyscale, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference};
+use std::fs::File;
+use std::io::BufWriter;
+use std::path::Path;
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 25.0;
+const QUOTE_INDENT: f32 = 10.0;
+const LINE_SPACING: f32 = 1.4;
+const MM_PER_PT: f32 = 0.352_778;
+
+struct Face {
+    font: IndirectFontRef,
+    metrics: FontVec
+}
+
+impl Face {
+    fn load(doc: &PdfDocumentReference, font: Option<&str>, style: Style, weight: Weight) -> Result<Self> {
+        let (data, index) = font_data(font, style, weight)?;
+        let metrics = FontVec::try_from_vec_and_index(data.clone(), index)
+            .map_err(|e| ConfigError(format!("Invalid font: {}", e)))?;
+        let font = doc
+            .add_external_font(data.as_slice())
+            .map_err(|e| ConfigError(format!("Failed to embed the font: {}", e)))?;
+        Ok(Face { font, metrics })
+    }
+
+    // width of `text` in mm at `size` points
+    fn width(&self, text: &str, size: f32) -> f32 {
+        let units = self.metrics.units_per_em().unwrap_or(1000.0);
+        let advance: f32 = text.chars().map(|c| self.metrics.h_advance_unscaled(self.metrics.glyph_id(c))).sum();
+        advance / units * size * MM_PER_PT
+    }
+}
+
+// the document and where the next line goes
+struct Writer {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    y: f32
+}
+
+impl Writer {
+    fn new_page(&mut self) -> printpdf::PdfPageIndex {
+        let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = PAGE_HEIGHT - MARGIN;
+        page
+    }
+
+    // wraps `text` to the width left after `indent` on both sides, continuing
+    // on a new page when the current one is full
+    fn paragraph(&mut self, text: &str, face: &Face, size: f32, indent: f32) {
+        let line_height = size * LINE_SPACING * MM_PER_PT;
+        let width = PAGE_WIDTH - 2.0 * (MARGIN + indent);
+
+        for line in wrap(text, |s| face.width(s, size), width) {
+            if self.y - line_height < MARGIN {
+                self.new_page();
+            }
+            self.y -= line_height;
+            self.layer.use_text(line, size, Mm(MARGIN + indent), Mm(self.y), &face.font);
+        }
+    }
+
+    fn space(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+
+    fn grey(&self, level: f32) {
+        self.layer.set_fill_color(Color::Greyscale(Greyscale::new(level, None)));
+    }
+}
+
+// greedy word wrap by measured width, long words get a line of their own
+fn wrap(text: &str, measure: impl Fn(&str) -> f32, width: f32) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let candidate = if line.is_empty() { word.to_string() } else { format!("{} {}", line, word) };
+        if measure(&candidate) > width && !line.is_empty() {
+            lines.push(std::mem::replace(&mut line, word.to_string()));
+        } else {
+            line = candidate;
+        }
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+    lines
+}
+
+/// Writes `dailies` to `path`, each starting on a new page with a bookmark,
+/// in `font` (a file or installed family, `None` for a system serif).
+pub fn export(dailies: &[Daily], path: &Path, font: Option<&str>) -> Result<()> {
+    let (doc, page, layer) = PdfDocument::new("The Daily Stoic", Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Text");
+    let regular = Face::load(&doc, font, Style::Normal, Weight::NORMAL)?;
+    let italic = Face::load(&doc, font, Style::Italic, Weight::NORMAL)?;
+    let bold = Face::load(&doc, font, Style::Normal, Weight::BOLD)?;
+
+    let layer = doc.get_page(page).get_layer(layer);
+    let mut writer = Writer { doc, layer, y: PAGE_HEIGHT - MARGIN };
+
+    for (i, daily) in dailies.iter().enumerate() {
+        let page = if i == 0 { page } else { writer.new_page() };
+        writer.doc.add_bookmark(format!("{}: {}", daily.date, daily.title), page);
+
+        writer.grey(0.45);
+        writer.paragraph(&daily.date.to_uppercase(), &regular, 10.0, 0.0);
+        writer.grey(0.0);
+        writer.space(2.0);
+        writer.paragraph(&daily.title, &bold, 20.0, 0.0);
+        writer.space(8.0);
+
+        for line in daily.quote.lines().filter(|l| !l.trim().is_empty()) {
+            writer.paragraph(line, &italic, 13.0, QUOTE_INDENT);
+        }
+        writer.space(2.0);
+        writer.grey(0.3);
+        writer.paragraph(&daily.quoter, &regular, 10.0, QUOTE_INDENT);
+        writer.grey(0.0);
+        writer.space(8.0);
+
+        for paragraph in daily.explanation.split("\n\n").filter(|p| !p.trim().is_empty()) {
+            writer.paragraph(paragraph, &regular, 11.0, 0.0);
+            writer.space(3.0);
+        }
+    }
+
+    let file = File::create(path).map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))?;
+    writer.doc
+        .save(&mut BufWriter::new(file))
+        .map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+}
@@ -0,0 +1,198 @@
+//! RSS 2.0, Atom and JSON Feed 1.1 feeds of entries, for reading them in a
+//! feed reader, and podcast feeds of them read aloud.
+
+use crate::render::{self, escape_html, OutputFormat};
+use crate::topics;
+use crate::Daily;
+use chrono::NaiveDate;
+use serde_json::json;
+use std::fmt;
+use std::str::FromStr;
+
+const TITLE: &str = "The Daily Stoic";
+const DESCRIPTION: &str = "A daily meditation from The Daily Stoic";
+// the feed's id and the prefix of each entry's, stable across rebuilds
+const ID: &str = "urn:daily-stoic:feed";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum FeedFormat {
+    #[default]
+    Rss,
+    Atom,
+    JsonFeed,
+}
+
+impl FromStr for FeedFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "rss" => Ok(FeedFormat::Rss),
+            "atom" => Ok(FeedFormat::Atom),
+            "jsonfeed" | "json" => Ok(FeedFormat::JsonFeed),
+            _ => Err(format!("Unknown feed format \"{}\" (expected rss, atom or jsonfeed)", s)),
+        }
+    }
+}
+
+impl fmt::Display for FeedFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            FeedFormat::Rss => "rss",
+            FeedFormat::Atom => "atom",
+            FeedFormat::JsonFeed => "jsonfeed",
+        })
+    }
+}
+
+/// A feed of `entries`, each dated on the calendar day it was published for,
+/// newest first, with `link` as the site the feed belongs to.
+pub fn feed(entries: &[(NaiveDate, Daily)], format: FeedFormat, link: &str) -> String {
+    let mut entries: Vec<&(NaiveDate, Daily)> = entries.iter().collect();
+    entries.sort_by_key(|(day, _)| std::cmp::Reverse(*day));
+
+    match format {
+        FeedFormat::Rss => rss(&entries, link),
+        FeedFormat::Atom => atom(&entries, link),
+        FeedFormat::JsonFeed => json_feed(&entries, link),
+    }
+}
+
+/// An episode's audio file, for the `<enclosure>` of a podcast feed.
+pub struct Enclosure {
+    pub url: String,
+    /// Size in bytes
+    pub length: u64,
+    pub mime_type: String
+}
+
+/// An RSS podcast feed of `episodes`, newest first, with the iTunes tags podcast
+/// apps expect.
+pub fn podcast(episodes: &[(NaiveDate, Daily, Enclosure)], link: &str) -> String {
+    let mut episodes: Vec<&(NaiveDate, Daily, Enclosure)> = episodes.iter().collect();
+    episodes.sort_by_key(|(day, _, _)| std::cmp::Reverse(*day));
+
+    let items: String = episodes
+        .iter()
+        .map(|(day, daily, enclosure)| format!(
+            "    <item>\n      <title>{}</title>\n      <guid isPermaLink=\"false\">{}</guid>\n      <pubDate>{}</pubDate>\n      <itunes:author>{}</itunes:author>\n      <description>{}</description>\n      <enclosure url=\"{}\" length=\"{}\" type=\"{}\"/>\n    </item>\n",
+            entry_title(daily),
+            entry_id(*day),
+            day.format("%a, %d %b %Y 00:00:00 +0000"),
+            author(daily),
+            escape_html(&format!("{}\n{}", daily.quote.trim(), daily.quoter)),
+            escape_html(&enclosure.url),
+            enclosure.length,
+            enclosure.mime_type
+        ))
+        .collect();
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:itunes=\"http://www.itunes.com/dtds/podcast-1.0.dtd\">\n  <channel>\n    <title>{}</title>\n    <link>{}</link>\n    <description>{}</description>\n    <language>en</language>\n    <itunes:author>{}</itunes:author>\n    <itunes:explicit>false</itunes:explicit>\n    <itunes:category text=\"Society &amp; Culture\"><itunes:category text=\"Philosophy\"/></itunes:category>\n{}  </channel>\n</rss>\n",
+        TITLE,
+        escape_html(link),
+        DESCRIPTION,
+        TITLE,
+        items
+    )
+}
+
+fn entry_title(daily: &Daily) -> String {
+    escape_html(&format!("{}: {}", daily.date, daily.title))
+}
+
+// the attribution's author, the feed's title when the quote has none
+fn author(daily: &Daily) -> String {
+    let author = if daily.citation.author.is_empty() { TITLE } else { &daily.citation.author };
+    escape_html(author)
+}
+
+fn entry_id(day: NaiveDate) -> String {
+    format!("{}:{}", ID, day.format("%Y-%m-%d"))
+}
+
+fn rss(entries: &[&(NaiveDate, Daily)], link: &str) -> String {
+    let items: String = entries
+        .iter()
+        .map(|(day, daily)| format!(
+            "    <item>\n      <title>{}</title>\n      <link>{}</link>\n      <guid isPermaLink=\"false\">{}</guid>\n      <pubDate>{}</pubDate>\n      <dc:creator>{}</dc:creator>\n      <description>{}</description>\n    </item>\n",
+            entry_title(daily),
+            escape_html(link),
+            entry_id(*day),
+            day.format("%a, %d %b %Y 00:00:00 +0000"),
+            author(daily),
+            escape_html(&render::render(daily, OutputFormat::Html))
+        ))
+        .collect();
+    let updated = entries.first().map(|(day, _)| day.format("%a, %d %b %Y 00:00:00 +0000").to_string());
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\" xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n  <channel>\n    <title>{}</title>\n    <link>{}</link>\n    <description>{}</description>\n{}{}  </channel>\n</rss>\n",
+        TITLE,
+        escape_html(link),
+        DESCRIPTION,
+        updated.map(|d| format!("    <lastBuildDate>{}</lastBuildDate>\n", d)).unwrap_or_default(),
+        items
+    )
+}
+
+fn atom(entries: &[&(NaiveDate, Daily)], link: &str) -> String {
+    let timestamp = |day: NaiveDate| day.format("%Y-%m-%dT00:00:00Z").to_string();
+    let items: String = entries
+        .iter()
+        .map(|(day, daily)| format!(
+            "  <entry>\n    <title>{}</title>\n    <id>{}</id>\n    <link href=\"{}\"/>\n    <updated>{}</updated>\n    <author><name>{}</name></author>\n    <summary>{}</summary>\n    <content type=\"html\">{}</content>\n  </entry>\n",
+            entry_title(daily),
+            entry_id(*day),
+            escape_html(link),
+            timestamp(*day),
+            author(daily),
+            escape_html(daily.quote.trim()),
+            escape_html(&render::render(daily, OutputFormat::Html))
+        ))
+        .collect();
+    // Atom requires an updated date even for an empty feed
+    let updated = entries
+        .first()
+        .map(|(day, _)| timestamp(*day))
+        .unwrap_or_else(|| chrono::Utc::now().format("%Y-%m-%dT%H:%M:%SZ").to_string());
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<feed xmlns=\"http://www.w3.org/2005/Atom\">\n  <title>{}</title>\n  <subtitle>{}</subtitle>\n  <id>{}</id>\n  <link href=\"{}\"/>\n  <updated>{}</updated>\n{}</feed>\n",
+        TITLE,
+        DESCRIPTION,
+        ID,
+        escape_html(link),
+        updated,
+        items
+    )
+}
+
+fn json_feed(entries: &[&(NaiveDate, Daily)], link: &str) -> String {
+    let items: Vec<serde_json::Value> = entries
+        .iter()
+        .map(|(day, daily)| {
+            let author = if daily.citation.author.is_empty() { TITLE } else { &daily.citation.author };
+            json!({
+                "id": entry_id(*day),
+                "url": link,
+                "title": format!("{}: {}", daily.date, daily.title),
+                "summary": daily.quote.trim(),
+                "content_html": render::render(daily, OutputFormat::Html),
+                "date_published": day.format("%Y-%m-%dT00:00:00Z").to_string(),
+                "authors": [{ "name": author }],
+                "tags": topics::classify(daily),
+            })
+        })
+        .collect();
+
+    let feed = json!({
+        "version": "https://jsonfeed.org/version/1.1",
+        "title": TITLE,
+        "home_page_url": link,
+        "description": DESCRIPTION,
+        "language": "en",
+        "items": items,
+    });
+    serde_json::to_string_pretty(&feed).unwrap() + "\n" // built from strings, always serializes
+}
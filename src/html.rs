@@ -0,0 +1,122 @@
+//! The text of HTML source pages, laid out a line per block element so the
+//! date-section parser finds each header on a line of its own.
+
+use crate::error::{DailyStoicError::ConfigError, Result};
+use scraper::{ElementRef, Html, Node, Selector};
+
+// elements that start a line of their own
+const BLOCKS: [&str; 24] = [
+    "address", "article", "aside", "blockquote", "dd", "div", "dl", "dt", "figcaption", "figure", "footer", "h1",
+    "h2", "h3", "h4", "h5", "h6", "header", "hr", "li", "main", "p", "section", "tr",
+];
+
+// elements without any of the book's text
+const SKIPPED: [&str; 7] = ["head", "script", "style", "noscript", "template", "nav", "svg"];
+
+/// Whether `body` is an HTML page rather than plain text.
+///
+/// ```
+/// use daily_stoic_rs::html::is_html;
+///
+/// assert!(is_html("<!DOCTYPE html>\n<html><body>January 1</body></html>"));
+/// assert!(!is_html("January 1\nON DISCIPLINE"));
+/// ```
+pub fn is_html(body: &str) -> bool {
+    let start: String = body.trim_start().chars().take(15).collect::<String>().to_lowercase();
+    start.starts_with("<!doctype html") || start.starts_with("<html")
+}
+
+/// Checks that `selector` is valid CSS before anything is fetched.
+pub fn parse_selector(selector: &str) -> Result<Selector> {
+    Selector::parse(selector).map_err(|e| ConfigError(format!("Invalid source_selector \"{}\": {}", selector, e)))
+}
+
+/// The text of the elements matching `selector`, or of the whole page without
+/// one. Block elements and `<br>` break lines and `<pre>` keeps its own, while
+/// other runs of whitespace collapse to a space as a browser would show them.
+///
+/// ```
+/// use daily_stoic_rs::html::to_text;
+///
+/// let page = "<html><body><nav>Menu</nav><article><h2>January 1</h2>\
+///             <p>ON DISCIPLINE</p><p>The wise man\n   does not yield.</p></article></body></html>";
+/// assert_eq!(to_text(page, Some("article")).unwrap(), "January 1\nON DISCIPLINE\nThe wise man does not yield.");
+/// ```
+pub fn to_text(body: &str, selector: Option<&str>) -> Result<String> {
+    let document = Html::parse_document(body);
+    let mut text = String::new();
+    match selector {
+        Some(selector) => {
+            for element in document.select(&parse_selector(selector)?) {
+                write_element(element, false, &mut text);
+                new_line(&mut text);
+            }
+        }
+        None => write_element(document.root_element(), false, &mut text),
+    }
+
+    // trailing spaces off every line and at most one blank line in a row
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(str::trim_end) {
+        if line.trim().is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
+        }
+        lines.push(if line.trim().is_empty() { "" } else { line.trim_start_matches(' ') });
+    }
+    while lines.last().is_some_and(|last| last.is_empty()) {
+        lines.pop();
+    }
+    Ok(lines.join("\n"))
+}
+
+fn write_element(element: ElementRef, in_pre: bool, out: &mut String) {
+    let name = element.value().name();
+    if SKIPPED.contains(&name) {
+        return;
+    }
+    let block = BLOCKS.contains(&name) || name == "pre";
+    if block {
+        new_line(out);
+    }
+
+    for child in element.children() {
+        match child.value() {
+            Node::Text(text) if in_pre || name == "pre" => out.push_str(text),
+            Node::Text(text) => {
+                // collapse whitespace, keeping a single space at either end where there was some
+                let words: Vec<&str> = text.split_whitespace().collect();
+                if words.is_empty() {
+                    if !text.is_empty() && !out.ends_with([' ', '\n']) {
+                        out.push(' ');
+                    }
+                    continue;
+                }
+                if text.starts_with(char::is_whitespace) && !out.ends_with([' ', '\n']) {
+                    out.push(' ');
+                }
+                out.push_str(&words.join(" "));
+                if text.ends_with(char::is_whitespace) {
+                    out.push(' ');
+                }
+            }
+            Node::Element(child_element) if child_element.name() == "br" => out.push('\n'),
+            Node::Element(_) => {
+                if let Some(child) = ElementRef::wrap(child) {
+                    write_element(child, in_pre || name == "pre", out);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if block {
+        new_line(out);
+    }
+}
+
+// a block's boundary ends the line, without leaving a blank one between blocks
+fn new_line(out: &mut String) {
+    if !out.is_empty() && !out.ends_with('\n') {
+        out.push('\n');
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::{HttpConfig, RetryConfig};
+use crate::error::{DailyStoicError::{self, ConfigError, NetworkError}, Result};
+use chrono::{DateTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, RETRY_AFTER};
+use reqwest::{Client, NoProxy, Proxy, RequestBuilder, Response, StatusCode, Url};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(60);
+const DEFAULT_USER_AGENT: &str = concat!("daily-stoic/", env!("CARGO_PKG_VERSION"), " (+https://github.com/aig77/daily-stoic-rs)");
+
+// a Retry-After longer than this fails right away rather than hang a cron job
+const MAX_RETRY_AFTER: Duration = Duration::from_secs(60);
+
+/// How often and how patiently to retry transient HTTP failures (timeouts,
+/// connection errors, 5xx responses and 429s).
+#[derive(Clone, Copy, Debug)]
+pub struct RetryPolicy {
+    /// Total tries, including the first one.
+    pub attempts: u32,
+    /// Delay before the first retry, doubled for every retry after it.
+    pub backoff: Duration,
+    /// Upper bound for a single delay.
+    pub max_backoff: Duration,
+    /// Randomize each delay between half and all of its value.
+    pub jitter: bool
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            attempts: 3,
+            backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            jitter: true
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Defaults overridden by whatever `config` sets.
+    pub fn from_config(config: &RetryConfig) -> Self {
+        let default = RetryPolicy::default();
+        RetryPolicy {
+            attempts: config.attempts.unwrap_or(default.attempts),
+            backoff: config.backoff_ms.map(Duration::from_millis).unwrap_or(default.backoff),
+            max_backoff: default.max_backoff,
+            jitter: config.jitter.unwrap_or(default.jitter)
+        }
+    }
+
+    fn delay(&self, retry: u32) -> Duration {
+        let delay = self.backoff
+            .saturating_mul(2u32.saturating_pow(retry))
+            .min(self.max_backoff);
+
+        if self.jitter {
+            delay.mul_f64(rand::random_range(0.5..=1.0))
+        } else {
+            delay
+        }
+    }
+}
+
+/// The client for source and LLM requests, with `config`'s User-Agent and
+/// timeout. It goes through `config.proxy` when set, for every host but those
+/// in NO_PROXY, and through HTTP_PROXY or HTTPS_PROXY otherwise.
+pub fn client(config: &HttpConfig) -> Result<Client> {
+    // a timeout between reads rather than for the whole request, so a long streamed reply isn't cut off
+    let timeout = config.timeout_secs.map(Duration::from_secs).unwrap_or(DEFAULT_TIMEOUT);
+    let mut builder = Client::builder()
+        .user_agent(config.user_agent.as_deref().unwrap_or(DEFAULT_USER_AGENT))
+        .connect_timeout(timeout)
+        .read_timeout(timeout);
+    if let Some(url) = &config.proxy {
+        // the url may hold a password, so it's left out of the error
+        let invalid = |e: &dyn std::fmt::Display| ConfigError(format!("Invalid proxy url (http.proxy): {}", e));
+        let url = Url::parse(url).map_err(|e| invalid(&e))?;
+        let proxy = Proxy::all(url).map_err(|e| invalid(&e))?;
+        builder = builder.proxy(proxy.no_proxy(NoProxy::from_env()));
+    }
+    builder.build().map_err(|e| ConfigError(format!("Failed to set up the HTTP client: {}", e)))
+}
+
+/// `config.headers` for the source fetch, failing on names or values that
+/// aren't valid in a header.
+pub fn source_headers(config: &HttpConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+    for (name, value) in &config.headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| ConfigError(format!("Invalid header name \"{}\" in http.headers: {}", name, e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| ConfigError(format!("Invalid value for header {} in http.headers: {}", name, e)))?;
+        headers.insert(name, value);
+    }
+    Ok(headers)
+}
+
+// a 503 without Retry-After is an outage rather than a rate limit
+fn is_rate_limited(response: &Response) -> bool {
+    response.status() == StatusCode::TOO_MANY_REQUESTS
+        || (response.status() == StatusCode::SERVICE_UNAVAILABLE && response.headers().contains_key(RETRY_AFTER))
+}
+
+// Retry-After is either a number of seconds or an HTTP date
+fn retry_after(response: &Response) -> Option<Duration> {
+    let value = response.headers().get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or_default())
+}
+
+fn rate_limited(response: &Response, wait: Option<Duration>) -> DailyStoicError {
+    let host = response.url().host_str().unwrap_or("the server").to_string();
+    match wait {
+        Some(wait) => NetworkError(format!(
+            "Rate limited by {} (status {}), try again in {} seconds",
+            host, response.status(), wait.as_secs().max(1)
+        )),
+        None => NetworkError(format!("Rate limited by {} (status {}), try again later", host, response.status())),
+    }
+}
+
+// reqwest's message doesn't say when the cause was a timeout
+fn describe(error: &reqwest::Error) -> String {
+    if error.is_timeout() {
+        format!("{} (timed out, see http.timeout_secs)", error)
+    } else {
+        error.to_string()
+    }
+}
+
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect() || error.is_request()
+}
+
+/// Sends the request built by `request`, rebuilding and resending it according to
+/// `policy` while it fails transiently. When rate limited (a 429, or a 503 with Retry-After) it waits
+/// as long as the server's Retry-After asks, up to a minute, and otherwise fails
+/// saying when to try again.
+pub async fn send_with_retry(
+    policy: &RetryPolicy,
+    request: impl Fn() -> RequestBuilder,
+) -> Result<Response> {
+    let attempts = policy.attempts.max(1);
+    let mut retry = 0;
+
+    loop {
+        let last_attempt = retry + 1 >= attempts;
+
+        let mut wait = None;
+        let reason = match request().send().await {
+            Ok(response) if is_rate_limited(&response) => {
+                wait = retry_after(&response);
+                let too_long = wait.is_some_and(|wait| wait > MAX_RETRY_AFTER);
+                if last_attempt || too_long {
+                    return Err(rate_limited(&response, wait));
+                }
+                format!("status {}", response.status())
+            }
+            Ok(response) if response.status().is_server_error() => {
+                if last_attempt {
+                    return Err(NetworkError(format!("Request failed with status {}", response.status())));
+                }
+                format!("status {}", response.status())
+            }
+            Ok(response) => {
+                debug!("{} {}", response.status(), response.url());
+                return Ok(response);
+            }
+            Err(e) => {
+                if last_attempt || !is_transient(&e) {
+                    return Err(NetworkError(format!("Request failed: {}", describe(&e))));
+                }
+                describe(&e)
+            }
+        };
+
+        let delay = wait.unwrap_or_else(|| policy.delay(retry));
+        warn!("Request failed ({}), retrying in {:?} ({}/{})", reason, delay, retry + 1, attempts - 1);
+        tokio::time::sleep(delay).await;
+        retry += 1;
+    }
+}
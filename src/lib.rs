@@ -0,0 +1,639 @@
+//! Fetch, parse and clean up entries from Ryan Holiday's The Daily Stoic.
+//!
+//! ```no_run
+//! use daily_stoic_rs::DailyStoicClient;
+//!
+//! # async fn run() -> daily_stoic_rs::error::Result<()> {
+//! let client = DailyStoicClient::from_env()?;
+//! let daily = client.get_daily("March 5").await?;
+//! println!("{}", daily.quote);
+//! # Ok(())
+//! # }
+//! ```
+
+extern crate reqwest;
+extern crate chrono;
+
+pub mod archive;
+pub mod banner;
+pub mod books;
+pub mod cache;
+pub mod card;
+pub mod citation;
+pub mod clean;
+pub mod config;
+pub mod credentials;
+pub mod date;
+pub mod diff;
+pub mod error;
+pub mod export;
+pub mod feed;
+pub mod html;
+pub mod http;
+pub mod llm;
+pub mod locale;
+pub mod parse;
+pub mod render;
+pub mod semantic;
+pub mod send;
+pub mod site;
+pub mod source;
+pub mod svg;
+pub mod theme;
+pub mod topics;
+pub mod tts;
+pub mod usage;
+
+use archive::Archive;
+use cache::{BodyMeta, Cache};
+use chrono::Utc;
+use citation::Citation;
+use config::Config;
+use error::{DailyStoicError, Result};
+use http::RetryPolicy;
+use llm::LlmProvider;
+use reqwest::StatusCode;
+use reqwest::header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tracing::{debug, info, warn};
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Daily {
+    pub date: String,
+    pub title: String,
+    pub quote: String,
+    pub quoter: String,
+    pub explanation: String,
+    /// `quoter` split into author, work and section.
+    #[serde(default)]
+    pub citation: Citation
+}
+
+pub struct DailyStoicClient {
+    url: String,
+    mirrors: Vec<String>,
+    selector: Option<String>,
+    footer_markers: Vec<String>,
+    end_marker: String,
+    cache: Option<Cache>,
+    archive: Option<Archive>,
+    llm: Option<Box<dyn LlmProvider>>,
+    cleanup_prompt: String,
+    // sampling settings that change LLM replies without changing the provider's name
+    llm_settings: String,
+    http: reqwest::Client,
+    headers: reqwest::header::HeaderMap,
+    retry: RetryPolicy
+}
+
+impl DailyStoicClient {
+    pub fn new(url: &str) -> Self {
+        DailyStoicClient {
+            url: url.to_string(),
+            mirrors: Vec::new(),
+            selector: None,
+            footer_markers: parse::default_footer_markers(),
+            end_marker: parse::DEFAULT_END_MARKER.to_string(),
+            cache: None,
+            archive: None,
+            llm: None,
+            cleanup_prompt: llm::DEFAULT_CLEANUP_PROMPT.to_string(),
+            llm_settings: String::new(),
+            http: reqwest::Client::new(),
+            headers: reqwest::header::HeaderMap::new(),
+            retry: RetryPolicy::default()
+        }
+    }
+
+    /// Builds a client from the source, footer, LLM, retry and HTTP settings in `config`.
+    /// The cache and archive are left to the caller.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let url = config.source_url
+            .as_deref()
+            .ok_or_else(|| DailyStoicError::ConfigError(match config.other_book() {
+                Some(book) => format!("No source url configured for {} (source_url in [books.{}], or --source-url)", book, book),
+                None => "No source url configured (source_url, or the DAILY_STOIC_URL env var)".to_string(),
+            }))?;
+
+        let mut client = DailyStoicClient::new(url);
+        client.mirrors = config.source_mirrors.clone();
+        client.retry = RetryPolicy::from_config(&config.retry);
+        client.http = http::client(&config.http)?;
+        client.headers = http::source_headers(&config.http)?;
+        client.llm = llm::provider_from_config(&config.llm, &client.http, client.retry)?;
+        if client.llm.is_some() {
+            client.cleanup_prompt = llm::load_cleanup_prompt(config.llm.cleanup_prompt.as_deref())?;
+        }
+        if config.llm.temperature.is_some() || config.llm.max_tokens.is_some() {
+            client.llm_settings = format!(" temperature={:?} max_tokens={:?}", config.llm.temperature, config.llm.max_tokens);
+        }
+
+        if let Some(markers) = &config.footer_markers {
+            client.footer_markers = markers.clone();
+        }
+        if let Some(marker) = &config.end_marker {
+            client.end_marker = marker.clone();
+        }
+        if let Some(selector) = &config.source_selector {
+            html::parse_selector(selector)?;
+            client.selector = Some(selector.clone());
+        }
+
+        Ok(client)
+    }
+
+    /// Builds a client from the default config file and env vars, see `Config::from_env`.
+    pub fn from_env() -> Result<Self> {
+        DailyStoicClient::from_config(&Config::from_env()?)
+    }
+
+    /// Falls back to `mirrors`, in order, when the source can't be read or
+    /// lacks the date asked for.
+    pub fn with_mirrors(mut self, mirrors: Vec<String>) -> Self {
+        self.mirrors = mirrors;
+        self
+    }
+
+    /// Takes the text of HTML pages from the elements matching the CSS
+    /// `selector` instead of the whole page.
+    pub fn with_selector(mut self, selector: &str) -> Self {
+        self.selector = Some(selector.to_string());
+        self
+    }
+
+    pub fn with_footer_markers(mut self, footer_markers: Vec<String>) -> Self {
+        self.footer_markers = footer_markers;
+        self
+    }
+
+    /// Ends December 31's entry at the `marker` header instead of "STAYING
+    /// STOIC", or at the end of the text when it's empty.
+    pub fn with_end_marker(mut self, marker: &str) -> Self {
+        self.end_marker = marker.to_string();
+        self
+    }
+
+    pub fn with_llm(mut self, llm: Box<dyn LlmProvider>) -> Self {
+        self.llm = Some(llm);
+        self
+    }
+
+    /// Cleans up with `template` instead of the built-in prompt, see
+    /// [`llm::cleanup_prompt`] for its placeholders.
+    pub fn with_cleanup_prompt(mut self, template: &str) -> Self {
+        self.cleanup_prompt = template.to_string();
+        self
+    }
+
+    /// Fetches the source with `http`, e.g. one made by [`http::client`].
+    pub fn with_http_client(mut self, http: reqwest::Client) -> Self {
+        self.http = http;
+        self
+    }
+
+    /// Sends `headers` along with the source fetch, e.g. a mirror's credentials.
+    pub fn with_headers(mut self, headers: reqwest::header::HeaderMap) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Serves bodies and cleaned up entries from `cache` when possible, skipping
+    /// both the fetch and the LLM calls.
+    pub fn with_cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Serves entries from the local archive before touching the cache or network.
+    pub fn with_archive(mut self, archive: Archive) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    pub fn mirrors(&self) -> &[String] {
+        &self.mirrors
+    }
+
+    pub fn archive(&self) -> Option<&Archive> {
+        self.archive.as_ref()
+    }
+
+    pub fn llm(&self) -> Option<&dyn LlmProvider> {
+        self.llm.as_deref()
+    }
+
+    // the LLM to clean up and write with, `None` when there's none or it can only
+    // hand the text back, which leaves cleanup to the rule-based cleaner
+    fn cleanup_llm(&self) -> Option<&dyn LlmProvider> {
+        self.llm.as_deref().filter(|llm| llm.generates_text())
+    }
+
+    fn archived(&self, date: &str) -> Result<Option<Daily>> {
+        match &self.archive {
+            Some(archive) => archive.get(date),
+            None => Ok(None),
+        }
+    }
+
+    /// The source's text, from the first of `url` and the mirrors that can be
+    /// read. See [`fetch_body_for`](Self::fetch_body_for) to also skip ones
+    /// missing a date.
+    pub async fn fetch_body(&self) -> Result<String> {
+        self.fetch_body_with(|_| Ok(())).await
+    }
+
+    /// The source's text from the first of `url` and the mirrors that can be
+    /// read and has `date`'s section, so a dead or truncated copy is passed over.
+    pub async fn fetch_body_for(&self, date: &str) -> Result<String> {
+        self.fetch_body_with(|body| self.raw_from_body(body, date).map(|_| ())).await
+    }
+
+    // tries each source in turn until one's body passes `check`, with the last error otherwise
+    async fn fetch_body_with(&self, check: impl Fn(&str) -> Result<()>) -> Result<String> {
+        let mut url = &self.url;
+        let mut result = self.read_source(url).await.and_then(|body| check(&body).map(|()| body));
+        for mirror in &self.mirrors {
+            let Err(e) = &result else { break };
+            warn!("Trying {}, {} failed: {}", mirror, url, e);
+            url = mirror;
+            result = self.read_source(url).await.and_then(|body| check(&body).map(|()| body));
+        }
+        result
+    }
+
+    // The text at `url`, read from a local copy of the book when it's one (see
+    // [`source::Source`]) and fetched otherwise. HTML pages are reduced to the
+    // text of their content, see [`html::to_text`], so dates are found whatever
+    // the markup's line breaks.
+    async fn read_source(&self, url: &str) -> Result<String> {
+        let page = match source::Source::parse(url).read_local().await {
+            Some(text) => text?,
+            None => self.fetch_page(url).await?,
+        };
+        if !html::is_html(&page) {
+            return Ok(page);
+        }
+        let text = html::to_text(&page, self.selector.as_deref())?;
+        debug!("Extracted {} bytes of text from the HTML page", text.len());
+        Ok(text)
+    }
+
+    // The source page, from the cache while it's fresh. A stale cached copy is
+    // revalidated with If-None-Match and If-Modified-Since, so an unchanged page
+    // costs a 304 instead of a download, and is still used when the server
+    // can't be reached.
+    async fn fetch_page(&self, url: &str) -> Result<String> {
+        let cached = self.cache.as_ref().and_then(|cache| {
+            let body = cache.load_body(url)?;
+            Some((cache, body, cache.load_body_meta(url).unwrap_or_default()))
+        });
+        if let Some((cache, body, meta)) = &cached && cache.is_fresh(meta) {
+            debug!("Using the cached body for {} ({} bytes)", url, body.len());
+            return Ok(body.clone());
+        }
+
+        info!("Fetching {}", url);
+        let result = http::send_with_retry(&self.retry, || {
+            let mut request = self.http.get(url).headers(self.headers.clone());
+            if let Some((_, _, meta)) = &cached {
+                if let Some(etag) = &meta.etag {
+                    request = request.header(IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &meta.last_modified {
+                    request = request.header(IF_MODIFIED_SINCE, last_modified);
+                }
+            }
+            request
+        }).await;
+
+        let response = match (result, cached) {
+            (Ok(response), Some((cache, body, meta))) if response.status() == StatusCode::NOT_MODIFIED => {
+                info!("{} is unchanged, using the cached body", url);
+                cache.store_body_meta(url, &BodyMeta { checked: Utc::now().timestamp(), ..meta })?;
+                return Ok(body);
+            }
+            (Ok(response), Some((_, body, _))) if !response.status().is_success() => {
+                warn!("Using the cached body for {}: status {}", url, response.status());
+                return Ok(body);
+            }
+            (Err(e), Some((_, body, _))) => {
+                warn!("Using the cached body for {}: {}", url, e);
+                return Ok(body);
+            }
+            (result, _) => result?,
+        };
+
+        let header = |name| response.headers().get(name).and_then(|value| value.to_str().ok()).map(str::to_string);
+        let meta = BodyMeta {
+            etag: header(ETAG),
+            last_modified: header(LAST_MODIFIED),
+            checked: Utc::now().timestamp(),
+        };
+
+        let body = response.text()
+            .await
+            .map_err(|e| DailyStoicError::NetworkError(format!("Failed to read response body: {}", e)))?;
+        info!("Fetched {} bytes from {}", body.len(), url);
+
+        if let Some(cache) = &self.cache {
+            cache.store_body(url, &body)?;
+            cache.store_body_meta(url, &meta)?;
+        }
+
+        Ok(body)
+    }
+
+    /// The header that ends `date`'s section, empty when it runs to the end of the text.
+    pub fn section_end(&self, date: &str) -> String {
+        parse::next_date_marker(date, &self.end_marker)
+    }
+
+    /// Extracts the unprocessed text for `date` from an already fetched body.
+    pub fn raw_from_body(&self, body: &str, date: &str) -> Result<String> {
+        parse::get_date_text(body, date, &self.section_end(date))
+            .ok_or_else(|| DailyStoicError::DateNotFound { date: date.to_string() })
+    }
+
+    /// Splits raw date text into a `Daily` without any LLM cleanup.
+    pub fn parse_raw(&self, raw: &str) -> Result<Daily> {
+        parse::format_daily(raw, &self.footer_markers)
+    }
+
+    // the entry found under `date`, dated with it rather than its header as
+    // written (e.g. "March 5th"), which keys the cache and the archive
+    fn parse_entry(&self, raw: &str, date: &str) -> Result<Daily> {
+        let mut daily = self.parse_raw(raw)?;
+        daily.date = date.to_string();
+        Ok(daily)
+    }
+
+    /// Runs the quote and explanation through the LLM (concurrently) to fix
+    /// extraction artifacts, falling back to the rule-based cleaner when no LLM that
+    /// can generate text is configured.
+    pub async fn clean_up(&self, mut daily: Daily) -> Result<Daily> {
+        let Some(llm) = self.cleanup_llm() else {
+            return Ok(clean::clean_daily(daily));
+        };
+
+        let kind = self.cleanup_kind();
+        let quote_prompt = llm::cleanup_prompt(&self.cleanup_prompt, &daily, "quote", &daily.quote);
+        let explanation_prompt = llm::cleanup_prompt(&self.cleanup_prompt, &daily, "explanation", &daily.explanation);
+        let (quote, explanation) = tokio::try_join!(
+            self.cached_llm(llm, &kind, &daily.quote, llm.fix_text(&quote_prompt, &daily.quote)),
+            self.cached_llm(llm, &kind, &daily.explanation, llm.fix_text(&explanation_prompt, &daily.explanation)),
+        )?;
+
+        daily.quote = quote;
+        daily.explanation = explanation;
+        Ok(daily)
+    }
+
+    /// Like [`clean_up`](Self::clean_up), one field after the other with the
+    /// replies streamed: `on_text` gets "quote" or "explanation" and each piece
+    /// of its cleaned up text as it arrives, or all of it when it was cached.
+    pub async fn clean_up_streaming(&self, mut daily: Daily, on_text: &(dyn Fn(&str, &str) + Send + Sync)) -> Result<Daily> {
+        let Some(llm) = self.cleanup_llm() else {
+            return Ok(clean::clean_daily(daily));
+        };
+
+        let kind = self.cleanup_kind();
+        for field in ["quote", "explanation"] {
+            let input = if field == "quote" { daily.quote.clone() } else { daily.explanation.clone() };
+            let prompt = llm::cleanup_prompt(&self.cleanup_prompt, &daily, field, &input);
+            let streamed = AtomicBool::new(false);
+            let on_piece = |piece: &str| {
+                streamed.store(true, Ordering::Relaxed);
+                on_text(field, piece);
+            };
+
+            let text = self.cached_llm(llm, &kind, &input, llm.fix_text_streaming(&prompt, &input, &on_piece)).await?;
+            if !streamed.load(Ordering::Relaxed) {
+                on_text(field, &text);
+            }
+            if field == "quote" { daily.quote = text } else { daily.explanation = text }
+        }
+        Ok(daily)
+    }
+
+    // replies to a custom template are cached apart from the built-in prompt's
+    fn cleanup_kind(&self) -> String {
+        if self.cleanup_prompt == llm::DEFAULT_CLEANUP_PROMPT {
+            format!("cleanup-v{}", llm::PROMPT_VERSION)
+        } else {
+            format!("cleanup-{}", &cache::llm_key("", "prompt", &self.cleanup_prompt)[..12])
+        }
+    }
+
+    // the provider and model with any sampling settings, which together with the
+    // request decide what the LLM replies
+    fn llm_identity(&self, llm: &dyn LlmProvider) -> String {
+        format!("{}{}", llm.name(), self.llm_settings)
+    }
+
+    // cleaned up entries are cached apart per provider, model and cleanup prompt,
+    // so changing any of them takes effect for days already seen
+    fn entry_variant(&self) -> String {
+        let identity = self.llm.as_deref().map(|llm| self.llm_identity(llm)).unwrap_or_default();
+        cache::llm_key(&identity, &self.cleanup_kind(), "")[..12].to_string()
+    }
+
+    /// Translates the title, quote and explanation of `daily` into `language`
+    /// with the LLM, caching the translations like cleanups.
+    pub async fn translate(&self, mut daily: Daily, language: &str) -> Result<Daily> {
+        let llm = self.llm
+            .as_deref()
+            .ok_or_else(|| DailyStoicError::ConfigError("Translating needs an LLM, configure one in [llm]".to_string()))?;
+
+        let kind = format!("translate-{}", language.trim().to_lowercase());
+        let (title, quote, explanation) = tokio::try_join!(
+            self.cached_llm(llm, &kind, &daily.title, llm.translate(&daily.title, language)),
+            self.cached_llm(llm, &kind, &daily.quote, llm.translate(&daily.quote, language)),
+            self.cached_llm(llm, &kind, &daily.explanation, llm.translate(&daily.explanation, language)),
+        )?;
+
+        daily.title = title.trim().to_string();
+        daily.quote = quote.trim().to_string();
+        daily.explanation = explanation.trim().to_string();
+        Ok(daily)
+    }
+
+    /// Two paragraphs on the theme of `month` drawn from its `dailies` by the
+    /// LLM, cached like cleanups. `None` without an LLM that can generate text.
+    pub async fn summarize_month(&self, month: &str, dailies: &[Daily]) -> Result<Option<String>> {
+        let Some(llm) = self.cleanup_llm() else {
+            return Ok(None);
+        };
+
+        let prompt = llm::month_prompt(month, dailies);
+        let text = self.cached_llm(llm, "month-v1", &prompt, llm.complete(&prompt)).await?;
+        Ok(Some(text.trim().to_string()))
+    }
+
+    // replays an earlier reply for the same provider, request kind and input if
+    // cached, only awaiting `request` on a miss
+    async fn cached_llm(
+        &self,
+        llm: &dyn LlmProvider,
+        kind: &str,
+        input: &str,
+        request: impl Future<Output = Result<String>>,
+    ) -> Result<String> {
+        let Some(cache) = &self.cache else {
+            return timed_llm(llm, kind, request).await;
+        };
+
+        let key = cache::llm_key(&self.llm_identity(llm), kind, input);
+        if let Some(text) = cache.load_llm(&key) {
+            debug!("Using the cached {} reply from {}", kind, llm.name());
+            return Ok(text);
+        }
+
+        let text = timed_llm(llm, kind, request).await?;
+        // filed under whichever provider replied, a fallback may have stood in
+        cache.store_llm(&cache::llm_key(&self.llm_identity(llm), kind, input), &text)?;
+        Ok(text)
+    }
+
+    /// Fetches the unprocessed text for `date` (e.g. "March 5").
+    pub async fn get_raw(&self, date: &str) -> Result<String> {
+        let body = self.fetch_body_for(date).await?;
+        self.raw_from_body(&body, date)
+    }
+
+    // an already processed entry from the archive or, for cleaned up entries, the cache
+    fn stored(&self, date: &str, use_llm: bool) -> Result<Option<Daily>> {
+        if let Some(daily) = self.archived(date)? {
+            debug!("Using the archived entry for {}", date);
+            return Ok(Some(daily));
+        }
+
+        if use_llm && self.cleanup_llm().is_some() {
+            let daily = self.cache.as_ref().and_then(|c| c.load_daily(date, &self.entry_variant()));
+            if daily.is_some() {
+                debug!("Using the cached entry for {}", date);
+            }
+            return Ok(daily);
+        }
+
+        Ok(None)
+    }
+
+    /// Parses and cleans up `date` from an already fetched body, with the LLM when
+    /// `use_llm` is set and one is configured and with the rule-based cleaner otherwise.
+    pub async fn daily_from_body(&self, body: &str, date: &str, use_llm: bool) -> Result<Daily> {
+        let daily = self.parse_entry(&self.raw_from_body(body, date)?, date)?;
+        if !use_llm || self.cleanup_llm().is_none() {
+            return Ok(clean::clean_daily(daily));
+        }
+
+        let daily = self.clean_up(daily).await?;
+
+        if let Some(cache) = &self.cache {
+            cache.store_daily(&daily, &self.entry_variant())?;
+        }
+
+        Ok(daily)
+    }
+
+    /// Fetches and parses the entry for `date`, with only the rule-based cleanup.
+    pub async fn get_parsed(&self, date: &str) -> Result<Daily> {
+        self.get_entry(date, false).await
+    }
+
+    /// Fetches, parses and cleans up the entry for `date` (e.g. "March 5").
+    pub async fn get_daily(&self, date: &str) -> Result<Daily> {
+        self.get_entry(date, true).await
+    }
+
+    /// [`get_daily`](Self::get_daily) when `use_llm` is set,
+    /// [`get_parsed`](Self::get_parsed) otherwise.
+    pub async fn get_entry(&self, date: &str, use_llm: bool) -> Result<Daily> {
+        match self.stored(date, use_llm)? {
+            Some(daily) => Ok(daily),
+            None => self.daily_from_body(&self.fetch_body_for(date).await?, date, use_llm).await,
+        }
+    }
+
+    /// Like [`get_daily`](Self::get_daily), with the cleanup streamed to
+    /// `on_text` as in [`clean_up_streaming`](Self::clean_up_streaming).
+    /// Stored entries come back without calling it.
+    pub async fn get_daily_streaming(&self, date: &str, on_text: &(dyn Fn(&str, &str) + Send + Sync)) -> Result<Daily> {
+        if let Some(daily) = self.stored(date, true)? {
+            return Ok(daily);
+        }
+
+        let daily = self.parse_entry(&self.get_raw(date).await?, date)?;
+        if self.cleanup_llm().is_none() {
+            return Ok(clean::clean_daily(daily));
+        }
+
+        let daily = self.clean_up_streaming(daily, on_text).await?;
+        if let Some(cache) = &self.cache {
+            cache.store_daily(&daily, &self.entry_variant())?;
+        }
+        Ok(daily)
+    }
+
+    /// Every entry from `from` through `to` inclusive, fetching the page at most once.
+    pub async fn get_range(&self, from: &str, to: &str, use_llm: bool) -> Result<Vec<Daily>> {
+        self.get_dates(&date::range(from, to), use_llm).await
+    }
+
+    /// The entries for `dates` in their order, fetching the page at most once.
+    pub async fn get_dates(&self, dates: &[String], use_llm: bool) -> Result<Vec<Daily>> {
+        let mut body: Option<String> = None;
+        let mut dailies = Vec::new();
+
+        for date in dates {
+            if let Some(daily) = self.stored(date, use_llm)? {
+                dailies.push(daily);
+                continue;
+            }
+
+            // a body without this date is swapped for the first mirror's with it
+            let body = match &body {
+                Some(known) if self.raw_from_body(known, date).is_ok() => known,
+                _ => body.insert(self.fetch_body_for(date).await?),
+            };
+            dailies.push(self.daily_from_body(body, date, use_llm).await?);
+        }
+
+        Ok(dailies)
+    }
+}
+
+async fn timed_llm(
+    llm: &dyn LlmProvider,
+    kind: &str,
+    request: impl Future<Output = Result<String>>,
+) -> Result<String> {
+    let start = Instant::now();
+    let text = request.await;
+    info!("{} request to {} took {:.2?}", kind, llm.name(), start.elapsed());
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BODY: &str = "March 5\nON CONTROL\nYou have power over your mind ,\nnot outside events.\n—MARCUS AURELIUS, MEDITATIONS, 6.8\nRealize this and you will find s t r e n g t h. Not the educa-\ntion of others.\nMarch 6\n";
+
+    #[tokio::test]
+    async fn passthrough_cleans_up_like_no_llm() {
+        let plain = DailyStoicClient::new("http://localhost");
+        let passthrough = DailyStoicClient::new("http://localhost").with_llm(Box::new(llm::Passthrough));
+
+        let expected = plain.daily_from_body(BODY, "March 5", false).await.unwrap();
+        let daily = passthrough.daily_from_body(BODY, "March 5", true).await.unwrap();
+        assert_eq!(serde_json::to_value(&daily).unwrap(), serde_json::to_value(&expected).unwrap());
+        assert_eq!(daily.explanation, "Realize this and you will find strength. Not the education of others.");
+    }
+}
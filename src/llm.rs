@@ -0,0 +1,891 @@
+use crate::config::LlmConfig;
+use crate::error::{DailyStoicError::{ConfigError, LlmError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::usage;
+use crate::Daily;
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use tracing::{info, warn};
+
+// room for the longest explanations, which 500 used to cut short
+const DEFAULT_MAX_TOKENS: u32 = 2048;
+
+/// Bump whenever `DEFAULT_CLEANUP_PROMPT` changes so cached corrections are not reused.
+pub const PROMPT_VERSION: u32 = 2;
+
+const DEFAULT_OPENAI_MODEL: &str = "openai/gpt-4o";
+const DEFAULT_ANTHROPIC_MODEL: &str = "claude-sonnet-4-5";
+const DEFAULT_ANTHROPIC_ENDPOINT: &str = "https://api.anthropic.com/v1/messages";
+// the latest GA version at the time, with stream usage
+const DEFAULT_AZURE_API_VERSION: &str = "2024-10-21";
+const DEFAULT_OLLAMA_MODEL: &str = "llama3.2";
+const DEFAULT_OLLAMA_HOST: &str = "http://localhost:11434";
+const DEFAULT_OPENAI_EMBEDDING_MODEL: &str = "text-embedding-3-small";
+const DEFAULT_OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+
+/// Tokens a provider reports for one completion.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct TokenUsage {
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64
+}
+
+impl TokenUsage {
+    // OpenAI's prompt/completion_tokens, Anthropic's input/output_tokens or
+    // Ollama's prompt_eval_count/eval_count, whichever the reply has
+    fn from_response(response: &Value) -> Option<Self> {
+        let count = |value: &Value| value.as_u64();
+        let usage = &response["usage"];
+        let (prompt, completion) = if usage.is_object() {
+            (
+                count(&usage["prompt_tokens"]).or_else(|| count(&usage["input_tokens"])),
+                count(&usage["completion_tokens"]).or_else(|| count(&usage["output_tokens"])),
+            )
+        } else {
+            (count(&response["prompt_eval_count"]), count(&response["eval_count"]))
+        };
+        if prompt.is_none() && completion.is_none() {
+            return None;
+        }
+        Some(TokenUsage { prompt_tokens: prompt.unwrap_or(0), completion_tokens: completion.unwrap_or(0) })
+    }
+}
+
+/// Called with each piece of a reply as it streams in.
+pub type OnText<'a> = dyn Fn(&str) + Send + Sync + 'a;
+
+/// A chat model that can be asked to clean up extracted text.
+#[async_trait]
+pub trait LlmProvider: Send + Sync {
+    /// Identifies the provider and model, e.g. "openai:openai/gpt-4o".
+    fn name(&self) -> String;
+
+    /// Whether `complete` can produce text at all, false for providers that
+    /// only hand the input back.
+    fn generates_text(&self) -> bool {
+        true
+    }
+
+    /// Sends a single user prompt and returns the model's reply.
+    async fn complete(&self, prompt: &str) -> Result<String>;
+
+    /// Like `complete`, along with the tokens the provider says the call used.
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        Ok((self.complete(prompt).await?, None))
+    }
+
+    /// Like `complete_with_usage`, handing each piece of the reply to `on_text`
+    /// as it streams in. Providers that can't stream hand over the whole reply.
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        let (text, usage) = self.complete_with_usage(prompt).await?;
+        on_text(&text);
+        Ok((text, usage))
+    }
+
+    /// Fixes extraction artifacts (broken words, stray line breaks, ...) in
+    /// `text` by sending `prompt`, a filled in cleanup template holding it.
+    async fn fix_text(&self, prompt: &str, _text: &str) -> Result<String> {
+        self.complete(prompt).await
+    }
+
+    /// `fix_text`, streamed like `complete_streaming`.
+    async fn fix_text_streaming(&self, prompt: &str, _text: &str, on_text: &OnText<'_>) -> Result<String> {
+        Ok(self.complete_streaming(prompt, on_text).await?.0)
+    }
+
+    /// Translates `text` into `language` (a name like "German" or a code like "de").
+    async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        self.complete(&translate_prompt(text, language)).await
+    }
+
+    /// Embeds `text` as a vector for semantic search.
+    async fn embed(&self, _text: &str) -> Result<Vec<f32>> {
+        Err(LlmError(format!("The {} provider does not support embeddings", self.name())))
+    }
+}
+
+/// The built-in cleanup instructions. Templates replacing them may use
+/// `{text}`, the text to fix, `{field}` ("quote" or "explanation"), and the
+/// entry's `{date}`, `{title}`, `{author}` and `{quoter}`.
+pub const DEFAULT_CLEANUP_PROMPT: &str = "Fix the text based on the following instructions:
+- Keep the quote as close to its original as possible.
+- Some words may be missing characters, combined together, or have a space in the middle of a word. Correct these.
+- Merge any line breaks that occur in the middle of a sentence.
+- Preserve paragraph breaks (indicated by empty lines or where appropriate).
+- Add an extra line break between paragraphs to improve readability.
+- Fix any missing characters or spacing issues in words.
+- Do not wrap the quote in quotation marks unless the text already has them.
+- If the line ends with a few lines with all caps that seem out of context, remove them.
+- Do not add any commentary or explanation—just output the corrected {field}.
+Text:
+{text}
+";
+
+/// `template` with its placeholders filled in for the `field` of `daily`
+/// holding `text`.
+///
+/// ```
+/// use daily_stoic_rs::{llm, Daily};
+///
+/// let daily = Daily { title: "On Patience".to_string(), ..Daily::default() };
+/// let prompt = llm::cleanup_prompt("Fix the {field} of \"{title}\": {text}", &daily, "quote", "Be  patient");
+/// assert_eq!(prompt, "Fix the quote of \"On Patience\": Be  patient");
+/// ```
+pub fn cleanup_prompt(template: &str, daily: &Daily, field: &str, text: &str) -> String {
+    template
+        .replace("{field}", field)
+        .replace("{date}", &daily.date)
+        .replace("{title}", &daily.title)
+        .replace("{author}", &daily.citation.author)
+        .replace("{quoter}", &daily.quoter)
+        // last, so braces in the text itself are left alone
+        .replace("{text}", text)
+}
+
+/// `$XDG_CONFIG_HOME/daily-stoic/prompts` (or the platform equivalent), where
+/// prompt templates are looked up by name.
+pub fn prompts_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("daily-stoic").join("prompts"))
+}
+
+/// The cleanup template `name` refers to: a path like "./archaic.txt", or a
+/// file in [`prompts_dir`] with or without its ".txt". Without a name that's
+/// `cleanup.txt` there if it exists, and [`DEFAULT_CLEANUP_PROMPT`] otherwise.
+pub fn load_cleanup_prompt(name: Option<&str>) -> Result<String> {
+    let dir = prompts_dir();
+    let path = match name {
+        Some(name) if Path::new(name).components().count() > 1 => PathBuf::from(name),
+        Some(name) => {
+            let file = if name.ends_with(".txt") { name.to_string() } else { format!("{}.txt", name) };
+            dir.ok_or_else(|| ConfigError("Could not determine the config dir for prompt templates".to_string()))?.join(file)
+        }
+        None => match dir.map(|dir| dir.join("cleanup.txt")).filter(|path| path.exists()) {
+            Some(path) => path,
+            None => return Ok(DEFAULT_CLEANUP_PROMPT.to_string()),
+        },
+    };
+
+    let template = fs::read_to_string(&path)
+        .map_err(|e| ConfigError(format!("Failed to read the prompt template {}: {}", path.display(), e)))?;
+    if !template.contains("{text}") {
+        return Err(ConfigError(format!("The prompt template {} has no {{text}} placeholder", path.display())));
+    }
+    Ok(template)
+}
+
+pub fn translate_prompt(text: &str, language: &str) -> String {
+    format!(
+        "Translate the following text into {}.\n\
+        - Keep the meaning, tone and paragraph breaks of the original.\n\
+        - Leave names of people and books as they are commonly written in that language.\n\
+        - Do not add any commentary or explanation—just output the translation.\n\
+        Text:\n{}",
+        language,
+        text
+    )
+}
+
+/// Asks for two paragraphs on the theme running through the entries of
+/// `month`, given their titles and quotes.
+pub fn month_prompt(month: &str, dailies: &[Daily]) -> String {
+    let entries: Vec<String> = dailies
+        .iter()
+        .map(|daily| format!("{}: {}\n{}", daily.date, daily.title.trim(), daily.quote.trim()))
+        .collect();
+    format!(
+        "Below are the titles and quotes of a month of daily Stoic meditations, for {}.\n\
+        Write two short paragraphs on the theme that runs through the month: what it asks of the reader \
+        and how the days build on each other.\n\
+        - Refer to the philosophers quoted where it helps, but do not list the entries one by one.\n\
+        - Output only the two paragraphs, separated by an empty line, without a heading.\n\
+        Entries:\n{}",
+        month,
+        entries.join("\n\n")
+    )
+}
+
+/// Selects a provider by `config.provider` (openai, azure, anthropic, ollama or
+/// none, defaulting to openai), configured by `endpoint`, `api_key`, `model`,
+/// `max_tokens` and `temperature`. For azure `endpoint` is the resource's url
+/// and `model` the deployment, for ollama `endpoint` is the server's base url
+/// and no key is needed. Returns `None`
+/// when no LLM is configured at all. Real providers are [`usage::Metered`], so
+/// their calls end up in the usage ledger and count against `monthly_budget`,
+/// and with `fallback` entries they are chained in a [`Fallback`].
+pub fn provider_from_config(
+    config: &LlmConfig,
+    client: &reqwest::Client,
+    retry: RetryPolicy,
+) -> Result<Option<Box<dyn LlmProvider>>> {
+    if config.is_empty() {
+        return Ok(None);
+    }
+
+    let first = provider(config, client, retry)?;
+    if config.fallback.is_empty() {
+        return Ok(Some(first));
+    }
+    let mut providers = vec![first];
+    for fallback in config.fallbacks() {
+        providers.push(provider(&fallback, client, retry)?);
+    }
+    Ok(Some(Box::new(Fallback::new(providers))))
+}
+
+fn provider(config: &LlmConfig, client: &reqwest::Client, retry: RetryPolicy) -> Result<Box<dyn LlmProvider>> {
+    let endpoint = config.endpoint.clone();
+    let key = config.api_key.clone();
+    let model = config.model.clone();
+    let embedding_model = config.embedding_model.clone();
+    let max_tokens = config.max_tokens.unwrap_or(DEFAULT_MAX_TOKENS);
+    let temperature = config.temperature;
+
+    let provider: Box<dyn LlmProvider> = match config.provider.as_deref().unwrap_or("openai") {
+        "openai" => Box::new(OpenAiCompatible {
+            endpoint: endpoint.ok_or_else(|| ConfigError("No LLM endpoint configured (llm.endpoint)".to_string()))?,
+            api_key: key.ok_or_else(|| ConfigError("No LLM API key configured (llm.api_key)".to_string()))?,
+            model: model.unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string()),
+            embedding_model: embedding_model.unwrap_or_else(|| DEFAULT_OPENAI_EMBEDDING_MODEL.to_string()),
+            max_tokens,
+            temperature,
+            client: client.clone(),
+            retry,
+        }),
+        "azure" => Box::new(Azure {
+            endpoint: endpoint.ok_or_else(|| ConfigError("No Azure resource url configured (llm.endpoint)".to_string()))?,
+            api_key: key.ok_or_else(|| ConfigError("No LLM API key configured (llm.api_key)".to_string()))?,
+            deployment: model.ok_or_else(|| ConfigError("No Azure deployment configured (llm.model)".to_string()))?,
+            embedding_deployment: embedding_model.unwrap_or_else(|| DEFAULT_OPENAI_EMBEDDING_MODEL.to_string()),
+            api_version: config.api_version.clone().unwrap_or_else(|| DEFAULT_AZURE_API_VERSION.to_string()),
+            max_tokens,
+            temperature,
+            client: client.clone(),
+            retry,
+        }),
+        "anthropic" => Box::new(Anthropic {
+            endpoint: endpoint.unwrap_or_else(|| DEFAULT_ANTHROPIC_ENDPOINT.to_string()),
+            api_key: key.ok_or_else(|| ConfigError("No LLM API key configured (llm.api_key)".to_string()))?,
+            model: model.unwrap_or_else(|| DEFAULT_ANTHROPIC_MODEL.to_string()),
+            max_tokens,
+            temperature,
+            client: client.clone(),
+            retry,
+        }),
+        "ollama" => Box::new(Ollama {
+            host: endpoint.unwrap_or_else(|| DEFAULT_OLLAMA_HOST.to_string()),
+            model: model.unwrap_or_else(|| DEFAULT_OLLAMA_MODEL.to_string()),
+            embedding_model: embedding_model.unwrap_or_else(|| DEFAULT_OLLAMA_EMBEDDING_MODEL.to_string()),
+            max_tokens,
+            temperature,
+            client: client.clone(),
+            retry,
+        }),
+        "none" => return Ok(Box::new(Passthrough)),
+        other => return Err(ConfigError(format!("Unknown LLM provider \"{}\" (expected openai, azure, anthropic, ollama or none)", other))),
+    };
+
+    let Some(path) = usage::Ledger::default_path() else {
+        return Ok(provider);
+    };
+    Ok(Box::new(usage::Metered {
+        inner: provider,
+        ledger: usage::Ledger::new(&path),
+        prices: config.prices.clone(),
+        budget: config.monthly_budget,
+    }))
+}
+
+/// Any endpoint speaking the OpenAI chat completions protocol.
+pub struct OpenAiCompatible {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    pub embedding_model: String,
+    pub max_tokens: u32,
+    /// The provider's default when `None`
+    pub temperature: Option<f64>,
+    pub client: reqwest::Client,
+    pub retry: RetryPolicy
+}
+
+impl OpenAiCompatible {
+    async fn send(&self, prompt: &str, stream: bool) -> Result<reqwest::Response> {
+        let body = chat_body(&self.model, prompt, self.max_tokens, self.temperature, stream);
+
+        let client = &self.client;
+        http::send_with_retry(&self.retry, || {
+            client
+                .post(&self.endpoint)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&body)
+        }).await.map_err(|e| LlmError(format!("LLM {}", e)))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for OpenAiCompatible {
+    fn name(&self) -> String {
+        format!("openai:{}", self.model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        Ok(self.complete_with_usage(prompt).await?.0)
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        chat_reply(&parse_response(self.send(prompt, false).await?).await?)
+    }
+
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        chat_stream(self.send(prompt, true).await?, on_text).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        // the embeddings route sits next to the chat completions one
+        let endpoint = match self.endpoint.strip_suffix("/chat/completions") {
+            Some(base) => format!("{}/embeddings", base),
+            None => return Err(LlmError(format!(
+                "Can't derive an embeddings endpoint from {} (expected it to end in /chat/completions)",
+                self.endpoint
+            ))),
+        };
+        let body = json!({ "model": self.embedding_model, "input": text });
+
+        let client = &self.client;
+        let response = http::send_with_retry(&self.retry, || {
+            client
+                .post(&endpoint)
+                .header("Authorization", format!("Bearer {}", self.api_key))
+                .json(&body)
+        }).await.map_err(|e| LlmError(format!("LLM {}", e)))?;
+
+        let response_json = parse_response(response).await?;
+        parse_vector(&response_json["data"][0]["embedding"])
+    }
+}
+
+/// An Azure OpenAI resource. It speaks the OpenAI protocol, but models are
+/// deployments addressed by url, with an `api-version` query parameter and
+/// the key in an `api-key` header.
+pub struct Azure {
+    /// The resource's url, e.g. https://my-resource.openai.azure.com
+    pub endpoint: String,
+    pub api_key: String,
+    pub deployment: String,
+    pub embedding_deployment: String,
+    pub api_version: String,
+    pub max_tokens: u32,
+    /// The provider's default when `None`
+    pub temperature: Option<f64>,
+    pub client: reqwest::Client,
+    pub retry: RetryPolicy
+}
+
+impl Azure {
+    fn url(&self, deployment: &str, route: &str) -> String {
+        format!(
+            "{}/openai/deployments/{}/{}?api-version={}",
+            self.endpoint.trim_end_matches('/'),
+            deployment,
+            route,
+            self.api_version
+        )
+    }
+
+    async fn post(&self, url: &str, body: &Value) -> Result<reqwest::Response> {
+        let client = &self.client;
+        http::send_with_retry(&self.retry, || {
+            client
+                .post(url)
+                .header("api-key", &self.api_key)
+                .json(body)
+        }).await.map_err(|e| LlmError(format!("LLM {}", e)))
+    }
+
+    async fn send(&self, prompt: &str, stream: bool) -> Result<reqwest::Response> {
+        let body = chat_body(&self.deployment, prompt, self.max_tokens, self.temperature, stream);
+        self.post(&self.url(&self.deployment, "chat/completions"), &body).await
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Azure {
+    fn name(&self) -> String {
+        format!("azure:{}", self.deployment)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        Ok(self.complete_with_usage(prompt).await?.0)
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        chat_reply(&parse_response(self.send(prompt, false).await?).await?)
+    }
+
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        chat_stream(self.send(prompt, true).await?, on_text).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let body = json!({ "input": text });
+        let response = self.post(&self.url(&self.embedding_deployment, "embeddings"), &body).await?;
+        let response_json = parse_response(response).await?;
+        parse_vector(&response_json["data"][0]["embedding"])
+    }
+}
+
+// an OpenAI chat completions request, which Azure shares
+fn chat_body(model: &str, prompt: &str, max_tokens: u32, temperature: Option<f64>, stream: bool) -> Value {
+    let mut body = json!({
+        "model": model,
+        "messages": [{ "role": "user", "content": prompt }],
+        "max_tokens": max_tokens
+    });
+    if let Some(temperature) = temperature {
+        body["temperature"] = json!(temperature);
+    }
+    if stream {
+        body["stream"] = json!(true);
+        body["stream_options"] = json!({ "include_usage": true });
+    }
+    body
+}
+
+fn chat_reply(response_json: &Value) -> Result<(String, Option<TokenUsage>)> {
+    let corrected_text = response_json["choices"][0]["message"]["content"]
+        .as_str()
+        .ok_or_else(|| LlmError("Failed to extract content from LLM response".to_string()))?
+        .to_string();
+
+    Ok((corrected_text, TokenUsage::from_response(response_json)))
+}
+
+async fn chat_stream(response: reqwest::Response, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+    let mut text = String::new();
+    let mut usage = None;
+    read_lines(response, |line| {
+        let Some(event) = sse_data(line) else { return Ok(()) };
+        check_error(&event)?;
+        if let Some(delta) = event["choices"][0]["delta"]["content"].as_str() {
+            text.push_str(delta);
+            on_text(delta);
+        }
+        // the last event has the usage and no choices
+        usage = TokenUsage::from_response(&event).or(usage);
+        Ok(())
+    }).await?;
+    Ok((text, usage))
+}
+
+/// Anthropic's messages API.
+pub struct Anthropic {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    pub max_tokens: u32,
+    /// The provider's default when `None`
+    pub temperature: Option<f64>,
+    pub client: reqwest::Client,
+    pub retry: RetryPolicy
+}
+
+impl Anthropic {
+    async fn send(&self, prompt: &str, stream: bool) -> Result<reqwest::Response> {
+        let mut body = json!({
+            "model": self.model,
+            "messages": [{ "role": "user", "content": prompt }],
+            "max_tokens": self.max_tokens,
+            "stream": stream
+        });
+        if let Some(temperature) = self.temperature {
+            body["temperature"] = json!(temperature);
+        }
+
+        let client = &self.client;
+        http::send_with_retry(&self.retry, || {
+            client
+                .post(&self.endpoint)
+                .header("x-api-key", &self.api_key)
+                .header("anthropic-version", "2023-06-01")
+                .json(&body)
+        }).await.map_err(|e| LlmError(format!("LLM {}", e)))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Anthropic {
+    fn name(&self) -> String {
+        format!("anthropic:{}", self.model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        Ok(self.complete_with_usage(prompt).await?.0)
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        let response_json = parse_response(self.send(prompt, false).await?).await?;
+
+        let corrected_text = response_json["content"][0]["text"]
+            .as_str()
+            .ok_or_else(|| LlmError("Failed to extract content from LLM response".to_string()))?
+            .to_string();
+
+        Ok((corrected_text, TokenUsage::from_response(&response_json)))
+    }
+
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        let mut text = String::new();
+        let mut usage = TokenUsage::default();
+        read_lines(self.send(prompt, true).await?, |line| {
+            let Some(event) = sse_data(line) else { return Ok(()) };
+            check_error(&event)?;
+            match event["type"].as_str() {
+                Some("message_start") => {
+                    usage.prompt_tokens = event["message"]["usage"]["input_tokens"].as_u64().unwrap_or(0);
+                }
+                Some("content_block_delta") => {
+                    if let Some(delta) = event["delta"]["text"].as_str() {
+                        text.push_str(delta);
+                        on_text(delta);
+                    }
+                }
+                Some("message_delta") => {
+                    usage.completion_tokens = event["usage"]["output_tokens"].as_u64().unwrap_or(0);
+                }
+                _ => {}
+            }
+            Ok(())
+        }).await?;
+        Ok((text, Some(usage)))
+    }
+}
+
+/// A local Ollama server, no API key required.
+pub struct Ollama {
+    pub host: String,
+    pub model: String,
+    pub embedding_model: String,
+    pub max_tokens: u32,
+    /// The provider's default when `None`
+    pub temperature: Option<f64>,
+    pub client: reqwest::Client,
+    pub retry: RetryPolicy
+}
+
+impl Ollama {
+    async fn send(&self, prompt: &str, stream: bool) -> Result<reqwest::Response> {
+        let mut body = json!({
+            "model": self.model,
+            "messages": [{ "role": "user", "content": prompt }],
+            "stream": stream,
+            "options": { "num_predict": self.max_tokens }
+        });
+        if let Some(temperature) = self.temperature {
+            body["options"]["temperature"] = json!(temperature);
+        }
+
+        let url = format!("{}/api/chat", self.host.trim_end_matches('/'));
+
+        let client = &self.client;
+        http::send_with_retry(&self.retry, || client.post(&url).json(&body))
+            .await
+            .map_err(|e| LlmError(format!("LLM {}", e)))
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Ollama {
+    fn name(&self) -> String {
+        format!("ollama:{}", self.model)
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        Ok(self.complete_with_usage(prompt).await?.0)
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        let response_json = parse_response(self.send(prompt, false).await?).await?;
+
+        let corrected_text = response_json["message"]["content"]
+            .as_str()
+            .ok_or_else(|| LlmError("Failed to extract content from LLM response".to_string()))?
+            .to_string();
+
+        Ok((corrected_text, TokenUsage::from_response(&response_json)))
+    }
+
+    // one JSON object per line, the last one with "done" and the token counts
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        let mut text = String::new();
+        let mut usage = None;
+        read_lines(self.send(prompt, true).await?, |line| {
+            let Ok(event) = serde_json::from_str::<Value>(line) else { return Ok(()) };
+            check_error(&event)?;
+            if let Some(delta) = event["message"]["content"].as_str() {
+                text.push_str(delta);
+                on_text(delta);
+            }
+            if event["done"].as_bool() == Some(true) {
+                usage = TokenUsage::from_response(&event);
+            }
+            Ok(())
+        }).await?;
+        Ok((text, usage))
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        let body = json!({ "model": self.embedding_model, "input": text });
+        let url = format!("{}/api/embed", self.host.trim_end_matches('/'));
+
+        let client = &self.client;
+        let response = http::send_with_retry(&self.retry, || client.post(&url).json(&body))
+            .await
+            .map_err(|e| LlmError(format!("LLM {}", e)))?;
+
+        let response_json = parse_response(response).await?;
+        parse_vector(&response_json["embeddings"][0])
+    }
+}
+
+/// Tries each provider in turn until one succeeds, for when the first is out
+/// of quota, rate limited or down. It goes by the name of the provider that
+/// gave the latest reply, the first one until any has, so cached replies and
+/// logs are credited to the model that wrote them. Embeddings only come from
+/// the first, as vectors of different models can't be compared.
+pub struct Fallback {
+    providers: Vec<Box<dyn LlmProvider>>,
+    // index of the provider that gave the latest reply
+    answered: AtomicUsize,
+}
+
+impl Fallback {
+    pub fn new(providers: Vec<Box<dyn LlmProvider>>) -> Self {
+        Fallback { providers, answered: AtomicUsize::new(0) }
+    }
+
+    // `call` gets each provider along with whether it's the last one left
+    async fn first_ok<'a, T>(
+        &'a self,
+        call: impl Fn(&'a dyn LlmProvider, bool) -> Pin<Box<dyn Future<Output = Result<T>> + Send + 'a>> + Send,
+    ) -> Result<T> {
+        let mut last_error = None;
+        for (i, provider) in self.providers.iter().enumerate() {
+            let next = self.providers.get(i + 1);
+            match call(provider.as_ref(), next.is_none()).await {
+                Ok(reply) => {
+                    info!("Reply from {}", provider.name());
+                    self.answered.store(i, Ordering::Relaxed);
+                    return Ok(reply);
+                }
+                Err(e) => {
+                    if let Some(next) = next {
+                        warn!("{} failed, falling back to {}: {}", provider.name(), next.name(), e);
+                    }
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or_else(|| LlmError("No LLM providers configured".to_string())))
+    }
+}
+
+// Replies are only streamed from the last provider. The ones before it are
+// awaited whole and handed to `on_text` once they succeed, so the text of one
+// that fails midway never mixes with the reply of the next.
+#[async_trait]
+impl LlmProvider for Fallback {
+    fn name(&self) -> String {
+        self.providers
+            .get(self.answered.load(Ordering::Relaxed))
+            .map(|provider| provider.name())
+            .unwrap_or_default()
+    }
+
+    fn generates_text(&self) -> bool {
+        self.providers.iter().any(|provider| provider.generates_text())
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        self.first_ok(|provider, _| provider.complete(prompt)).await
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        self.first_ok(|provider, _| provider.complete_with_usage(prompt)).await
+    }
+
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        self.first_ok(|provider, last| {
+            if last {
+                return provider.complete_streaming(prompt, on_text);
+            }
+            Box::pin(async move {
+                let (text, usage) = provider.complete_with_usage(prompt).await?;
+                on_text(&text);
+                Ok((text, usage))
+            })
+        })
+        .await
+    }
+
+    async fn fix_text(&self, prompt: &str, text: &str) -> Result<String> {
+        self.first_ok(|provider, _| provider.fix_text(prompt, text)).await
+    }
+
+    async fn fix_text_streaming(&self, prompt: &str, text: &str, on_text: &OnText<'_>) -> Result<String> {
+        self.first_ok(|provider, last| {
+            if last {
+                return provider.fix_text_streaming(prompt, text, on_text);
+            }
+            Box::pin(async move {
+                let fixed = provider.fix_text(prompt, text).await?;
+                on_text(&fixed);
+                Ok(fixed)
+            })
+        })
+        .await
+    }
+
+    async fn translate(&self, text: &str, language: &str) -> Result<String> {
+        self.first_ok(|provider, _| provider.translate(text, language)).await
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        match self.providers.first() {
+            Some(provider) => provider.embed(text).await,
+            None => Err(LlmError("No LLM providers configured".to_string())),
+        }
+    }
+}
+
+/// Leaves text untouched, for running without any model.
+pub struct Passthrough;
+
+#[async_trait]
+impl LlmProvider for Passthrough {
+    fn name(&self) -> String {
+        "none".to_string()
+    }
+
+    fn generates_text(&self) -> bool {
+        false
+    }
+
+    async fn complete(&self, _prompt: &str) -> Result<String> {
+        Err(LlmError("The passthrough LLM provider cannot generate text".to_string()))
+    }
+
+    async fn fix_text(&self, _prompt: &str, text: &str) -> Result<String> {
+        Ok(text.to_string())
+    }
+
+    async fn fix_text_streaming(&self, _prompt: &str, text: &str, on_text: &OnText<'_>) -> Result<String> {
+        on_text(text);
+        Ok(text.to_string())
+    }
+}
+
+// OpenAI and Anthropic report failures as {"error": {"message": ...}}, Ollama as {"error": "..."}
+fn check_error(response_json: &Value) -> Result<()> {
+    if let Some(error) = response_json.get("error") {
+        if let Some(message) = error.as_str() {
+            return Err(LlmError(format!("Request to format text with LLM resulted in an error: {}", message)));
+        } else if let Some(message) = error.get("message") { 
+            return Err(LlmError(format!("Request to format text with LLM resulted in an error: {}", message)));
+        } else { 
+            return Err(LlmError("Request to format text with LLM resulted in an error and no message was found.".to_string()));
+        }
+    } 
+    Ok(())
+}
+
+async fn parse_response(response: reqwest::Response) -> Result<Value> {
+    let response_json: Value = response
+        .json()
+        .await
+        .map_err(|e| LlmError(format!("Failed to parse LLM response JSON: {}", e)))?;
+
+    check_error(&response_json)?;
+    Ok(response_json)
+}
+
+// hands each line of a streamed reply to `on_line` as soon as it is complete;
+// failures come back as a plain JSON error instead of a stream
+async fn read_lines(mut response: reqwest::Response, mut on_line: impl FnMut(&str) -> Result<()>) -> Result<()> {
+    let status = response.status();
+    if !status.is_success() {
+        parse_response(response).await?;
+        return Err(LlmError(format!("LLM request failed with status {}", status)));
+    }
+
+    let mut buffer = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|e| LlmError(format!("Failed to read the LLM stream: {}", e)))? {
+        buffer.extend_from_slice(&chunk);
+        while let Some(end) = buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            on_line(String::from_utf8_lossy(&line).trim_end())?;
+        }
+    }
+    if !buffer.is_empty() {
+        on_line(String::from_utf8_lossy(&buffer).trim_end())?;
+    }
+    Ok(())
+}
+
+// the JSON of a server-sent event's "data:" line, None for other lines and OpenAI's closing [DONE]
+fn sse_data(line: &str) -> Option<Value> {
+    let data = line.strip_prefix("data:")?.trim();
+    serde_json::from_str(data).ok()
+}
+
+fn parse_vector(value: &Value) -> Result<Vec<f32>> {
+    value
+        .as_array()
+        .and_then(|values| values.iter().map(|v| v.as_f64().map(|f| f as f32)).collect())
+        .ok_or_else(|| LlmError("Failed to extract the embedding from LLM response".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // streams half of its reply, then fails like a dropped connection
+    struct CutOff;
+
+    #[async_trait]
+    impl LlmProvider for CutOff {
+        fn name(&self) -> String {
+            "cut-off".to_string()
+        }
+
+        async fn complete(&self, _prompt: &str) -> Result<String> {
+            Err(LlmError("connection reset".to_string()))
+        }
+
+        async fn complete_streaming(&self, _prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+            on_text("Half a rep");
+            Err(LlmError("connection reset".to_string()))
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_only_the_reply_of_the_provider_that_answered() {
+        let fallback = Fallback::new(vec![Box::new(CutOff), Box::new(Passthrough)]);
+        assert_eq!(fallback.name(), "cut-off");
+
+        let streamed = Mutex::new(String::new());
+        let text = fallback
+            .fix_text_streaming("prompt", "The whole reply", &|piece| streamed.lock().unwrap().push_str(piece))
+            .await
+            .unwrap();
+
+        assert_eq!(text, "The whole reply");
+        assert_eq!(*streamed.lock().unwrap(), "The whole reply");
+        assert_eq!(fallback.name(), "none");
+    }
+}
@@ -0,0 +1,185 @@
+//! Interface labels and error headings in the reader's language, picked by
+//! `--locale` or the usual `LC_ALL`, `LC_MESSAGES` and `LANG` variables. This is
+//! separate from `--translate`, which translates the entry itself.
+
+use crate::error::DailyStoicError;
+use std::env;
+
+/// The words around the entry for one language.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Locale {
+    /// ISO 639-1 code, e.g. "de"
+    pub code: &'static str,
+    pub date: &'static str,
+    pub title: &'static str,
+    pub quote: &'static str,
+    pub quoter: &'static str,
+    pub explanation: &'static str,
+    pub error: &'static str,
+    /// "No match found for {date}"
+    pub not_found: &'static str,
+    // headings for the kinds of library errors
+    network: &'static str,
+    parse: &'static str,
+    llm: &'static str,
+    config: &'static str,
+    storage: &'static str
+}
+
+pub const EN: Locale = Locale {
+    code: "en",
+    date: "Date",
+    title: "Title",
+    quote: "Quote",
+    quoter: "Quoter",
+    explanation: "Explanation",
+    error: "Error",
+    not_found: "No match found for {date}",
+    network: "network",
+    parse: "parsing",
+    llm: "LLM",
+    config: "configuration",
+    storage: "storage"
+};
+
+pub const LOCALES: [Locale; 7] = [
+    EN,
+    Locale {
+        code: "de",
+        date: "Datum",
+        title: "Titel",
+        quote: "Zitat",
+        quoter: "Quelle",
+        explanation: "Erläuterung",
+        error: "Fehler",
+        not_found: "Kein Eintrag für {date} gefunden",
+        network: "Netzwerk",
+        parse: "Verarbeitung",
+        llm: "LLM",
+        config: "Konfiguration",
+        storage: "Speicher"
+    },
+    Locale {
+        code: "fr",
+        date: "Date",
+        title: "Titre",
+        quote: "Citation",
+        quoter: "Auteur",
+        explanation: "Explication",
+        error: "Erreur",
+        not_found: "Aucune entrée trouvée pour {date}",
+        network: "réseau",
+        parse: "analyse",
+        llm: "LLM",
+        config: "configuration",
+        storage: "stockage"
+    },
+    Locale {
+        code: "es",
+        date: "Fecha",
+        title: "Título",
+        quote: "Cita",
+        quoter: "Autor",
+        explanation: "Explicación",
+        error: "Error",
+        not_found: "No se encontró ninguna entrada para {date}",
+        network: "red",
+        parse: "análisis",
+        llm: "LLM",
+        config: "configuración",
+        storage: "almacenamiento"
+    },
+    Locale {
+        code: "it",
+        date: "Data",
+        title: "Titolo",
+        quote: "Citazione",
+        quoter: "Autore",
+        explanation: "Spiegazione",
+        error: "Errore",
+        not_found: "Nessuna voce trovata per {date}",
+        network: "rete",
+        parse: "analisi",
+        llm: "LLM",
+        config: "configurazione",
+        storage: "archiviazione"
+    },
+    Locale {
+        code: "pt",
+        date: "Data",
+        title: "Título",
+        quote: "Citação",
+        quoter: "Autor",
+        explanation: "Explicação",
+        error: "Erro",
+        not_found: "Nenhuma entrada encontrada para {date}",
+        network: "rede",
+        parse: "análise",
+        llm: "LLM",
+        config: "configuração",
+        storage: "armazenamento"
+    },
+    Locale {
+        code: "nl",
+        date: "Datum",
+        title: "Titel",
+        quote: "Citaat",
+        quoter: "Bron",
+        explanation: "Toelichting",
+        error: "Fout",
+        not_found: "Geen item gevonden voor {date}",
+        network: "netwerk",
+        parse: "verwerking",
+        llm: "LLM",
+        config: "configuratie",
+        storage: "opslag"
+    },
+];
+
+impl Default for Locale {
+    fn default() -> Self {
+        EN
+    }
+}
+
+impl Locale {
+    /// The locale for a tag like "de", "de_DE.UTF-8" or "pt-BR", `None` when
+    /// there are no labels for its language.
+    ///
+    /// ```
+    /// use daily_stoic_rs::locale::Locale;
+    ///
+    /// assert_eq!(Locale::parse("de_DE.UTF-8").map(|l| l.code), Some("de"));
+    /// assert_eq!(Locale::parse("pt-BR").map(|l| l.title), Some("Título"));
+    /// assert_eq!(Locale::parse("C"), None);
+    /// ```
+    pub fn parse(tag: &str) -> Option<Locale> {
+        let language = tag.split(['_', '-', '.', '@']).next()?.to_lowercase();
+        LOCALES.into_iter().find(|locale| locale.code == language)
+    }
+
+    /// The first of `LC_ALL`, `LC_MESSAGES` and `LANG` that is set, English when
+    /// none is or its language has no labels.
+    pub fn from_env() -> Locale {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .find_map(|name| env::var(name).ok().filter(|value| !value.is_empty()))
+            .and_then(|tag| Locale::parse(&tag))
+            .unwrap_or_default()
+    }
+
+    /// `error` for printing after [`Locale::error`]: the date not found message
+    /// translated, other library errors headed by their kind. The details come
+    /// from the source or APIs and stay as they are.
+    pub fn describe(&self, error: &DailyStoicError) -> String {
+        let kind = match error {
+            DailyStoicError::DateNotFound { date } => return self.not_found.replace("{date}", date),
+            DailyStoicError::NetworkError(_) => self.network,
+            DailyStoicError::ParseError(_) => self.parse,
+            DailyStoicError::LlmError(_) => self.llm,
+            DailyStoicError::ConfigError(_) => self.config,
+            DailyStoicError::StorageError(_) => self.storage,
+        };
+        if self.code == EN.code { error.to_string() } else { format!("({}) {}", kind, error) }
+    }
+}
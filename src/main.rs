@@ -1,230 +1,2081 @@
-extern crate reqwest;
-extern crate chrono;
 extern crate dotenv;
 
-use chrono::{NaiveDate, Days};
-use chrono::prelude::*;
+mod commands;
+mod pager;
+mod progress;
+
+use chrono::{Datelike, Days, NaiveDate, NaiveTime};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::{ArgValueCompleter, CompleteEnv, Shell};
+use daily_stoic_rs::archive::{Archive, DayStatus};
+use daily_stoic_rs::cache::Cache;
+use daily_stoic_rs::config::Config;
+use daily_stoic_rs::diff;
+use daily_stoic_rs::error::DailyStoicError;
+use daily_stoic_rs::export::notion::Notion;
+use daily_stoic_rs::feed::{self, FeedFormat};
+use daily_stoic_rs::http::RetryPolicy;
+use daily_stoic_rs::locale::{self, Locale};
+use daily_stoic_rs::render::{self, OutputFormat};
+use daily_stoic_rs::send::telegram::Telegram;
+use daily_stoic_rs::site::Protocol;
+use daily_stoic_rs::svg::{self, SvgStyle};
+use daily_stoic_rs::theme::{self, Theme};
+use daily_stoic_rs::usage::Ledger;
+use daily_stoic_rs::{banner, books, card, citation, credentials, date, export, llm, parse, semantic, send, site, tts, Daily, DailyStoicClient};
 use dotenv::dotenv;
-use serde_json::json;
-use std::env;
+use futures::stream::{self, StreamExt};
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
+use std::fs;
+use std::io::{self, IsTerminal};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::{Duration, Instant};
+use tracing::level_filters::LevelFilter;
+use tracing::warn;
+
+// banners drawn for a pipe or with --width 0 still need some bound
+const BANNER_WIDTH: usize = 80;
+// login messages are read on all sorts of terminals
+const MOTD_WIDTH: usize = 78;
+// lines of the body shown around the date with -v and no --context-lines
+const CONTEXT_LINES: usize = 5;
+
+/// Daily meditations from Ryan Holiday's The Daily Stoic.
+#[derive(Parser)]
+#[command(name = "daily-stoic", version)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// Print the extracted text as is, without parsing or cleanup
+    #[arg(long, global = true)]
+    raw: bool,
+
+    /// Show what the cleanup changed, as a word diff of the extracted entry against the cleaned up one
+    #[arg(long, global = true, conflicts_with_all = ["raw", "no_llm", "format", "template", "bilingual", "banner", "translate"])]
+    diff: bool,
+
+    /// Output format: text, json, markdown, html, svg, hugo, jekyll, org, gemtext [default: text]
+    #[arg(long, global = true, value_parser = parse_output_format)]
+    format: Option<String>,
+
+    /// Clean up the quote and explanation with simple rules instead of the LLM
+    #[arg(long, global = true)]
+    no_llm: bool,
+
+    /// Translate the title, quote and explanation into this language with the LLM, e.g. German or de
+    #[arg(long, global = true, value_name = "LANG", conflicts_with = "no_llm")]
+    translate: Option<String>,
+
+    /// Render entries with this Tera template instead of --format, with all entry fields available
+    #[arg(long, global = true, value_name = "PATH", conflicts_with_all = ["format", "bilingual"])]
+    template: Option<PathBuf>,
+
+    /// Print the text interleaved with its translation into this language, side by side on wide terminals
+    #[arg(long, global = true, value_name = "LANG", conflicts_with_all = ["no_llm", "translate", "format"])]
+    bilingual: Option<String>,
+
+    /// Print the title in block letters and the quote in a box, as a greeting
+    #[arg(long, global = true, conflicts_with_all = ["format", "template", "bilingual"])]
+    banner: bool,
+
+    /// Also copy the quote and who said it to the clipboard
+    #[arg(long, global = true)]
+    copy: bool,
+
+    /// Language of labels and error headings, e.g. de or fr [default: from LANG]
+    #[arg(long, global = true, value_name = "LANG", value_parser = parse_locale)]
+    locale: Option<Locale>,
+
+    /// Wrap the text output at N columns, 0 to not wrap [default: the terminal width]
+    #[arg(long, global = true, value_name = "N")]
+    width: Option<usize>,
+
+    /// When the date can't be found, or always with -v, print N lines of the body around where it was expected [default with -v: 5]
+    #[arg(long, global = true, value_name = "N")]
+    context_lines: Option<usize>,
+
+    /// Ignore the local cache and always fetch and clean up from scratch
+    #[arg(long, global = true)]
+    no_cache: bool,
+
+    /// Ask the source whether its page changed rather than use the cached copy for up to a day
+    #[arg(long, global = true, conflicts_with = "no_cache")]
+    refresh: bool,
+
+    /// Config file to use instead of ~/.config/daily-stoic/config.toml
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Url of the page holding the book's text, a path to a local copy (text, HTML, PDF or EPUB), or - for stdin
+    #[arg(long, global = true, value_name = "URL", visible_alias = "source")]
+    source_url: Option<String>,
+
+    /// Copy of the source to try when it fails or lacks the date, in order (repeatable)
+    #[arg(long = "mirror", global = true, value_name = "URL")]
+    mirrors: Vec<String>,
+
+    /// Daily reader to use: daily-stoic, daily-dad, daily-laws or a [books.<name>] table of the config
+    #[arg(long, global = true, value_name = "NAME")]
+    book: Option<String>,
+
+    /// LLM provider for the cleanup: openai, azure, anthropic, ollama or none
+    #[arg(long, global = true, value_name = "PROVIDER")]
+    llm_provider: Option<String>,
+
+    /// LLM endpoint (or base url for ollama)
+    #[arg(long, global = true, value_name = "URL")]
+    endpoint: Option<String>,
+
+    /// LLM model name
+    #[arg(long, global = true)]
+    model: Option<String>,
+
+    /// LLM sampling temperature, lower for more literal cleanups
+    #[arg(long, global = true)]
+    temperature: Option<f64>,
+
+    /// Longest LLM reply in tokens
+    #[arg(long, global = true, value_name = "N")]
+    max_tokens: Option<u32>,
+
+    /// Proxy url for source and LLM requests, instead of HTTP_PROXY and HTTPS_PROXY
+    #[arg(long, global = true, value_name = "URL")]
+    proxy: Option<String>,
+
+    /// Seconds to wait for a connection or more of a reply
+    #[arg(long, global = true, value_name = "SECS")]
+    timeout: Option<u64>,
+
+    /// User-Agent of source and LLM requests
+    #[arg(long, global = true, value_name = "TEXT")]
+    user_agent: Option<String>,
+
+    /// Extra header on the source fetch, as "Name: value" (repeatable)
+    #[arg(long = "header", global = true, value_name = "HEADER")]
+    headers: Vec<String>,
+
+    /// Directory for cached pages and entries
+    #[arg(long, global = true, value_name = "PATH")]
+    cache_dir: Option<PathBuf>,
+
+    /// Print straight to the terminal even when the output is taller than it
+    #[arg(long, global = true)]
+    no_pager: bool,
+
+    /// Log what is fetched, parsed and sent to the LLM on stderr (-vv for more detail)
+    #[arg(short, long, global = true, action = ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Only log errors
+    #[arg(short, long, global = true)]
+    quiet: bool,
+}
+
+impl Cli {
+    // config file < env vars < these flags
+    fn load_config(&self) -> Result<Config, DailyStoicError> {
+        let mut config = match self.config.clone().or_else(Config::default_path) {
+            Some(path) => Config::load(&path)?,
+            None => Config::default(),
+        };
+        config.apply_env()?;
+        config.apply_credentials();
+        // before the flags below, so --source-url still beats the book's own source
+        if let Some(book) = &self.book {
+            config.book = Some(book.clone());
+        }
+        config.apply_book()?;
+
+        if let Some(url) = &self.source_url {
+            // the configured mirrors are copies of another source
+            config.source_url = Some(url.clone());
+            config.source_mirrors.clear();
+        }
+        if !self.mirrors.is_empty() {
+            config.source_mirrors = self.mirrors.clone();
+        }
+        // feed's own --format takes the place of this one, see parse_feed_format
+        if let Some(format) = &self.format
+            && !matches!(self.command, Some(Command::Feed { .. }))
+        {
+            config.format = Some(format.clone());
+        }
+        if let Some(dir) = &self.cache_dir {
+            config.cache_dir = Some(dir.clone());
+        }
+        if let Some(provider) = &self.llm_provider {
+            config.llm.provider = Some(provider.clone());
+        }
+        if let Some(endpoint) = &self.endpoint {
+            config.llm.endpoint = Some(endpoint.clone());
+        }
+        if let Some(model) = &self.model {
+            config.llm.model = Some(model.clone());
+        }
+        if let Some(proxy) = &self.proxy {
+            config.http.proxy = Some(proxy.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            config.http.timeout_secs = Some(timeout);
+        }
+        if let Some(user_agent) = &self.user_agent {
+            config.http.user_agent = Some(user_agent.clone());
+        }
+        for header in &self.headers {
+            let (name, value) = header
+                .split_once(':')
+                .ok_or_else(|| DailyStoicError::ConfigError(format!("Expected --header \"Name: value\", got \"{}\"", header)))?;
+            config.http.headers.insert(name.trim().to_string(), value.trim().to_string());
+        }
+        if let Some(temperature) = self.temperature {
+            config.llm.temperature = Some(temperature);
+        }
+        if let Some(max_tokens) = self.max_tokens {
+            config.llm.max_tokens = Some(max_tokens);
+        }
+
+        Ok(config)
+    }
+
+    fn log_level(&self) -> LevelFilter {
+        if self.quiet {
+            return LevelFilter::ERROR;
+        }
+        match self.verbose {
+            0 => LevelFilter::WARN,
+            1 => LevelFilter::INFO,
+            2 => LevelFilter::DEBUG,
+            _ => LevelFilter::TRACE,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show today's entry (the default)
+    Today,
+    /// Show the entry for a specific date, e.g. "March 5", tomorrow or -1
+    Date {
+        // two words so `date March 5` needs no quotes, and negative numbers for days back
+        #[arg(allow_negative_numbers = true, num_args = 1..=2, required = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Vec<String>,
+    },
+    /// Show the entry for a random day
+    Random,
+    /// Show every entry in a date range, e.g. range "March 1" "March 7"
+    Range {
+        #[arg(required_unless_present = "from_flag", allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        from: Option<String>,
+        #[arg(required_unless_present = "to_flag", allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        to: Option<String>,
+        /// First date of the range
+        #[arg(long = "from", id = "from_flag", conflicts_with = "from")]
+        from_flag: Option<String>,
+        /// Last date of the range
+        #[arg(long = "to", id = "to_flag", conflicts_with = "to")]
+        to_flag: Option<String>,
+        /// Write each entry to a file of its own in this directory, e.g. a Hugo or Jekyll one
+        #[arg(long, value_name = "DIR")]
+        out_dir: Option<PathBuf>,
+    },
+    /// Search titles, quotes and explanations in the archive
+    Search {
+        query: String,
+        /// Show at most this many matches
+        #[arg(long, default_value_t = 20)]
+        limit: usize,
+        /// Rank by meaning with the embeddings from `index embed` instead of matching words
+        #[arg(long)]
+        semantic: bool,
+    },
+    /// List the themes entries are tagged with (see `index topics`)
+    Topics,
+    /// List the topic of every month and the discipline it belongs to
+    Themes,
+    /// Show the current and longest streaks of days reading the entry, and a year of them
+    Streak,
+    /// List the entries tagged with a theme, e.g. topic anger
+    Topic {
+        name: String,
+    },
+    /// List archived entries attributed to a philosopher, e.g. by-author Seneca
+    ByAuthor {
+        name: String,
+    },
+    /// Show how many archived entries each philosopher is quoted in
+    Authors,
+    /// Show figures about the archive: authors, entry lengths and the most frequent words
+    Stats {
+        /// Show this many authors, words and longest and shortest entries
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+    },
+    /// Print a link to the full passage a date's quote comes from [default: today]
+    Source {
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Open the link in the browser instead of printing it
+        #[arg(long)]
+        open: bool,
+    },
+    /// Browse entries on a month calendar
+    Tui,
+    /// Process upcoming days into the archive in parallel
+    Prefetch {
+        /// How many days to process, starting today
+        #[arg(long, default_value_t = 366)]
+        days: usize,
+        /// How many days to process at once
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+        /// Limit how fast days are started, e.g. 1/s or 30/m, at least one a day
+        #[arg(long, value_parser = parse_rate)]
+        rate: Option<Duration>,
+        /// Re-process days that are already archived
+        #[arg(long)]
+        force: bool,
+        /// Start over instead of resuming an interrupted or partly failed run
+        #[arg(long)]
+        restart: bool,
+    },
+    /// Interactively set up and check the config file
+    Init,
+    /// Manage the LLM API key in the OS credential store
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommand,
+    },
+    /// Customize the instructions the LLM cleans up entries with
+    Prompt {
+        #[command(subcommand)]
+        command: PromptCommand,
+    },
+    /// Collect favorite entries to resurface later
+    Fav {
+        #[command(subcommand)]
+        command: FavCommand,
+    },
+    /// Write reflections against the day's entry in $EDITOR
+    Journal {
+        #[command(subcommand)]
+        command: JournalCommand,
+    },
+    /// Build search indexes over the archive
+    Index {
+        #[command(subcommand)]
+        command: IndexCommand,
+    },
+    /// Deliver a day's entry, e.g. from a cron job
+    Send {
+        #[command(subcommand)]
+        command: SendCommand,
+        /// Day to send [default: today]
+        #[arg(long, global = true, allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+    },
+    /// Summarize several days' entries, e.g. the week ahead
+    Digest {
+        #[command(subcommand)]
+        command: DigestCommand,
+    },
+    /// Write an RSS, Atom or JSON feed of the most recent days' entries
+    Feed {
+        /// Output file [default: stdout]
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// How many days back from today to include
+        #[arg(long, default_value_t = 30, value_parser = clap::value_parser!(u32).range(1..=366))]
+        days: u32,
+        /// Feed format: rss, atom or jsonfeed
+        #[arg(long, default_value = "rss", value_parser = parse_feed_format)]
+        format: String,
+        /// Site the feed and its entries link to
+        #[arg(long, value_name = "URL", default_value = "https://dailystoic.com")]
+        link: String,
+    },
+    /// Read recent entries aloud into a podcast with an RSS feed
+    Podcast {
+        #[command(subcommand)]
+        command: PodcastCommand,
+    },
+    /// Serve entries as JSON over HTTP, from the archive with on-demand fetching
+    Serve {
+        #[arg(long, default_value_t = 8080)]
+        port: u16,
+        /// Address to listen on, 0.0.0.0 to accept connections from other machines
+        #[arg(long, default_value = "127.0.0.1")]
+        host: String,
+    },
+    /// Show the day's title and quote as a desktop notification
+    Notify {
+        /// Day to show [default: today]
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Return right after showing it instead of waiting for a click to open the full entry
+        #[arg(long)]
+        no_wait: bool,
+    },
+    /// Render the quote onto a wallpaper image, and optionally set it
+    Wallpaper {
+        /// Day to render [default: today]
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Image size as WIDTHxHEIGHT
+        #[arg(long, default_value = "1920x1080", value_parser = commands::wallpaper::parse_resolution)]
+        resolution: (u32, u32),
+        /// Output file [default: the data dir]
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// Set it as the desktop wallpaper
+        #[arg(long)]
+        set: bool,
+    },
+    /// A short plain message for logging in to a server, as an update-motd script
+    Motd {
+        /// Day to print [default: today]
+        #[arg(allow_hyphen_values = true, conflicts_with = "out", add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Write a script printing the day's message here, e.g. /etc/update-motd.d/50-stoic
+        #[arg(long, value_name = "PATH", required_unless_present = "print")]
+        out: Option<PathBuf>,
+        /// Print the message instead
+        #[arg(long, conflicts_with = "out")]
+        print: bool,
+        /// Keep the whole message within N characters, shortening the quote if needed
+        #[arg(long, value_name = "N")]
+        max_chars: Option<usize>,
+    },
+    /// Show a QR code of the quote, or of the day's page on your site, to scan with a phone
+    Qr {
+        /// Day to encode [default: today]
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Encode the link to the day's page of a site built with `site build` at this URL instead
+        #[arg(long)]
+        url: Option<String>,
+        /// Also write the code as a PNG image
+        #[arg(long, value_name = "PATH")]
+        png: Option<PathBuf>,
+        /// Width of the PNG in pixels
+        #[arg(long, default_value_t = 512, value_parser = clap::value_parser!(u32).range(64..=4096))]
+        size: u32,
+    },
+    /// Print the script that sets up tab completion for a shell, e.g. source <(daily-stoic completions bash)
+    Completions {
+        /// bash, zsh, fish, powershell or elvish
+        shell: Shell,
+    },
+    /// Parse every day of the source without the LLM and list the days that come out incomplete
+    Validate {
+        /// Check a local copy of the source instead of fetching it
+        #[arg(long, value_name = "PATH")]
+        file: Option<PathBuf>,
+    },
+    /// Show the tokens and estimated cost of LLM calls per month and model
+    Usage {
+        /// Per day instead of per month
+        #[arg(long)]
+        daily: bool,
+    },
+    /// Inspect and prune the cached pages, entries and LLM replies
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Check the config, the source url, the LLM and the cache dir, suggesting fixes for what fails
+    Doctor,
+    /// Replace this binary with the latest release from GitHub, after verifying its checksum
+    SelfUpdate {
+        /// Only report whether a newer release is out
+        #[arg(long)]
+        check: bool,
+    },
+    /// Print the man page, or write it and a page per subcommand into a directory
+    Man {
+        /// Directory to write daily-stoic.1 and the subcommand pages to, e.g. /usr/local/share/man/man1
+        #[arg(long, value_name = "DIR")]
+        out: Option<PathBuf>,
+    },
+    /// Read the entry aloud with the system voice or a cloud text-to-speech API
+    Speak {
+        /// Day to read [default: today]
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Save the audio here instead of playing it, e.g. entry.mp3
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// system, openai or elevenlabs [default: tts.provider from the config]
+        #[arg(long)]
+        provider: Option<String>,
+        /// Voice to use [default: tts.voice from the config]
+        #[arg(long)]
+        voice: Option<String>,
+    },
+    /// Run daily-stoic every day with a systemd user timer
+    Schedule {
+        #[command(subcommand)]
+        command: ScheduleCommand,
+    },
+    /// Build a static website of the whole year
+    Site {
+        #[command(subcommand)]
+        command: SiteCommand,
+    },
+    /// Run a chat bot that answers commands and pushes the daily entry
+    Bot {
+        #[command(subcommand)]
+        command: BotCommand,
+    },
+    /// Export the whole year for other tools (from the archive when there is one)
+    Export {
+        #[command(subcommand)]
+        command: ExportCommand,
+    },
+    /// Manage the local archive of the whole year
+    Archive {
+        #[command(subcommand)]
+        command: ArchiveCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum PromptCommand {
+    /// Write the built-in cleanup prompt to ~/.config/daily-stoic/prompts/cleanup.txt to edit
+    Init {
+        /// Overwrite an existing cleanup.txt
+        #[arg(long)]
+        force: bool,
+    },
+    /// Print the cleanup prompt in use
+    Show,
+}
+
+#[derive(Subcommand)]
+enum AuthCommand {
+    /// Store the LLM API key in the OS credential store (read from stdin if omitted)
+    SetKey {
+        key: Option<String>,
+    },
+    /// Remove the LLM API key from the OS credential store
+    DeleteKey,
+}
+
+#[derive(Subcommand)]
+enum FavCommand {
+    /// Mark a date as a favorite [default: today]
+    Add {
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+    },
+    /// List favorite dates in calendar order
+    List,
+    /// Unmark a favorite date
+    Rm {
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: String,
+    },
+    /// Show a random favorite entry
+    Random,
+}
+
+#[derive(Subcommand)]
+enum JournalCommand {
+    /// Write this year's reflection on a date [default: today]
+    Write {
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+    },
+    /// Show every year's reflections on a date [default: today]
+    Show {
+        #[arg(allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+    },
+    /// List all reflections
+    List,
+}
+
+#[derive(Subcommand)]
+enum IndexCommand {
+    /// Embed archived entries with the configured LLM provider for `search --semantic`
+    Embed {
+        /// Re-embed entries that already have an embedding
+        #[arg(long)]
+        force: bool,
+    },
+    /// Tag archived entries with themes, with the LLM unless --no-llm is given
+    Topics {
+        /// Re-tag entries that already have themes
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum DigestCommand {
+    /// The next seven entries, a date, title and the start of the quote each
+    Week {
+        /// First day of the week [default: today]
+        #[arg(long, allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        from: Option<String>,
+        /// Email the digest to the recipients in the [email] config section instead of printing it
+        #[arg(long)]
+        email: bool,
+    },
+    /// Every entry title of a month, after an overview of its theme by the LLM
+    Month {
+        /// Month by name or abbreviation, e.g. March or mar [default: this month]
+        month: Option<String>,
+        /// Email the digest to the recipients in the [email] config section instead of printing it
+        #[arg(long)]
+        email: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum SendCommand {
+    /// Email the entry to the configured recipients over SMTP
+    Email,
+    /// Post the entry to a Discord channel as an embed
+    Discord {
+        /// Webhook url [default: discord.webhook from the config]
+        #[arg(long, value_name = "URL")]
+        webhook: Option<String>,
+    },
+    /// Post the entry to Slack with an incoming webhook or a bot token
+    Slack {
+        /// Incoming webhook url [default: slack.webhook from the config]
+        #[arg(long, value_name = "URL", conflicts_with = "channel")]
+        webhook: Option<String>,
+        /// Channel to post to with the bot token in slack.token
+        #[arg(long)]
+        channel: Option<String>,
+    },
+    /// Post the entry to a Matrix room
+    Matrix {
+        /// Room id or alias [default: matrix.room from the config]
+        #[arg(long)]
+        room: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScheduleCommand {
+    /// Write and enable a per-user systemd service and timer
+    Install {
+        /// Local time to run at, as HH:MM
+        #[arg(long, default_value = "07:00")]
+        time: String,
+        /// Subcommand to run, e.g. "notify", "send email" or "feed --out /srv/feed.xml"
+        #[arg(long, default_value = "notify")]
+        action: String,
+        /// Only write the unit files, without enabling the timer
+        #[arg(long)]
+        no_enable: bool,
+    },
+    /// Disable the timer and remove the units
+    Uninstall,
+}
+
+#[derive(Subcommand)]
+enum SiteCommand {
+    /// Render a page per day, an index per month and a front page with today's entry
+    Build {
+        /// Output directory
+        #[arg(long, value_name = "PATH", default_value = "site")]
+        out: PathBuf,
+        /// http for HTML pages or gemini for a capsule of .gmi pages
+        #[arg(long, default_value_t = Protocol::Http)]
+        protocol: Protocol,
+    },
+}
 
-fn main() -> Result<(), Box<dyn Error>> {
+#[derive(Subcommand)]
+enum BotCommand {
+    /// Long-poll a Telegram bot, answering /today, /random and /date and pushing to /start-ed chats
+    Telegram {
+        /// Local time to push the entry, as HH:MM [default: telegram.time from the config or 07:00]
+        #[arg(long)]
+        time: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum PodcastCommand {
+    /// Write an audio file per day and a feed.xml, keeping files already there
+    Build {
+        /// Directory to write to, served as --url
+        #[arg(long, value_name = "PATH", default_value = "podcast")]
+        out: PathBuf,
+        /// How many days back from today to include
+        #[arg(long, default_value_t = 7, value_parser = clap::value_parser!(u32).range(1..=366))]
+        days: u32,
+        /// Public URL the directory is served at, for the episode links
+        #[arg(long)]
+        url: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ExportCommand {
+    /// Anki flashcards, an .apkg package or a TSV file for plain text import
+    Anki {
+        /// Output file, ending in .apkg for a package
+        #[arg(long, value_name = "PATH")]
+        out: PathBuf,
+    },
+    /// A CSV file with a row per day, its themes in the last column once `index topics` has tagged them
+    Csv {
+        /// Output file
+        #[arg(long, value_name = "PATH", default_value = "daily-stoic.csv")]
+        out: PathBuf,
+    },
+    /// An EPUB book of the year for e-readers, a chapter per month
+    Epub {
+        /// Output file
+        #[arg(long, value_name = "PATH", default_value = "daily-stoic.epub")]
+        out: PathBuf,
+    },
+    /// A fortune(6) database of the quotes with its strfile index
+    Fortune {
+        /// Output file, the index is written next to it with .dat appended
+        #[arg(long, value_name = "PATH", default_value = "stoic")]
+        out: PathBuf,
+    },
+    /// An iCalendar file with a yearly all-day event per day, titled with the entry
+    Ics {
+        /// Output file
+        #[arg(long, value_name = "PATH", default_value = "daily-stoic.ics")]
+        out: PathBuf,
+    },
+    /// A square PNG quote card for sharing
+    Image {
+        /// Output file
+        #[arg(long, value_name = "PATH", default_value = "quote.png")]
+        out: PathBuf,
+        /// Day to render [default: today]
+        #[arg(long, allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Width and height in pixels
+        #[arg(long, default_value_t = 1080, value_parser = clap::value_parser!(u32).range(256..=4096))]
+        size: u32,
+        /// Colors: dark, light, sepia, marble or night [default: the [image] config]
+        #[arg(long)]
+        theme: Option<String>,
+    },
+    /// A typeset A4 PDF of one day or a whole month, for printing
+    Pdf {
+        /// Day to typeset [default: today]
+        #[arg(allow_hyphen_values = true, conflicts_with = "month", add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Typeset every day of this month instead, e.g. March
+        #[arg(long, add = ArgValueCompleter::new(commands::completions::months))]
+        month: Option<String>,
+        /// Output file
+        #[arg(long, value_name = "PATH", default_value = "daily-stoic.pdf")]
+        out: PathBuf,
+    },
+    /// Add a day's entry to its Obsidian daily note, replacing it on repeat runs
+    Obsidian {
+        /// Vault directory [default: obsidian.vault from the config]
+        #[arg(long, value_name = "PATH")]
+        vault: Option<PathBuf>,
+        /// Day to export, into this year's note for it [default: today]
+        #[arg(long, allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+    },
+    /// Add a day's entry as a page of the configured Notion database
+    Notion {
+        /// Day to export, dated this year [default: today]
+        #[arg(long, allow_hyphen_values = true, add = ArgValueCompleter::new(commands::completions::dates))]
+        date: Option<String>,
+        /// Add the page even if the database already has one for the day
+        #[arg(long)]
+        force: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show the files and size of each part of the cache
+    Stats,
+    /// Delete cached files, all of them or only old ones
+    Clean {
+        /// Only files last written longer ago than this, e.g. 30d, 12h or 2w
+        #[arg(long, value_name = "AGE", value_parser = parse_age)]
+        older_than: Option<Duration>,
+    },
+    /// Print the cache dir
+    Path,
+}
+
+#[derive(Subcommand)]
+enum ArchiveCommand {
+    /// Fetch, parse and clean up all 366 days into the local archive
+    Build {
+        /// Re-process days that are already archived
+        #[arg(long)]
+        force: bool,
+        /// Start over instead of resuming an interrupted or partly failed run
+        #[arg(long)]
+        restart: bool,
+    },
+    /// Show where the last build or prefetch got to and which days failed
+    Status,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
     // load env vars from .env (key and url)
     dotenv().ok();
 
-    let date = get_date_arg()?;
-    
-    // get next date or handle last date edge case
-    let next_date = if date == "December 31" { 
-        String::from("STAYING STOIC") 
-    } else { 
-       increment_date(&date)
+    CompleteEnv::with_factory(Cli::command).var(commands::completions::COMPLETE_ENV).complete();
+
+    if std::env::var_os(commands::clipboard::SERVE_ENV).is_some() {
+        return if commands::clipboard::serve().is_ok() { ExitCode::SUCCESS } else { ExitCode::FAILURE };
+    }
+
+    let cli = Cli::parse();
+
+    // logs go to stderr so stdout only ever holds the rendered entry
+    tracing_subscriber::fmt()
+        .with_max_level(cli.log_level())
+        .with_writer(io::stderr)
+        .with_ansi(io::stderr().is_terminal() && theme::color_allowed())
+        .with_target(false)
+        .without_time()
+        .init();
+
+    let locale = cli.locale.unwrap_or_else(Locale::from_env);
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            // typed library errors get their own code, anything else is a general failure
+            match e.downcast_ref::<DailyStoicError>() {
+                Some(e) => {
+                    eprintln!("{}: {}", locale.error, locale.describe(e));
+                    ExitCode::from(e.exit_code())
+                }
+                None => {
+                    eprintln!("{}: {}", locale.error, e);
+                    ExitCode::FAILURE
+                }
+            }
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<(), Box<dyn Error>> {
+    if let Some(Command::Auth { command }) = &cli.command {
+        return auth(command);
+    }
+
+    if let Some(Command::Init) = &cli.command {
+        let path = cli.config.clone()
+            .or_else(Config::default_path)
+            .ok_or("Could not determine the config dir, pass --config")?;
+        return commands::init::run(&path).await;
+    }
+
+    if let Some(Command::Doctor) = &cli.command {
+        let path = cli.config.clone().or_else(Config::default_path);
+        return commands::doctor::run(path, cli.load_config()).await;
+    }
+
+    if let Some(Command::SelfUpdate { check }) = &cli.command {
+        let config = cli.load_config()?;
+        return commands::update::run(&RetryPolicy::from_config(&config.retry), *check).await;
+    }
+
+    if let Some(Command::Man { out }) = &cli.command {
+        match out {
+            Some(dir) => {
+                let pages = commands::man::write_all(Cli::command(), dir)?;
+                println!("Wrote {} pages to {}", pages.len(), dir.display());
+            }
+            None => {
+                let mut cmd = Cli::command().disable_help_subcommand(true);
+                cmd.build();
+                commands::man::page(cmd, &mut io::stdout())?;
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Completions { shell }) = &cli.command {
+        print!("{}", commands::completions::registration(*shell)?);
+        return Ok(());
+    }
+
+    if let Some(Command::Schedule { command }) = &cli.command {
+        return match command {
+            ScheduleCommand::Install { time, action, no_enable } => {
+                let time = NaiveTime::parse_from_str(time, "%H:%M")
+                    .map_err(|e| format!("Invalid time \"{}\" (expected HH:MM): {}", time, e))?;
+                commands::schedule::install(time, action, cli.config.as_deref(), !no_enable)
+            }
+            ScheduleCommand::Uninstall => commands::schedule::uninstall(),
+        };
+    }
+
+    let config = cli.load_config()?;
+    if let Some(Command::Prompt { command }) = &cli.command {
+        return prompt(command, &config);
+    }
+
+    if let Some(Command::Usage { daily }) = &cli.command {
+        let path = Ledger::default_path().ok_or("Could not determine the data dir for the usage ledger")?;
+        return commands::usage::report(&Ledger::new(&path), &config.llm, *daily);
+    }
+
+    if let Some(Command::Cache { command }) = &cli.command {
+        let cache = Cache::new(&cache_dir(&config).ok_or("Could not determine the cache dir")?);
+        return match command {
+            CacheCommand::Stats => commands::cache::stats(&cache),
+            CacheCommand::Clean { older_than } => commands::cache::clean(&cache, *older_than),
+            CacheCommand::Path => {
+                println!("{}", cache.dir().display());
+                Ok(())
+            }
+        };
+    }
+
+    if let Some(Command::Themes) = &cli.command {
+        return themes(&config);
+    }
+
+    if let Some(Command::Streak) = &cli.command {
+        let path = Archive::path_for(config.other_book()).ok_or("Could not determine the data dir for the reading history")?;
+        let color = io::stdout().is_terminal() && theme::color_allowed();
+        println!("{}", commands::streak::report(&Archive::open(&path)?.views_per_day()?, chrono::Local::now().date_naive(), color));
+        return Ok(());
+    }
+
+    if let Some(Command::Archive { command: ArchiveCommand::Status }) = &cli.command {
+        let path = Archive::path_for(config.other_book()).ok_or("Could not determine the data dir for the archive")?;
+        return archive_status(&Archive::open(&path)?);
+    }
+
+    let format: OutputFormat = match &config.format {
+        Some(format) => format.parse()?,
+        None => OutputFormat::default(),
+    };
+    let locale = cli.locale.unwrap_or_else(Locale::from_env);
+    // styling is only for people reading on a terminal, pipes and files get plain text
+    let theme = if io::stdout().is_terminal() && theme::color_allowed() {
+        Some(Theme::from_config(&config.theme)?)
+    } else {
+        None
+    };
+    let width = match cli.width {
+        Some(0) => None,
+        Some(width) => Some(width),
+        None => terminal_width(),
+    };
+    let template = match &cli.template {
+        Some(path) => Some(fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?),
+        None => None,
+    };
+
+    let mut client = DailyStoicClient::from_config(&config)?;
+    if !cli.no_cache && let Some(dir) = cache_dir(&config) {
+        let cache = Cache::new(&dir);
+        client = client.with_cache(if cli.refresh { cache.with_max_age(Duration::ZERO) } else { cache });
+    }
+
+    let archive_path = Archive::path_for(config.other_book());
+    match &cli.command {
+        Some(Command::Archive { command: ArchiveCommand::Build { force, restart } }) => {
+            let job = ArchiveJob {
+                dates: date::all_dates(),
+                use_llm: !cli.no_llm,
+                force: *force,
+                restart: *restart,
+                concurrency: 1,
+                rate: None,
+                archive: archive_path.clone(),
+            };
+            return fill_archive(&client, job).await;
+        }
+        Some(Command::Prefetch { days, concurrency, rate, force, restart }) => {
+            let job = ArchiveJob {
+                dates: date::upcoming(*days),
+                use_llm: !cli.no_llm,
+                force: *force,
+                restart: *restart,
+                concurrency: *concurrency,
+                rate: *rate,
+                archive: archive_path.clone(),
+            };
+            return fill_archive(&client, job).await;
+        }
+        Some(Command::Validate { file }) => {
+            let body = match file {
+                Some(path) => fs::read_to_string(path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?,
+                None => client.fetch_body().await?,
+            };
+            return commands::validate::run(&client, &body);
+        }
+        _ => {}
+    }
+
+    // prefer the archive once one has been built, favorites and the journal are kept in it too
+    // reading an entry is recorded in it for `streak`
+    let needs_archive = matches!(
+        cli.command,
+        None | Some(
+            Command::Today | Command::Date { .. } | Command::Random | Command::Fav { .. } | Command::Journal { .. }
+            | Command::Bot { .. }
+        )
+    );
+    if let Some(path) = archive_path.filter(|p| needs_archive || p.exists()) {
+        client = client.with_archive(Archive::open(&path)?);
+    }
+
+    if let Some(Command::Fav { command }) = &cli.command
+        && !matches!(command, FavCommand::Random)
+    {
+        let archive = client.archive().ok_or("Could not determine the data dir for favorites")?;
+        return fav(archive, command);
+    }
+
+    if let Some(Command::Search { query, limit, semantic }) = &cli.command {
+        let archive = client.archive().ok_or("No archive yet, build one with `daily-stoic archive build`")?;
+        if !semantic {
+            return search(archive, query, *limit);
+        }
+
+        let llm = client.llm().ok_or("Semantic search needs an LLM provider, see `daily-stoic init`")?;
+        for (score, daily) in semantic::search(archive, llm, query, *limit).await? {
+            println!("{:<12} {:.2}  {}\n    {}", daily.date, score, daily.title, daily.quote);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Index { command }) = &cli.command {
+        let archive = client.archive().ok_or("No archive yet, build one with `daily-stoic archive build`")?;
+        return match command {
+            IndexCommand::Embed { force } => {
+                let llm = client.llm().ok_or("Embedding needs an LLM provider, see `daily-stoic init`")?;
+                commands::index::embed(archive, llm, *force).await
+            }
+            IndexCommand::Topics { force } => {
+                let llm = client.llm().filter(|_| !cli.no_llm);
+                commands::index::topics(archive, llm, *force).await
+            }
+        };
+    }
+
+    if let Some(Command::Authors | Command::ByAuthor { .. }) = &cli.command {
+        let archive = client.archive().ok_or("No archive yet, build one with `daily-stoic archive build`")?;
+        return authors(archive, cli.command.as_ref());
+    }
+
+    if let Some(Command::Stats { limit }) = &cli.command {
+        let archive = client.archive().ok_or("No archive yet, build one with `daily-stoic archive build`")?;
+        let dailies = archive.all()?;
+        if dailies.is_empty() {
+            return Err("The archive is empty, build it with `daily-stoic archive build`".into());
+        }
+        let stats = commands::stats::compute(&dailies, *limit);
+        match format {
+            OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&stats)?),
+            _ => println!("{}", commands::stats::render(&stats)),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Topics | Command::Topic { .. }) = &cli.command {
+        let archive = client.archive().ok_or("No archive yet, build one with `daily-stoic archive build`")?;
+        return topics(archive, cli.command.as_ref());
+    }
+
+    if let Some(Command::Journal { command }) = &cli.command {
+        let archive = client.archive().ok_or("Could not determine the data dir for the journal")?;
+        return match command {
+            JournalCommand::Write { date } => {
+                let date = optional_date(date.as_deref())?;
+                let daily = client.get_entry(&date, !cli.no_llm).await?;
+                commands::journal::write(archive, &daily)
+            }
+            JournalCommand::Show { date } => commands::journal::show(archive, &optional_date(date.as_deref())?, !cli.no_pager),
+            JournalCommand::List => commands::journal::list(archive),
+        };
+    }
+
+    if let Some(Command::Tui) = &cli.command {
+        return commands::tui::run(&client, !cli.no_llm).await;
+    }
+
+    if let Some(Command::Export { command }) = &cli.command {
+        match command {
+            ExportCommand::Anki { out } => {
+                let dailies = whole_year(&client, !cli.no_llm).await?;
+                export::anki::export(&dailies, out)?;
+                println!("Exported {} cards to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Csv { out } => {
+                let dailies = whole_year(&client, !cli.no_llm).await?;
+                let tags = match client.archive() {
+                    Some(archive) => archive.topics_by_date()?,
+                    None => HashMap::new(),
+                };
+                export::csv::export(&dailies, &tags, out)?;
+                println!("Exported {} entries to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Epub { out } => {
+                let dailies = whole_year(&client, !cli.no_llm).await?;
+                export::epub::export(&dailies, out)?;
+                println!("Exported {} entries to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Fortune { out } => {
+                let dailies = whole_year(&client, !cli.no_llm).await?;
+                export::fortune::export(&dailies, out)?;
+                println!("Exported {} quotes to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Ics { out } => {
+                let dailies = whole_year(&client, !cli.no_llm).await?;
+                export::ics::export(&dailies, out, chrono::Local::now().year())?;
+                println!("Exported {} events to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Image { out, date, size, theme } => {
+                let date = optional_date(date.as_deref())?;
+                let daily = client.get_entry(&date, !cli.no_llm).await?;
+                let style = match theme {
+                    Some(theme) => card::Style::with_theme(&config.image, theme)?,
+                    None => card::Style::from_config(&config.image)?,
+                };
+                card::render_card(&daily, *size, &style)?
+                    .save(out)
+                    .map_err(|e| format!("Failed to write {}: {}", out.display(), e))?;
+                println!("Wrote {}", out.display());
+            }
+            ExportCommand::Pdf { date, month, out } => {
+                let dailies = match month {
+                    Some(month) => {
+                        let dates = date::month(month)?;
+                        client.get_range(&dates[0], &dates[dates.len() - 1], !cli.no_llm).await?
+                    }
+                    None => {
+                        let date = optional_date(date.as_deref())?;
+                        vec![client.get_entry(&date, !cli.no_llm).await?]
+                    }
+                };
+                export::pdf::export(&dailies, out, config.image.font.as_deref())?;
+                println!("Exported {} entries to {}", dailies.len(), out.display());
+            }
+            ExportCommand::Obsidian { vault, date } => {
+                let date = optional_date(date.as_deref())?;
+                let day = this_year(&date)?;
+
+                let mut obsidian = config.obsidian.clone();
+                if let Some(vault) = vault {
+                    obsidian.vault = Some(vault.clone());
+                }
+
+                let daily = client.get_entry(&date, !cli.no_llm).await?;
+                let path = export::obsidian::export(&daily, &obsidian, day)?;
+                println!("Wrote {} to {}", date, path.display());
+            }
+            ExportCommand::Notion { date, force } => {
+                let date = optional_date(date.as_deref())?;
+                let day = this_year(&date)?;
+                let notion = Notion::new(&config.notion, RetryPolicy::from_config(&config.retry))?;
+
+                if !force && notion.exists(day).await? {
+                    println!("The Notion database already has a page for {}", day);
+                    return Ok(());
+                }
+
+                let daily = client.get_entry(&date, !cli.no_llm).await?;
+                let url = notion.create_page(&daily, day).await?;
+                println!("Created {}", url);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Feed { out, days, format, link }) = &cli.command {
+        let entries = recent(&client, *days, !cli.no_llm).await?;
+        let xml = feed::feed(&entries, format.parse()?, link);
+        match out {
+            Some(path) => {
+                fs::write(path, xml).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                println!("Wrote {} entries to {}", entries.len(), path.display());
+            }
+            None => print!("{}", xml),
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Podcast { command: PodcastCommand::Build { out, days, url } }) = &cli.command {
+        let tts = tts::provider_from_config(&config.tts, RetryPolicy::from_config(&config.retry))?;
+        let entries = recent(&client, *days, !cli.no_llm).await?;
+        let (created, path) = commands::podcast::build(&entries, tts.as_ref(), out, url).await?;
+        println!("Wrote {} new episodes and {}", created, path.display());
+        return Ok(());
+    }
+
+    if let Some(Command::Notify { date, no_wait }) = &cli.command {
+        let date = optional_date(date.as_deref())?;
+        let daily = client.get_entry(&date, !cli.no_llm).await?;
+        return commands::notify::show(&daily, !no_wait);
+    }
+
+    if let Some(Command::Wallpaper { date, resolution, out, set }) = &cli.command {
+        let date = optional_date(date.as_deref())?;
+        let daily = client.get_entry(&date, !cli.no_llm).await?;
+        let path = commands::wallpaper::run(&daily, &config.image, *resolution, out.as_deref(), *set)?;
+        println!("{} {}", if *set { "Set the wallpaper to" } else { "Wrote" }, path.display());
+        return Ok(());
+    }
+
+    if let Some(Command::Motd { date, out, max_chars, .. }) = &cli.command {
+        let width = cli.width.unwrap_or(MOTD_WIDTH);
+        if let Some(path) = out {
+            let exe = std::env::current_exe().map_err(|e| format!("Failed to locate the daily-stoic binary: {}", e))?;
+            let mut args = Vec::new();
+            if let Some(config) = &cli.config {
+                let config = fs::canonicalize(config).unwrap_or_else(|_| config.clone());
+                args.extend(["--config".to_string(), config.to_string_lossy().to_string()]);
+            }
+            if cli.no_llm {
+                args.push("--no-llm".to_string());
+            }
+            args.extend(["motd".to_string(), "--print".to_string(), "--width".to_string(), width.to_string()]);
+            if let Some(max_chars) = max_chars {
+                args.extend(["--max-chars".to_string(), max_chars.to_string()]);
+            }
+            commands::motd::install(path, &exe, &args)?;
+            println!("Wrote {}", path.display());
+            return Ok(());
+        }
+
+        let date = optional_date(date.as_deref())?;
+        let daily = client.get_entry(&date, !cli.no_llm).await?;
+        println!("{}", commands::motd::render(&daily, width, *max_chars));
+        return Ok(());
+    }
+
+    if let Some(Command::Qr { date, url, png, size }) = &cli.command {
+        let date = optional_date(date.as_deref())?;
+        let data = match url {
+            Some(url) => format!("{}/{}", url.trim_end_matches('/'), site::day_url(&date)),
+            None => {
+                let daily = client.get_entry(&date, !cli.no_llm).await?;
+                render::render_quote(&daily)
+            }
+        };
+        let code = commands::qr::encode(&data)?;
+        println!("{}", commands::qr::terminal(&code));
+        if let Some(path) = png {
+            commands::qr::png(&code, path, *size)?;
+            println!("Wrote {}", path.display());
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Speak { date, out, provider, voice }) = &cli.command {
+        let mut tts = config.tts.clone();
+        if let Some(provider) = provider {
+            tts.provider = Some(provider.clone());
+        }
+        if let Some(voice) = voice {
+            tts.voice = Some(voice.clone());
+        }
+        let tts = tts::provider_from_config(&tts, RetryPolicy::from_config(&config.retry))?;
+
+        let date = optional_date(date.as_deref())?;
+        let daily = client.get_entry(&date, !cli.no_llm).await?;
+        return commands::speak::run(&daily, tts.as_ref(), out.as_deref()).await;
+    }
+
+    if let Some(Command::Serve { port, host }) = &cli.command {
+        let addr = format!("{}:{}", host, port);
+        return commands::serve::run(client, &addr, !cli.no_llm).await;
+    }
+
+    if let Some(Command::Site { command: SiteCommand::Build { out, protocol } }) = &cli.command {
+        let dailies = whole_year(&client, !cli.no_llm).await?;
+        let pages = match protocol {
+            Protocol::Http => site::build(&dailies, &date::today(), out)?,
+            Protocol::Gemini => site::build_gemini(&dailies, &date::today(), out)?,
+        };
+        println!("Wrote {} pages to {}", pages, out.display());
+        return Ok(());
+    }
+
+    if let Some(Command::Bot { command: BotCommand::Telegram { time } }) = &cli.command {
+        let time = time.as_deref().or(config.telegram.time.as_deref()).unwrap_or("07:00");
+        let time = NaiveTime::parse_from_str(time, "%H:%M")
+            .map_err(|e| DailyStoicError::ConfigError(format!("Invalid push time \"{}\" (expected HH:MM): {}", time, e)))?;
+        let bot = Telegram::new(config.telegram.token.as_deref(), RetryPolicy::from_config(&config.retry))?;
+        return commands::bot::telegram(&client, &bot, time, !cli.no_llm).await;
+    }
+
+    if let Some(Command::Send { command, date }) = &cli.command {
+        let date = optional_date(date.as_deref())?;
+        let daily = client.get_entry(&date, !cli.no_llm).await?;
+        match command {
+            SendCommand::Email => {
+                send::email::send(&daily, &config.email).await?;
+                println!("Emailed {} to {}", date, config.email.to.join(", "));
+            }
+            SendCommand::Discord { webhook } => {
+                let webhook = webhook.as_deref().or(config.discord.webhook.as_deref());
+                send::discord::send(&daily, webhook, &RetryPolicy::from_config(&config.retry)).await?;
+                println!("Posted {} to Discord", date);
+            }
+            SendCommand::Slack { webhook, channel } => {
+                let mut slack = config.slack.clone();
+                if webhook.is_some() {
+                    slack.webhook = webhook.clone();
+                }
+                // an explicit channel means posting as the bot
+                if channel.is_some() {
+                    slack.webhook = None;
+                    slack.channel = channel.clone();
+                }
+                send::slack::send(&daily, &slack, &RetryPolicy::from_config(&config.retry)).await?;
+                println!("Posted {} to Slack", date);
+            }
+            SendCommand::Matrix { room } => {
+                let mut matrix = config.matrix.clone();
+                if room.is_some() {
+                    matrix.room = room.clone();
+                }
+                let event = send::matrix::send(&daily, &matrix, &RetryPolicy::from_config(&config.retry)).await?;
+                println!("Posted {} to Matrix ({})", date, event);
+            }
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Digest { command }) = &cli.command {
+        let (subject, digest, email) = match command {
+            DigestCommand::Week { from, email } => {
+                let dates = date::following(&optional_date(from.as_deref())?, 7);
+                let dailies = client.get_range(&dates[0], &dates[dates.len() - 1], !cli.no_llm).await?;
+                (commands::digest::week_subject(&dailies), commands::digest::week(&dailies), *email)
+            }
+            DigestCommand::Month { month, email } => {
+                let today = date::today();
+                let this_month = today.split(' ').next().unwrap_or_default();
+                let dates = date::month(month.as_deref().unwrap_or(this_month))?;
+                let name = dates[0].split(' ').next().unwrap_or_default().to_string();
+                let dailies = client.get_range(&dates[0], &dates[dates.len() - 1], !cli.no_llm).await?;
+                // the titles alone still make a digest without an LLM
+                let synthesis = if cli.no_llm { None } else { client.summarize_month(&name, &dailies).await? };
+                let heading = config.built_in_book().and_then(|book| book.month_theme(&name)).map(|theme| theme.to_string());
+                let digest = commands::digest::month(heading.as_deref().unwrap_or(&name), &dailies, synthesis.as_deref());
+                (commands::digest::month_subject(&name), digest, *email)
+            }
+        };
+        if email {
+            send::email::send_text(&subject, &digest, &config.email).await?;
+            println!("Emailed \"{}\" to {}", subject, config.email.to.join(", "));
+        } else {
+            print!("{}", digest);
+        }
+        return Ok(());
+    }
+
+    if let Some(Command::Source { date, open }) = &cli.command {
+        let date = optional_date(date.as_deref())?;
+        // the citation is the same with or without the LLM cleanup
+        let daily = client.get_parsed(&date).await?;
+        let url = citation::source_url(&daily.citation)
+            .ok_or_else(|| format!("Could not tell where \"{}\" is quoted from", daily.quoter))?;
+
+        if *open {
+            webbrowser::open(&url)?;
+        } else {
+            println!("{}", url);
+        }
+        return Ok(());
+    }
+
+    let date = match &cli.command {
+        None | Some(Command::Today) => date::today(),
+        Some(Command::Date { date }) => date::parse_date(&date.join(" "))?,
+        Some(Command::Random) => random_date(&client)?,
+        Some(Command::Fav { .. }) => client.archive()
+            .ok_or("Could not determine the data dir for favorites")?
+            .random_favorite()?
+            .ok_or("No favorites yet, add one with `daily-stoic fav add`")?,
+        Some(Command::Range { from, to, from_flag, to_flag, out_dir }) => {
+            // one of each is guaranteed by clap
+            let from = date::parse_date(from.as_ref().or(from_flag.as_ref()).unwrap())?;
+            let to = date::parse_date(to.as_ref().or(to_flag.as_ref()).unwrap())?;
+
+            let spinner = progress::Spinner::start(&format!("Fetching {} to {}", from, to), !cli.quiet);
+            let mut dailies = client.get_range(&from, &to, !cli.no_llm).await?;
+            drop(spinner);
+            if let Some(language) = &cli.bilingual {
+                let mut output = Vec::new();
+                for daily in &dailies {
+                    let translation = client.translate(daily.clone(), language).await?;
+                    output.push(render::render_bilingual(daily, &translation, bilingual_width(), &locale));
+                }
+                pager::print(&output.join("\n\n"), !cli.no_pager);
+                return Ok(());
+            }
+            if let Some(language) = &cli.translate {
+                dailies = stream::iter(dailies)
+                    .map(|daily| client.translate(daily, language))
+                    .buffered(4)
+                    .collect::<Vec<_>>()
+                    .await
+                    .into_iter()
+                    .collect::<Result<_, _>>()?;
+            }
+            if let Some(dir) = out_dir {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+                for daily in &dailies {
+                    let contents = match &template {
+                        Some(template) => render::render_template(daily, template)?,
+                        None => render::render_in(daily, format, &locale),
+                    };
+                    let path = dir.join(render::file_name(daily, format));
+                    fs::write(&path, contents).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+                }
+                println!("Wrote {} entries to {}", dailies.len(), dir.display());
+                return Ok(());
+            }
+            if cli.copy {
+                let quotes: Vec<String> = dailies.iter().map(render::render_quote).collect();
+                commands::clipboard::copy(&quotes.join("\n\n"))?;
+                eprintln!("Copied {} quotes to the clipboard", quotes.len());
+            }
+            if let Some(template) = &template {
+                let output = dailies
+                    .iter()
+                    .map(|daily| render::render_template(daily, template))
+                    .collect::<Result<Vec<_>, _>>()?;
+                pager::print(&output.join("\n"), !cli.no_pager);
+                return Ok(());
+            }
+            if cli.banner {
+                let theme = theme.clone().unwrap_or_default();
+                let output: Vec<String> = dailies
+                    .iter()
+                    .map(|daily| banner::render(daily, width.unwrap_or(BANNER_WIDTH), &theme))
+                    .collect();
+                pager::print(&output.join("\n\n"), !cli.no_pager);
+                return Ok(());
+            }
+            if let (OutputFormat::Text, Some(width)) = (format, width) {
+                dailies = dailies.iter().map(|daily| render::wrap(daily, width)).collect();
+            }
+            let output = match format {
+                OutputFormat::Svg => svg::render_all(&dailies, &SvgStyle::from_config(&config.image)),
+                OutputFormat::Text => {
+                    // each month's theme goes above its first entry
+                    let mut last_heading = None;
+                    let mut entries = Vec::new();
+                    for daily in &dailies {
+                        let text = match &theme {
+                            Some(theme) => render::render_styled(daily, &locale, theme),
+                            None => render::render_in(daily, format, &locale),
+                        };
+                        let heading = month_heading(&config, daily, theme.as_ref());
+                        if heading.is_some() && heading != last_heading {
+                            entries.push(heading.clone().unwrap_or_default());
+                            last_heading = heading;
+                        }
+                        entries.push(text);
+                    }
+                    entries.join("\n\n")
+                }
+                _ => render::render_all_in(&dailies, format, &locale),
+            };
+            pager::print(&output, !cli.no_pager);
+            return Ok(());
+        }
+        Some(
+            Command::Archive { .. } | Command::Prefetch { .. } | Command::Auth { .. } | Command::Init | Command::Tui
+            | Command::Journal { .. } | Command::Search { .. }
+            | Command::Index { .. } | Command::Topics | Command::Themes | Command::Streak | Command::Topic { .. }
+            | Command::Authors | Command::Stats { .. } | Command::ByAuthor { .. } | Command::Source { .. }
+            | Command::Export { .. } | Command::Send { .. } | Command::Digest { .. } | Command::Bot { .. }
+            | Command::Feed { .. }
+            | Command::Site { .. } | Command::Serve { .. } | Command::Schedule { .. }
+            | Command::Notify { .. } | Command::Wallpaper { .. } | Command::Speak { .. }
+            | Command::Motd { .. } | Command::Qr { .. } | Command::Completions { .. }
+            | Command::Man { .. } | Command::SelfUpdate { .. } | Command::Doctor
+            | Command::Validate { .. } | Command::Usage { .. } | Command::Prompt { .. } | Command::Cache { .. }
+            | Command::Podcast { .. }
+        ) => unreachable!(),
+    };
+
+    if cli.raw {
+        let raw = client.get_raw(&date).await;
+        if raw.is_err() || cli.verbose > 0 {
+            print_date_context(&client, &date, &cli).await;
+        }
+        pager::print(&raw?, !cli.no_pager);
+        return Ok(());
+    }
+
+    if cli.diff {
+        let raw = client.parse_raw(&client.get_raw(&date).await?)?;
+        let cleaned = client.get_daily(&date).await?;
+        let color = io::stdout().is_terminal() && theme::color_allowed();
+        pager::print(&diff::render(&raw, &cleaned, color), !cli.no_pager);
+        return Ok(());
+    }
+
+    let spinner = progress::Spinner::start(&format!("Fetching {}", date), !cli.quiet);
+    let daily = if !cli.no_llm && spinner.visible() {
+        client.get_daily_streaming(&date, &|field, text| spinner.stream(field, text)).await
+    } else {
+        client.get_entry(&date, !cli.no_llm).await
+    };
+    drop(spinner);
+    if daily.is_err() || cli.verbose > 0 {
+        print_date_context(&client, &date, &cli).await;
+    }
+    let mut daily = daily?;
+    if let Some(language) = &cli.bilingual {
+        let translation = client.translate(daily.clone(), language).await?;
+        pager::print(&render::render_bilingual(&daily, &translation, bilingual_width(), &locale), !cli.no_pager);
+        return Ok(());
+    }
+    if let Some(language) = &cli.translate {
+        daily = client.translate(daily, language).await?;
+    }
+    if cli.copy {
+        commands::clipboard::copy(&render::render_quote(&daily))?;
+        eprintln!("Copied the quote to the clipboard");
+    }
+    if let (None, OutputFormat::Text, Some(width), false) = (&template, format, width, cli.banner) {
+        daily = render::wrap(&daily, width);
+    }
+
+    // svg takes its fonts and colors from the config
+    let output = match (&template, format, &theme) {
+        (None, _, theme) if cli.banner => {
+            banner::render(&daily, width.unwrap_or(BANNER_WIDTH), &theme.clone().unwrap_or_default())
+        }
+        (Some(template), _, _) => render::render_template(&daily, template)?,
+        (None, OutputFormat::Svg, _) => svg::render(&daily, &SvgStyle::from_config(&config.image)),
+        (None, OutputFormat::Text, Some(theme)) => render::render_styled(&daily, &locale, theme),
+        (None, _, _) => render::render_in(&daily, format, &locale),
+    };
+    let output = match month_heading(&config, &daily, theme.as_ref()) {
+        Some(heading) if template.is_none() && format == OutputFormat::Text && !cli.banner => {
+            format!("{}\n\n{}", heading, output)
+        }
+        _ => output,
+    };
+    pager::print(&output, !cli.no_pager);
+
+    // a missed record only costs a day of the streak, not the entry
+    if let Some(archive) = client.archive()
+        && let Err(e) = archive.record_view(chrono::Local::now().date_naive(), &date)
+    {
+        warn!("{}", e);
+    }
+
+    Ok(())
+}
+
+// the book's theme for the month of `daily`, e.g. "March — Awareness (Discipline of Perception)"
+fn month_heading(config: &Config, daily: &Daily, theme: Option<&Theme>) -> Option<String> {
+    let heading = config.built_in_book()?.month_theme(&daily.date)?.to_string();
+    Some(theme.map(|theme| theme.label.paint(&heading)).unwrap_or(heading))
+}
+
+fn themes(config: &Config) -> Result<(), Box<dyn Error>> {
+    let book = config.built_in_book().filter(|book| !book.months.is_empty());
+    let Some(book) = book else {
+        println!("{} has no monthly themes", config.book.as_deref().unwrap_or(books::DEFAULT));
+        return Ok(());
     };
-    
-    // get content url from env vars
-    let url = env::var("daily_stoic_url")?;
-    
-    // fetch body from page and process it
-    let body = fetch_page_body(&url)?;
-    
-    // get specific daily date text from body
-    let date_text = get_date_text(&body, &date, &next_date)
-        .ok_or("No match found")?;
-    
-    // format daily struct
-    let mut daily: Daily = format_daily(&date_text);
-    
-    // fix quote
-    daily.quote = fix_text_using_llm(&daily.quote)?;
-    
-    // fix explanation
-    daily.explanation = fix_text_using_llm(&daily.explanation)?;
-        
-    println!("Date:\n{}\n", daily.date);
-    println!("Title:\n{}\n", daily.title);
-    println!("Quote:\n{}\n", daily.quote);
-    println!("Quoter:\n{}\n", daily.quoter);
-    println!("Explanation:\n{}", daily.explanation);
+
+    let width = book.months.iter().map(|theme| theme.month.len()).max().unwrap_or(0);
+    let mut discipline = "";
+    for theme in book.months {
+        if theme.discipline != discipline {
+            if !discipline.is_empty() {
+                println!();
+            }
+            discipline = theme.discipline;
+            println!("Discipline of {}", discipline);
+        }
+        println!("  {:<width$}  {}", theme.month, theme.topic);
+    }
+    Ok(())
+}
+
+fn prompt(command: &PromptCommand, config: &Config) -> Result<(), Box<dyn Error>> {
+    match command {
+        PromptCommand::Init { force } => {
+            let path = llm::prompts_dir()
+                .ok_or("Could not determine the config dir for prompt templates")?
+                .join("cleanup.txt");
+            if path.exists() && !force {
+                return Err(format!("{} already exists, pass --force to overwrite it", path.display()).into());
+            }
+            if let Some(dir) = path.parent() {
+                fs::create_dir_all(dir).map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+            }
+            fs::write(&path, llm::DEFAULT_CLEANUP_PROMPT).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+            println!("Wrote {}, edit it to change how entries are cleaned up", path.display());
+        }
+        PromptCommand::Show => print!("{}", llm::load_cleanup_prompt(config.llm.cleanup_prompt.as_deref())?),
+    }
+    Ok(())
+}
+
+fn auth(command: &AuthCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        AuthCommand::SetKey { key } => {
+            let key = match key {
+                Some(key) => key.clone(),
+                None => {
+                    eprint!("API key: ");
+                    let mut line = String::new();
+                    io::stdin().read_line(&mut line)?;
+                    line.trim().to_string()
+                }
+            };
+            if key.is_empty() {
+                return Err("No API key given".into());
+            }
+
+            credentials::set_api_key(&key)?;
+            println!("Stored the API key in the credential store");
+        }
+        AuthCommand::DeleteKey => {
+            credentials::delete_api_key()?;
+            println!("Removed the API key from the credential store");
+        }
+    }
 
     Ok(())
 }
 
-fn get_date_arg() -> Result<String, String> {
-    let args: Vec<String> = env::args().collect();
-    
-    // first arg is at args[2] 
-    if args.len() < 3 {
-        let today = Local::now()
-            .date_naive()
-            .with_year(2000)
-            .unwrap(); // fixed to force leap year
-        return Ok(today.format("%B %-d").to_string()); 
-    } 
-    
-    let input = &args[2];
-    let full_date = format!("{} 2000", input); // assume a leap year to get all possible days
-
-    // verify valid date str
-    let dt = NaiveDate::parse_from_str(&full_date, "%B %-d %Y")
-        .map_err(|e| format!("Invalid date format for arg \"{}\" (must be %B %-d): {}", input, e))?;
-    
-    let parsed = dt.format("%B %-d").to_string();
-
-    Ok(parsed)
-}
-
-fn fetch_page_body(url: &str) -> Result<String, String> {
-    let response = reqwest::blocking::get(url)
-        .map_err(|e| format!("Request failed: {}", e))?;
-    
-    let body = response.text()
-        .map_err(|e| format!("Failed to read response body: {}", e))?;
-    
-    Ok(body)
-}
-
-fn get_date_text(text: &str, date: &str, next_date: &str) -> Option<String> {
-    let lines: Vec<&str> = text.lines().collect();
-
-    // find the start
-    let mut start = 0;
-    for line in &lines {
-        if line.starts_with(date) { break; }
-        else { start += 1; }
-    }
-    
-    // couldn't find date
-    if start >= lines.len() { return None; }
-    
-    // find the end
-    let mut end = start + 1;
-    for line in &lines[end..] {
-        if line.starts_with(&next_date) { break; } 
-        else { end += 1; }
-    }
-    
-    // couldn't find next date
-    if end >= lines.len() { return None; } 
-    
-    let rejoined = lines[start..end].join("\n");
-    Some(rejoined)
-}
-
-fn increment_date(date: &str) -> String {
-    let full_date = format!("{} 2000", date); // assume a leap year to get all possible days
-    let dt = NaiveDate::parse_from_str(&full_date, "%B %-d %Y").unwrap(); // date is already validated 
-    let plus_one = dt + Days::new(1);
-    plus_one.format("%B %-d").to_string()
-}
-
-struct Daily {
-    date: String,
-    title: String,
-    quote: String,
-    quoter: String,
-    explanation: String 
-}
-
-fn format_daily(text: &str) -> Daily {
-    let lines: Vec<&str> = text.lines().collect();
-
-    let _date = lines[0].trim().to_string();
-    let _title = lines[1].trim().to_string();
-    
-    let quote_start = 2;
-    let mut quote_end = None;
-    for (i, line) in lines[quote_start..].iter().enumerate() {
-        if line.starts_with("—") {
-            quote_end = Some(i + quote_start);
-            break;
-        }
-    }
-
-    let quote_end = quote_end.expect("Expected a line starting with — to end the quote");
-
-    let _quote = lines[2..quote_end]
-        .join(" ")
-        .trim()
-        .to_string();
-
-    let _quoter = lines[quote_end]
-        .trim()
-        .to_string();
-
-    let _explanation = lines[quote_end+1..]
-        .join(" ")
-        .trim()
-        .to_string();
-
-    Daily {
-        date: _date,
-        title: _title,
-        quote: _quote,
-        quoter: _quoter,
-        explanation: _explanation
-    }
-}
-
-fn fix_text_using_llm(text: &str) -> Result<String, String> {
-    let endpoint = env::var("endpoint")
-        .map_err(|e| format!("Failed to retrive endpoint from env vars: {}", e))?;
-
-    let key = env::var("api_key")
-        .map_err(|e| format!("Failed to retrive API key from env vars: {}", e))?;
-    
-    let max_tokens = 500;
-
-    let client = reqwest::blocking::Client::new();
-
-    let body = json!({
-        "model": "openai/gpt-4o",
-        "messages": [
-            {
-                "role": "user",
-                "content": format!(
-                    "Fix the text based on the following instructions:\n\
-                    - Keep the quote as close to its original as possible.\n\
-                    - Some words may be missing characters, combined together, or have a space in the middle of a word. Correct these.\n\
-                    - Merge any line breaks that occur in the middle of a sentence.\n\
-                    - Preserve paragraph breaks (indicated by empty lines or where appropriate).\n\
-                    - Add an extra line break between paragraphs to improve readability.\n\
-                    - Fix any missing characters or spacing issues in words.\n\
-                    - Do not wrap the quote in quotation marks unless the text already has them.\n\
-                    - If the line ends with a few lines with all caps that seem out of context, remove them.
-                    - Do not add any commentary or explanation—just output the corrected quote.\n\
-                    Text:\n{}",
-                    text
-                )
-            }
-        ],
-        "max_tokens": max_tokens
-    });
-
-    let response = client
-        .post(endpoint)
-        .header("Authorization", format!("Bearer {}", key))
-        .json(&body)
-        .send()
-        .map_err(|e| format!("LLM request failed: {}", e))?;
-   
-    let response_json: serde_json::Value = response
-        .json()
-        .map_err(|e| format!("Failed to parse LLM response JSON: {}", e))?;
-
-    if let Some(error) = response_json.get("error") {
-        if let Some(message) = error.get("message") { 
-            return Err(format!("Request to format text with LLM resulted in an error: {}", message));
-        } else { 
-            return Err(format!("Request to format text with LLM resulted in an error and no message was found."));
-        }
-    } 
-    
-    let corrected_text = response_json["choices"][0]["message"]["content"]
-        .as_str()
-        .ok_or("Failed to extract content from LLM response")?
-        .to_string();
-    
-    Ok(corrected_text)
+fn search(archive: &Archive, query: &str, limit: usize) -> Result<(), Box<dyn Error>> {
+    // bold on a terminal, markdown emphasis when piped
+    let highlight = if io::stdout().is_terminal() && theme::color_allowed() { ("\x1b[1m", "\x1b[0m") } else { ("**", "**") };
+
+    let hits = archive.search(query, highlight, limit)?;
+    if hits.is_empty() {
+        return Err(format!("No archived entries match \"{}\"", query).into());
+    }
+
+    for hit in hits {
+        println!("{:<12} {}\n    {}", hit.date, hit.title, hit.snippet);
+    }
+    Ok(())
+}
+
+fn authors(archive: &Archive, command: Option<&Command>) -> Result<(), Box<dyn Error>> {
+    let dailies = archive.all()?;
+
+    if let Some(Command::ByAuthor { name }) = command {
+        let needle = name.to_lowercase();
+        let matching: Vec<_> = dailies
+            .iter()
+            .filter(|daily| daily.citation.author.to_lowercase().contains(&needle))
+            .collect();
+        if matching.is_empty() {
+            return Err(format!("No archived entries quote \"{}\"", name).into());
+        }
+        for daily in matching {
+            println!("{:<12} {:<40} {}", daily.date, daily.title, daily.quoter);
+        }
+        return Ok(());
+    }
+
+    let mut counts: Vec<(String, usize)> = Vec::new();
+    for daily in &dailies {
+        let author = daily.citation.author.clone();
+        match counts.iter_mut().find(|(a, _)| *a == author) {
+            Some((_, count)) => *count += 1,
+            None => counts.push((author, 1)),
+        }
+    }
+    if counts.is_empty() {
+        return Err("The archive is empty, build it with `daily-stoic archive build`".into());
+    }
+
+    counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    for (author, count) in counts {
+        println!("{:<20} {}", author, count);
+    }
+    Ok(())
 }
 
+fn topics(archive: &Archive, command: Option<&Command>) -> Result<(), Box<dyn Error>> {
+    if let Some(Command::Topic { name }) = command {
+        let dailies = archive.by_topic(&name.to_lowercase())?;
+        if dailies.is_empty() {
+            return Err(format!("No entries tagged \"{}\"", name).into());
+        }
+        for daily in dailies {
+            println!("{:<12} {}", daily.date, daily.title);
+        }
+        return Ok(());
+    }
+
+    let counts = archive.topic_counts()?;
+    if counts.is_empty() {
+        return Err("No entries are tagged yet, run `daily-stoic index topics`".into());
+    }
+    for (topic, count) in counts {
+        println!("{:<12} {}", topic, count);
+    }
+    Ok(())
+}
+
+// every entry, straight from the archive when it has been built
+async fn whole_year(client: &DailyStoicClient, use_llm: bool) -> Result<Vec<Daily>, DailyStoicError> {
+    if let Some(archive) = client.archive() {
+        let dailies = archive.all()?;
+        if !dailies.is_empty() {
+            return Ok(dailies);
+        }
+    }
+    client.get_range("January 1", "December 31", use_llm).await
+}
+
+// the entries of the last `days` calendar days through today, each with its day
+async fn recent(client: &DailyStoicClient, days: u32, use_llm: bool) -> Result<Vec<(NaiveDate, Daily)>, DailyStoicError> {
+    let window = window(chrono::Local::now().date_naive(), days);
+    let dates: Vec<String> = window.iter().map(|(_, date)| date.clone()).collect();
+    let dailies = client.get_dates(&dates, use_llm).await?;
+    Ok(window.into_iter().map(|(day, _)| day).zip(dailies).collect())
+}
+
+// the last `days` calendar days up to `today`, oldest first, with the book's date for each
+fn window(today: NaiveDate, days: u32) -> Vec<(NaiveDate, String)> {
+    (0..days as u64)
+        .rev()
+        .filter_map(|i| today.checked_sub_days(Days::new(i)))
+        .map(|day| (day, day.format("%B %-d").to_string()))
+        .collect()
+}
+
+fn parse_output_format(s: &str) -> Result<String, String> {
+    s.parse::<OutputFormat>().map(|format| format.to_string())
+}
+
+// feed's --format shares its id with the global one, which clap then hands the
+// value to as well, so both keep it as a checked name rather than their own type
+fn parse_feed_format(s: &str) -> Result<String, String> {
+    s.parse::<FeedFormat>().map(|format| format.to_string())
+}
+
+fn parse_locale(tag: &str) -> Result<Locale, String> {
+    Locale::parse(tag).ok_or_else(|| {
+        let codes: Vec<&str> = locale::LOCALES.iter().map(|l| l.code).collect();
+        format!("No labels for \"{}\" (expected one of {})", tag, codes.join(", "))
+    })
+}
+
+// the terminal's columns, `None` when piped
+fn terminal_width() -> Option<usize> {
+    if !io::stdout().is_terminal() {
+        return None;
+    }
+    ratatui::crossterm::terminal::size().ok().map(|(columns, _)| columns as usize)
+}
+
+// two columns need a wide terminal, narrower ones and pipes get paragraphs in turn
+fn bilingual_width() -> Option<usize> {
+    terminal_width().filter(|columns| *columns >= 100)
+}
+
+// the calendar day of a book date in the current year
+fn this_year(date: &str) -> Result<chrono::NaiveDate, String> {
+    let year = chrono::Local::now().year();
+    date::in_year(date, year).ok_or_else(|| format!("{} {} does not exist", date, year))
+}
+
+// a date argument that defaults to today
+fn optional_date(date: Option<&str>) -> Result<String, DailyStoicError> {
+    match date {
+        Some(date) => date::parse_date(date),
+        None => Ok(date::today()),
+    }
+}
+
+fn fav(archive: &Archive, command: &FavCommand) -> Result<(), Box<dyn Error>> {
+    match command {
+        FavCommand::Add { date } => {
+            let date = optional_date(date.as_deref())?;
+            if archive.add_favorite(&date)? {
+                println!("Added {} to favorites", date);
+            } else {
+                println!("{} is already a favorite", date);
+            }
+        }
+        FavCommand::Rm { date } => {
+            let date = date::parse_date(date)?;
+            if !archive.remove_favorite(&date)? {
+                return Err(format!("{} is not a favorite", date).into());
+            }
+            println!("Removed {} from favorites", date);
+        }
+        FavCommand::List => {
+            // titles are only known for archived days
+            for date in archive.favorites()? {
+                match archive.get(&date)? {
+                    Some(daily) => println!("{:<12} {}", date, daily.title),
+                    None => println!("{}", date),
+                }
+            }
+        }
+        FavCommand::Random => unreachable!(),
+    }
+
+    Ok(())
+}
+
+// pick from the archive when there is one so no fetch is needed
+fn random_date(client: &DailyStoicClient) -> Result<String, DailyStoicError> {
+    let archived = match client.archive() {
+        Some(archive) => archive.random_date()?,
+        None => None,
+    };
+    Ok(archived.unwrap_or_else(date::random))
+}
+
+// the configured or default cache dir, with a subdir for a book other than The Daily Stoic
+fn cache_dir(config: &Config) -> Option<PathBuf> {
+    let dir = config.cache_dir.clone().or_else(Cache::default_dir)?;
+    Some(match config.other_book() {
+        Some(book) => dir.join("books").join(book),
+        None => dir,
+    })
+}
+
+// "N" followed by s, m, h, d or w
+fn parse_age(s: &str) -> Result<Duration, String> {
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (count, unit) = s.split_at(split);
+    let count: u64 = count.parse().map_err(|e| format!("Invalid age \"{}\": {}", s, e))?;
+
+    let unit = match unit.trim() {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        other => return Err(format!("Invalid age unit \"{}\" (expected s, m, h, d or w)", other)),
+    };
+
+    count
+        .checked_mul(unit)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("Invalid age \"{}\": too long", s))
+}
+
+// "N/s", "N/m" or "N/h" (a bare N means per second), as the delay between starts
+fn parse_rate(s: &str) -> Result<Duration, String> {
+    let (count, unit) = s.split_once('/').unwrap_or((s, "s"));
+
+    let count: f64 = count.trim().parse()
+        .map_err(|e| format!("Invalid rate \"{}\": {}", s, e))?;
+    // NaN fails both comparisons, so it's caught here too
+    if !(count.is_finite() && count > 0.0) {
+        return Err(format!("Invalid rate \"{}\": must be a positive number", s));
+    }
+
+    let unit = match unit.trim() {
+        "s" => 1.0,
+        "m" => 60.0,
+        "h" => 3600.0,
+        other => return Err(format!("Invalid rate unit \"{}\" (expected s, m or h)", other)),
+    };
+
+    let interval = Duration::try_from_secs_f64(unit / count).map_err(|e| format!("Invalid rate \"{}\": {}", s, e))?;
+    // keeps the start of every day of the year within reach of an Instant
+    if interval > Duration::from_secs(24 * 60 * 60) {
+        return Err(format!("Invalid rate \"{}\": must be at least one a day", s));
+    }
+    Ok(interval)
+}
+
+struct ArchiveJob {
+    dates: Vec<String>,
+    use_llm: bool,
+    force: bool,
+    // ignore the manifest of an unfinished run
+    restart: bool,
+    concurrency: usize,
+    // minimum delay between starting two days
+    rate: Option<Duration>,
+    archive: Option<PathBuf>,
+}
+
+// processes the job's days from a single page fetch into the archive, reporting
+// progress on stderr
+async fn fill_archive(client: &DailyStoicClient, job: ArchiveJob) -> Result<(), Box<dyn Error>> {
+    let path = job.archive.as_ref().ok_or("Could not determine the data dir for the archive")?;
+    let archive = Archive::open(path)?;
+
+    // days an unfinished run already got through aren't redone, even with --force
+    if job.restart {
+        archive.clear_manifest()?;
+    }
+    let done: HashSet<String> = archive
+        .manifest()?
+        .into_iter()
+        .filter(|day| day.status == DayStatus::Done)
+        .map(|day| day.date)
+        .collect();
+
+    let mut todo = Vec::new();
+    for date in &job.dates {
+        if !done.contains(date) && (job.force || archive.get(date)?.is_none()) {
+            todo.push(date.clone());
+        }
+    }
+    let resumed = job.dates.iter().filter(|date| done.contains(*date)).count();
+    if resumed > 0 {
+        eprintln!("Resuming the last run, {} days already done (--restart to start over)", resumed);
+    }
+    for date in &todo {
+        archive.mark(date, &DayStatus::Pending)?;
+    }
+
+    // the source's body, and those of mirrors for days missing from it
+    let mut bodies = vec![client.fetch_body().await?];
+    let mut jobs = Vec::new();
+    for date in todo {
+        let mut index = bodies.iter().position(|body| client.raw_from_body(body, &date).is_ok());
+        if index.is_none() && !client.mirrors().is_empty() && let Ok(body) = client.fetch_body_for(&date).await {
+            bodies.push(body);
+            index = Some(bodies.len() - 1);
+        }
+        // a day in none of them fails when it's processed
+        jobs.push((date, index.unwrap_or(0)));
+    }
+
+    let start = Instant::now();
+    let total = jobs.len();
+
+    let mut results = stream::iter(jobs.into_iter().enumerate())
+        .map(|(i, (date, index))| {
+            let body = &bodies[index];
+            async move {
+                if let Some(rate) = job.rate {
+                    tokio::time::sleep_until((start + rate * i as u32).into()).await;
+                }
+                let daily = client.daily_from_body(body, &date, job.use_llm).await;
+                (date, daily)
+            }
+        })
+        .buffer_unordered(job.concurrency.max(1));
+
+    let mut done = 0;
+    let mut failed = Vec::new();
+
+    while let Some((date, daily)) = results.next().await {
+        done += 1;
+        match daily {
+            Ok(daily) => {
+                archive.put(&daily)?;
+                archive.mark(&date, &DayStatus::Done)?;
+                eprintln!("[{}/{}] {}", done, total, date);
+            }
+            Err(e) => {
+                archive.mark(&date, &DayStatus::Failed(e.to_string()))?;
+                eprintln!("[{}/{}] {} failed: {}", done, total, date, e);
+                failed.push(date);
+            }
+        }
+    }
+
+    println!("Archived {} of 366 days to {}", archive.count()?, path.display());
+    if failed.is_empty() {
+        archive.clear_manifest()?;
+    } else {
+        println!("Failed {} days: {}", failed.len(), failed.join(", "));
+        println!("Run it again to retry only those");
+    }
+
+    Ok(())
+}
+
+// the manifest of an unfinished build, or how much is archived when there's none
+fn archive_status(archive: &Archive) -> Result<(), Box<dyn Error>> {
+    let manifest = archive.manifest()?;
+    if manifest.is_empty() {
+        println!("No unfinished build, {} of 366 days archived", archive.count()?);
+        return Ok(());
+    }
+
+    let count = |status: fn(&DayStatus) -> bool| manifest.iter().filter(|day| status(&day.status)).count();
+    println!(
+        "Last run: {} done, {} failed, {} left",
+        count(|status| *status == DayStatus::Done),
+        count(|status| matches!(status, DayStatus::Failed(_))),
+        count(|status| *status == DayStatus::Pending),
+    );
+    for day in &manifest {
+        if let DayStatus::Failed(error) = &day.status {
+            println!("  {:<12} {}", day.date, error);
+        }
+    }
+    println!("Run `daily-stoic archive build` or `prefetch` again to resume it, or with --restart to start over");
+    Ok(())
+}
+
+// only prints anything when the body is reachable and the date is missing from
+// it, or with -v, where it also shows where a found date was matched
+async fn print_date_context(client: &DailyStoicClient, date: &str, cli: &Cli) {
+    let verbose = cli.verbose > 0;
+    let Some(n) = cli.context_lines.or(verbose.then_some(CONTEXT_LINES)) else { return };
+    let Ok(body) = client.fetch_body().await else { return };
+
+    if verbose || client.raw_from_body(&body, date).is_err() {
+        eprintln!("{}", parse::date_context(&body, date, n));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_rates_without_a_duration() {
+        assert_eq!(parse_rate("2/m").unwrap(), Duration::from_secs(30));
+        assert_eq!(parse_rate("0.25/h").unwrap(), Duration::from_secs(4 * 60 * 60));
+        for rate in ["nan", "inf", "-1", "0", "1e-300/s", "1e-19/s", "0.01/h", "NaN/h"] {
+            assert!(parse_rate(rate).is_err(), "{}", rate);
+        }
+    }
+
+    #[test]
+    fn rejects_ages_that_overflow() {
+        assert_eq!(parse_age("2w").unwrap(), Duration::from_secs(14 * 24 * 60 * 60));
+        assert!(parse_age("99999999999999999w").is_err());
+    }
+
+    #[test]
+    fn feed_takes_its_own_formats() {
+        let cli = Cli::try_parse_from(["daily-stoic", "feed", "--format", "jsonfeed"]).unwrap();
+        assert!(matches!(cli.command, Some(Command::Feed { ref format, .. }) if format == "jsonfeed"));
+        assert!(Cli::try_parse_from(["daily-stoic", "feed", "--format", "html"]).is_err());
+        assert!(Cli::try_parse_from(["daily-stoic", "--format", "rss", "today"]).is_err());
+        assert_eq!(Cli::try_parse_from(["daily-stoic", "--format", "HTML", "today"]).unwrap().format.as_deref(), Some("html"));
+    }
+
+    #[test]
+    fn window_covers_a_whole_year_of_days() {
+        let today = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+        for days in [1, 365, 366] {
+            let window = window(today, days);
+            assert_eq!(window.len(), days as usize);
+            assert_eq!(window.last().unwrap(), &(today, "October 16".to_string()));
+        }
+        let window = window(today, 366);
+        assert_eq!(window[0], (NaiveDate::from_ymd_opt(2025, 10, 16).unwrap(), "October 16".to_string()));
+    }
+}
@@ -0,0 +1,57 @@
+use ratatui::crossterm::terminal;
+use std::env;
+use std::io::{self, IsTerminal, Write};
+use std::process::{Command, Stdio};
+
+/// Prints `text`, through `$PAGER` (or `less`) like git does when stdout is a
+/// terminal and the text is taller than it. Falls back to plain printing if the
+/// pager can't be started.
+pub fn print(text: &str, enabled: bool) {
+    if !enabled || !io::stdout().is_terminal() || fits_terminal(text) {
+        println!("{}", text);
+        return;
+    }
+
+    let pager = env::var("PAGER").unwrap_or_else(|_| "less".to_string());
+    if pager.is_empty() || pager == "cat" || page(&pager, text).is_err() {
+        println!("{}", text);
+    }
+}
+
+fn fits_terminal(text: &str) -> bool {
+    match terminal::size() {
+        Ok((_, rows)) => text.lines().count() < rows as usize,
+        Err(_) => true,
+    }
+}
+
+fn page(pager: &str, text: &str) -> io::Result<()> {
+    let mut command = shell(pager);
+    // same defaults as git: quit if one screen, keep colors, don't clear on exit
+    if env::var_os("LESS").is_none() {
+        command.env("LESS", "FRX");
+    }
+
+    let mut child = command.stdin(Stdio::piped()).spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // the user quitting the pager early closes the pipe, which is fine
+        let _ = writeln!(stdin, "{}", text);
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Runs `command` through the platform shell, as git does for `$PAGER` and `$EDITOR`.
+#[cfg(unix)]
+pub fn shell(command: &str) -> Command {
+    let mut shell = Command::new("sh");
+    shell.arg("-c").arg(command);
+    shell
+}
+
+#[cfg(not(unix))]
+pub fn shell(command: &str) -> Command {
+    let mut shell = Command::new("cmd");
+    shell.arg("/C").arg(command);
+    shell
+}
@@ -0,0 +1,221 @@
+use crate::Daily;
+use crate::citation::Citation;
+use crate::error::{DailyStoicError::ParseError, Result};
+use crate::date::increment_date;
+use tracing::debug;
+
+// promo text the source appends after some explanations
+const DEFAULT_FOOTER_MARKERS: [&str; 2] = ["SIGN UP", "DAILY STOIC"];
+
+pub fn default_footer_markers() -> Vec<String> {
+    DEFAULT_FOOTER_MARKERS.iter().map(|m| m.to_string()).collect()
+}
+
+/// Splits a comma separated list of footer markers.
+pub fn split_footer_markers(markers: &str) -> Vec<String> {
+    markers
+        .split(',')
+        .map(|m| m.trim().to_string())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// The header after December 31's entry in The Daily Stoic.
+pub const DEFAULT_END_MARKER: &str = "STAYING STOIC";
+
+/// The header that follows `date` in the body, used to find the end of its
+/// section: the next day's, or `end_marker` after December 31.
+pub fn next_date_marker(date: &str, end_marker: &str) -> String {
+    // get next date or handle last date edge case
+    if date == "December 31" {
+        end_marker.to_string()
+    } else {
+        increment_date(date)
+    }
+}
+
+/// The lines from `date`'s header up to the `next_date` one, or to the end of
+/// the text when `next_date` is empty.
+pub fn get_date_text(text: &str, date: &str, next_date: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+
+    // find the start
+    let mut start = 0;
+    for line in &lines {
+        if line.starts_with(date) { break; }
+        else { start += 1; }
+    }
+    
+    // couldn't find date
+    if start >= lines.len() {
+        debug!("No line starts with {}", date);
+        return None;
+    }
+
+    if next_date.is_empty() {
+        debug!("Found {} at line {}, reading to the end", date, start + 1);
+        return Some(lines[start..].join("\n"));
+    }
+    
+    // find the end
+    let mut end = start + 1;
+    for line in &lines[end..] {
+        if line.starts_with(next_date) { break; } 
+        else { end += 1; }
+    }
+    
+    // couldn't find next date
+    if end >= lines.len() {
+        debug!("Found {} at line {} but no following {}", date, start + 1, next_date);
+        return None;
+    }
+
+    debug!("Found {} at lines {}-{}", date, start + 1, end);
+
+    let rejoined = lines[start..end].join("\n");
+    Some(rejoined)
+}
+
+// best-effort guess at the line where the date header should have been
+fn locate_date(lines: &[&str], date: &str) -> Option<usize> {
+    let date = date.to_lowercase();
+    let (month, day) = date.split_once(' ')?;
+
+    let score = |line: &str| -> u8 {
+        let line = line.trim().to_lowercase();
+        let has_day = line
+            .split(|c: char| !c.is_ascii_digit())
+            .any(|word| word == day);
+
+        if line.starts_with(&date) { 3 }
+        else if line.contains(month) && has_day { 2 }
+        else if line.contains(month) { 1 }
+        else { 0 }
+    };
+
+    // first line with the highest score wins
+    let mut best: Option<(usize, u8)> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let s = score(line);
+        if s > 0 && best.is_none_or(|(_, b)| s > b) {
+            best = Some((i, s));
+        }
+    }
+
+    best.map(|(i, _)| i)
+}
+
+/// Renders `n` lines of the body on either side of where `date` most likely is.
+pub fn date_context(text: &str, date: &str, n: usize) -> String {
+    let lines: Vec<&str> = text.lines().collect();
+
+    let Some(center) = locate_date(&lines, date) else {
+        return format!("Could not locate anything resembling \"{}\" in the body", date);
+    };
+
+    let start = center.saturating_sub(n);
+    let end = (center + n + 1).min(lines.len());
+
+    let mut context = format!("Closest match for \"{}\" at line {}:", date, center + 1);
+    for (i, line) in lines[start..end].iter().enumerate() {
+        let marker = if start + i == center { ">" } else { " " };
+        context.push_str(&format!("\n{} {:>6} | {}", marker, start + i + 1, line));
+    }
+    context
+}
+
+// a single em dash followed by the attribution with single spaces, whatever the
+// source used ("— SENECA,  MORAL LETTERS" -> "—SENECA, MORAL LETTERS")
+fn normalize_quoter(line: &str) -> String {
+    let attribution = line
+        .trim()
+        .trim_start_matches(['—', '―', '–', '-'])
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!("—{}", attribution)
+}
+
+// cut the text at the earliest footer marker found
+fn strip_footer(text: &str, footer_markers: &[String]) -> String {
+    let cut = footer_markers
+        .iter()
+        .filter_map(|marker| text.find(marker.as_str()))
+        .min()
+        .unwrap_or(text.len());
+
+    text[..cut].trim().to_string()
+}
+
+pub fn format_daily(text: &str, footer_markers: &[String]) -> Result<Daily> {
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < 3 {
+        return Err(ParseError("Expected a date, title and quote in the entry".to_string()));
+    }
+
+    let _date = lines[0].trim().to_string();
+    let _title = lines[1].trim().to_string();
+    
+    let quote_start = 2;
+    let mut quote_end = None;
+    for (i, line) in lines[quote_start..].iter().enumerate() {
+        if line.starts_with("—") {
+            quote_end = Some(i + quote_start);
+            break;
+        }
+    }
+
+    let quote_end = quote_end
+        .ok_or_else(|| ParseError("Expected a line starting with — to end the quote".to_string()))?;
+
+    let _quote = lines[2..quote_end]
+        .join(" ")
+        .trim()
+        .to_string();
+
+    let _quoter = normalize_quoter(lines[quote_end]);
+
+    let _explanation = strip_footer(&lines[quote_end+1..].join(" "), footer_markers);
+
+    Ok(Daily {
+        date: _date,
+        title: _title,
+        quote: _quote,
+        citation: Citation::parse(&_quoter),
+        quoter: _quoter,
+        explanation: _explanation
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strips_at_the_default_markers() {
+        let markers = default_footer_markers();
+        assert_eq!(strip_footer("Be patient. SIGN UP for the newsletter", &markers), "Be patient.");
+        assert_eq!(strip_footer("Be patient. DAILY STOIC 2024", &markers), "Be patient.");
+    }
+
+    #[test]
+    fn cuts_at_the_earliest_marker() {
+        let markers = default_footer_markers();
+        assert_eq!(strip_footer("Be patient. DAILY STOIC store SIGN UP today", &markers), "Be patient.");
+        assert_eq!(strip_footer("Be patient. SIGN UP to the DAILY STOIC", &markers), "Be patient.");
+    }
+
+    #[test]
+    fn keeps_text_without_a_marker() {
+        assert_eq!(strip_footer("  Be patient with others.  ", &default_footer_markers()), "Be patient with others.");
+        assert_eq!(strip_footer("Be patient.", &[]), "Be patient.");
+    }
+
+    #[test]
+    fn uses_custom_markers() {
+        let markers = split_footer_markers("SUBSCRIBE, ©");
+        assert_eq!(markers, ["SUBSCRIBE", "©"]);
+        assert_eq!(strip_footer("Be patient. © 2016 Portfolio", &markers), "Be patient.");
+        assert_eq!(strip_footer("Be patient. SIGN UP", &markers), "Be patient. SIGN UP");
+    }
+}
@@ -0,0 +1,58 @@
+use indicatif::{ProgressBar, ProgressStyle};
+use ratatui::crossterm::terminal;
+use std::io::{self, IsTerminal};
+use std::sync::Mutex;
+use std::time::Duration;
+
+// lines of the streamed text kept under the spinner
+const PREVIEW_LINES: usize = 6;
+
+/// A spinner on stderr while an entry is fetched and cleaned up, with the
+/// cleaned up text shown below it as it streams in. Hidden when stderr isn't
+/// a terminal, and cleared once dropped so only the final output remains.
+pub struct Spinner {
+    bar: ProgressBar,
+    // the field being cleaned up and its text so far
+    streamed: Mutex<(String, String)>
+}
+
+impl Spinner {
+    pub fn start(message: &str, enabled: bool) -> Self {
+        let bar = if enabled && io::stderr().is_terminal() { ProgressBar::new_spinner() } else { ProgressBar::hidden() };
+        if let Ok(style) = ProgressStyle::with_template("{spinner} {msg}") {
+            bar.set_style(style);
+        }
+        bar.set_message(message.to_string());
+        bar.enable_steady_tick(Duration::from_millis(100));
+        Spinner { bar, streamed: Mutex::new((String::new(), String::new())) }
+    }
+
+    /// Whether it's drawn, which is when streaming the cleanup is worth it.
+    pub fn visible(&self) -> bool {
+        !self.bar.is_hidden()
+    }
+
+    /// Adds a piece of `field`'s cleaned up text to the preview.
+    pub fn stream(&self, field: &str, text: &str) {
+        if !self.visible() {
+            return;
+        }
+        let Ok(mut streamed) = self.streamed.lock() else { return };
+        if streamed.0 != field {
+            *streamed = (field.to_string(), String::new());
+        }
+        streamed.1.push_str(text);
+
+        // the spinner's line is indented by the spinner and a space
+        let width = terminal::size().map(|(cols, _)| cols as usize).unwrap_or(80).saturating_sub(2).max(20);
+        let lines = textwrap::wrap(&streamed.1, width);
+        let preview = lines[lines.len().saturating_sub(PREVIEW_LINES)..].join("\n");
+        self.bar.set_message(format!("Cleaning up the {}\n{}", field, preview));
+    }
+}
+
+impl Drop for Spinner {
+    fn drop(&mut self) {
+        self.bar.finish_and_clear();
+    }
+}
@@ -0,0 +1,399 @@
+use crate::citation;
+use crate::date;
+use crate::error::{self, DailyStoicError::ConfigError};
+use crate::locale::Locale;
+use crate::svg::{self, SvgStyle};
+use crate::theme::{Style, Theme};
+use crate::topics;
+use crate::Daily;
+use chrono::{Datelike, NaiveDate};
+use std::fmt;
+use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum OutputFormat {
+    #[default]
+    Text,
+    Json,
+    Markdown,
+    Html,
+    Svg,
+    /// Markdown with YAML front matter for a Hugo content directory
+    Hugo,
+    /// Markdown with YAML front matter for a Jekyll `_posts` directory
+    Jekyll,
+    /// An Org heading for Emacs
+    Org,
+    /// Gemtext for a Gemini capsule
+    Gemtext,
+}
+
+impl FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "text" => Ok(OutputFormat::Text),
+            "json" => Ok(OutputFormat::Json),
+            "markdown" | "md" => Ok(OutputFormat::Markdown),
+            "html" => Ok(OutputFormat::Html),
+            "svg" => Ok(OutputFormat::Svg),
+            "hugo" => Ok(OutputFormat::Hugo),
+            "jekyll" => Ok(OutputFormat::Jekyll),
+            "org" => Ok(OutputFormat::Org),
+            "gemtext" | "gmi" => Ok(OutputFormat::Gemtext),
+            _ => Err(format!("Unknown output format \"{}\" (expected text, json, markdown, html, svg, hugo, jekyll, org or gemtext)", s)),
+        }
+    }
+}
+
+impl fmt::Display for OutputFormat {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            OutputFormat::Text => "text",
+            OutputFormat::Json => "json",
+            OutputFormat::Markdown => "markdown",
+            OutputFormat::Html => "html",
+            OutputFormat::Svg => "svg",
+            OutputFormat::Hugo => "hugo",
+            OutputFormat::Jekyll => "jekyll",
+            OutputFormat::Org => "org",
+            OutputFormat::Gemtext => "gemtext",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub fn render(daily: &Daily, format: OutputFormat) -> String {
+    render_in(daily, format, &Locale::default())
+}
+
+/// Like [`render`], with the text format's labels in `locale`.
+pub fn render_in(daily: &Daily, format: OutputFormat, locale: &Locale) -> String {
+    match format {
+        OutputFormat::Text => render_text(daily, locale),
+        OutputFormat::Json => render_json(daily),
+        OutputFormat::Markdown => render_markdown(daily),
+        OutputFormat::Html => render_html(daily),
+        OutputFormat::Svg => svg::render(daily, &SvgStyle::default()),
+        OutputFormat::Hugo | OutputFormat::Jekyll => render_post(daily, format),
+        OutputFormat::Org => render_org(daily),
+        OutputFormat::Gemtext => render_gemtext(daily),
+    }
+}
+
+/// A name for `daily`'s file in `format`, like `march-5.md`, with the date in
+/// front for Jekyll as its posts need.
+///
+/// ```
+/// use daily_stoic_rs::Daily;
+/// use daily_stoic_rs::render::{file_name, OutputFormat};
+///
+/// let daily = Daily { date: "March 5".to_string(), ..Default::default() };
+/// assert_eq!(file_name(&daily, OutputFormat::Hugo), "march-5.md");
+/// assert!(file_name(&daily, OutputFormat::Jekyll).ends_with("-03-05-march-5.md"));
+/// ```
+pub fn file_name(daily: &Daily, format: OutputFormat) -> String {
+    let extension = match format {
+        OutputFormat::Text => "txt",
+        OutputFormat::Json => "json",
+        OutputFormat::Markdown | OutputFormat::Hugo | OutputFormat::Jekyll => "md",
+        OutputFormat::Html => "html",
+        OutputFormat::Svg => "svg",
+        OutputFormat::Org => "org",
+        OutputFormat::Gemtext => "gmi",
+    };
+    let name = format!("{}.{}", daily.date.to_lowercase().replace(' ', "-"), extension);
+    match (format, post_date(daily)) {
+        (OutputFormat::Jekyll, Some(date)) => format!("{}-{}", date.format("%Y-%m-%d"), name),
+        _ => name,
+    }
+}
+
+/// Renders several entries as one document (a JSON array for `Json`).
+pub fn render_all(dailies: &[Daily], format: OutputFormat) -> String {
+    render_all_in(dailies, format, &Locale::default())
+}
+
+/// Like [`render_all`], with the text format's labels in `locale`.
+pub fn render_all_in(dailies: &[Daily], format: OutputFormat, locale: &Locale) -> String {
+    match format {
+        OutputFormat::Json => serde_json::to_string_pretty(dailies).unwrap(), // plain string fields always serialize
+        OutputFormat::Svg => svg::render_all(dailies, &SvgStyle::default()),
+        _ => dailies
+            .iter()
+            .map(|daily| render_in(daily, format, locale))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    }
+}
+
+/// `daily` with the title, quote and explanation wrapped to `width` columns.
+/// Line breaks inside explanation paragraphs are dropped first so cleaned up
+/// text reflows, while the quote keeps its own line breaks.
+///
+/// ```
+/// use daily_stoic_rs::Daily;
+/// use daily_stoic_rs::render::wrap;
+///
+/// let daily = Daily {
+///     date: "March 5".to_string(),
+///     title: "THE DAY".to_string(),
+///     quote: "Begin at once to live.".to_string(),
+///     quoter: "—SENECA".to_string(),
+///     explanation: "One two\nthree four.\n\nFive.".to_string(),
+///     citation: Default::default(),
+/// };
+/// let wrapped = wrap(&daily, 10);
+/// assert_eq!(wrapped.quote, "Begin at\nonce to\nlive.");
+/// assert_eq!(wrapped.explanation, "One two\nthree\nfour.\n\nFive.");
+/// ```
+pub fn wrap(daily: &Daily, width: usize) -> Daily {
+    let fill = |text: &str| textwrap::wrap(text, width).join("\n");
+    let paragraphs = |text: &str, reflow: bool| {
+        text.split("\n\n")
+            .map(|paragraph| {
+                if reflow {
+                    fill(&paragraph.split_whitespace().collect::<Vec<_>>().join(" "))
+                } else {
+                    paragraph.lines().map(fill).collect::<Vec<_>>().join("\n")
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    };
+
+    Daily {
+        title: fill(&daily.title),
+        quote: paragraphs(&daily.quote, false),
+        explanation: paragraphs(&daily.explanation, true),
+        ..daily.clone()
+    }
+}
+
+/// Just the quote with its attribution below it, for sharing.
+pub fn render_quote(daily: &Daily) -> String {
+    format!("{}\n{}", daily.quote.trim(), daily.quoter.trim())
+}
+
+fn render_text(daily: &Daily, locale: &Locale) -> String {
+    render_styled(daily, locale, &Theme::default())
+}
+
+/// The text format with ANSI styles from `theme`, for terminals.
+pub fn render_styled(daily: &Daily, locale: &Locale, theme: &Theme) -> String {
+    let section = |label: &str, style: &Style, text: &str| format!("{}\n{}", theme.label.paint(&format!("{}:", label)), style.paint(text));
+    [
+        section(locale.date, &Style::default(), &daily.date),
+        section(locale.title, &theme.title, &daily.title),
+        section(locale.quote, &theme.quote, &daily.quote),
+        section(locale.quoter, &theme.quoter, &daily.quoter),
+        section(locale.explanation, &theme.explanation, &daily.explanation),
+    ]
+    .join("\n\n")
+}
+
+/// Renders `daily` with a user supplied [Tera](https://keats.github.io/tera/)
+/// template, which sees every field of the entry (`citation.author` and so on
+/// included) plus `paragraphs`, the explanation split at blank lines, and
+/// `source_url`, where the quote can be read in context.
+///
+/// ```
+/// use daily_stoic_rs::Daily;
+/// use daily_stoic_rs::citation::Citation;
+/// use daily_stoic_rs::render::render_template;
+///
+/// let daily = Daily {
+///     date: "March 5".to_string(),
+///     title: "THE DAY".to_string(),
+///     quote: "Begin at once to live.".to_string(),
+///     quoter: "—SENECA, MORAL LETTERS, 101.10".to_string(),
+///     explanation: "One.\n\nTwo.".to_string(),
+///     citation: Citation::parse("—SENECA, MORAL LETTERS, 101.10"),
+/// };
+/// let out = render_template(&daily, "{{ quote }} ({{ citation.author }}, {{ paragraphs | length }})").unwrap();
+/// assert_eq!(out, "Begin at once to live. (Seneca, 2)");
+/// ```
+pub fn render_template(daily: &Daily, template: &str) -> error::Result<String> {
+    let mut context = tera::Context::from_serialize(daily)
+        .map_err(|e| ConfigError(format!("Failed to prepare the template context: {}", e)))?;
+    let paragraphs: Vec<&str> = daily.explanation.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).collect();
+    context.insert("paragraphs", &paragraphs);
+    context.insert("source_url", &citation::source_url(&daily.citation));
+
+    tera::Tera::one_off(template, &context, false).map_err(|e| {
+        // the useful part of tera's errors is usually in the sources
+        let mut message = e.to_string();
+        let mut source = std::error::Error::source(&e);
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        ConfigError(format!("Invalid template: {}", message))
+    })
+}
+
+fn render_json(daily: &Daily) -> String {
+    serde_json::to_string_pretty(daily).unwrap() // plain string fields always serialize
+}
+
+// the quote and its attribution as a markdown blockquote
+fn quote_block(daily: &Daily) -> String {
+    let quote = daily.quote
+        .lines()
+        .map(|line| if line.trim().is_empty() { ">".to_string() } else { format!("> {}", line) })
+        .collect::<Vec<_>>()
+        .join("\n");
+    format!("{}\n>\n> {}", quote, daily.quoter)
+}
+
+fn render_markdown(daily: &Daily) -> String {
+    format!("# {}\n\n## {}\n\n{}\n\n{}", daily.date, daily.title, quote_block(daily), daily.explanation)
+}
+
+// a heading with the date, attribution and where to read the quote in a
+// properties drawer, then the quote block and the explanation
+fn render_org(daily: &Daily) -> String {
+    let mut lines = vec![format!("* {}", daily.title), ":PROPERTIES:".to_string()];
+    match post_date(daily) {
+        Some(date) => lines.push(format!(":DATE: [{}]", date.format("%Y-%m-%d %a"))),
+        None => lines.push(format!(":DATE: {}", daily.date)),
+    }
+    lines.push(format!(":QUOTER: {}", daily.quoter));
+    if let Some(url) = citation::source_url(&daily.citation) {
+        lines.push(format!(":SOURCE: {}", url));
+    }
+    lines.push(":END:".to_string());
+    lines.push("#+BEGIN_QUOTE".to_string());
+    // a line starting with * inside the block would still be read as a heading
+    lines.extend(daily.quote.trim().lines().map(|line| if line.starts_with('*') { format!(",{}", line) } else { line.to_string() }));
+    lines.push(daily.quoter.clone());
+    lines.push("#+END_QUOTE".to_string());
+    format!("{}\n\n{}", lines.join("\n"), daily.explanation.trim())
+}
+
+// Gemini clients wrap lines themselves, so each paragraph of the explanation is
+// one line, while the quote keeps its line breaks as quote lines
+fn render_gemtext(daily: &Daily) -> String {
+    let mut lines = vec![format!("# {}", daily.date), format!("## {}", daily.title), String::new()];
+    lines.extend(daily.quote.trim().lines().map(|line| format!("> {}", line.trim())));
+    lines.push(format!("> {}", daily.quoter));
+    for paragraph in daily.explanation.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        lines.push(String::new());
+        lines.push(paragraph.split_whitespace().collect::<Vec<_>>().join(" "));
+    }
+    if let Some(url) = citation::source_url(&daily.citation) {
+        lines.push(String::new());
+        lines.push(format!("=> {} Read the quote in context", url));
+    }
+    lines.join("\n")
+}
+
+// this year's date of the entry, or the last leap year's for February 29 so
+// the post isn't dated in the future
+fn post_date(daily: &Daily) -> Option<NaiveDate> {
+    let year = chrono::Local::now().year();
+    (0..8).find_map(|back| date::in_year(&daily.date, year - back))
+}
+
+// a YAML double-quoted string
+fn yaml_string(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n"))
+}
+
+// markdown with the date, title, author and themes in YAML front matter
+fn render_post(daily: &Daily, format: OutputFormat) -> String {
+    let mut front = vec!["---".to_string()];
+    if format == OutputFormat::Jekyll {
+        front.push("layout: post".to_string());
+    }
+    front.push(format!("title: {}", yaml_string(&daily.title)));
+    if let Some(date) = post_date(daily) {
+        front.push(format!("date: {}", date.format("%Y-%m-%d")));
+    }
+    if !daily.citation.author.is_empty() {
+        front.push(format!("author: {}", yaml_string(&daily.citation.author)));
+    }
+    let tags: Vec<String> = topics::classify(daily).iter().map(|tag| yaml_string(tag)).collect();
+    front.push(format!("tags: [{}]", tags.join(", ")));
+    front.push("---".to_string());
+
+    format!("{}\n\n{}\n\n{}\n", front.join("\n"), quote_block(daily), daily.explanation.trim())
+}
+
+/// Escapes the characters HTML gives a meaning to.
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+// an <article> fragment, with blank lines in the explanation as paragraphs
+fn render_html(daily: &Daily) -> String {
+    let paragraphs: String = daily.explanation
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("  <p>{}</p>\n", escape_html(p.trim())))
+        .collect();
+
+    format!(
+        "<article>\n  <h1>{}</h1>\n  <h2>{}</h2>\n  <blockquote>\n    <p>{}</p>\n    <footer>{}</footer>\n  </blockquote>\n{}</article>",
+        escape_html(&daily.date),
+        escape_html(&daily.title),
+        escape_html(&daily.quote).replace('\n', "<br>\n    "),
+        escape_html(&daily.quoter),
+        paragraphs
+    )
+}
+
+/// `original` and its `translation` as text, each paragraph followed by its
+/// translation, or side by side in two columns when `width` is given.
+pub fn render_bilingual(original: &Daily, translation: &Daily, width: Option<usize>, locale: &Locale) -> String {
+    let paragraphs = |text: &str| -> Vec<String> {
+        text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()).map(str::to_string).collect()
+    };
+    let sections = [
+        (locale.title, vec![original.title.clone()], vec![translation.title.clone()]),
+        (locale.quote, paragraphs(&original.quote), paragraphs(&translation.quote)),
+        (locale.explanation, paragraphs(&original.explanation), paragraphs(&translation.explanation)),
+    ];
+
+    let mut out = format!("{}:\n{}\n\n", locale.date, original.date);
+    for (label, left, right) in sections {
+        out.push_str(&format!("{}:\n", label));
+        for i in 0..left.len().max(right.len()) {
+            let left = left.get(i).map(String::as_str).unwrap_or("");
+            let right = right.get(i).map(String::as_str).unwrap_or("");
+            match width {
+                Some(width) => out.push_str(&columns(left, right, width)),
+                None => out.push_str(&format!("{}\n{}\n", left, right)),
+            }
+            out.push('\n');
+        }
+        if label == locale.quote {
+            out.push_str(&format!("{}:\n{}\n\n", locale.quoter, original.quoter));
+        }
+    }
+    out.trim_end().to_string()
+}
+
+// two texts wrapped side by side within `width` columns
+fn columns(left: &str, right: &str, width: usize) -> String {
+    const GUTTER: &str = " │ ";
+    let column = (width.saturating_sub(GUTTER.width()) / 2).max(10);
+    let left = textwrap::wrap(left, column);
+    let right = textwrap::wrap(right, column);
+
+    let mut out = String::new();
+    for i in 0..left.len().max(right.len()) {
+        let l = left.get(i).map(|l| l.as_ref()).unwrap_or("");
+        let r = right.get(i).map(|r| r.as_ref()).unwrap_or("");
+        let padding = column.saturating_sub(l.width());
+        let line = format!("{}{}{}{}", l, " ".repeat(padding), GUTTER, r);
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
@@ -0,0 +1,52 @@
+//! Semantic search over archived entries, by cosine similarity of embeddings
+//! from the configured LLM provider.
+
+use crate::archive::Archive;
+use crate::error::{DailyStoicError::StorageError, Result};
+use crate::llm::LlmProvider;
+use crate::Daily;
+
+/// The text embedded for an entry.
+pub fn embedding_text(daily: &Daily) -> String {
+    format!("{}\n\n{}\n\n{}", daily.title, daily.quote, daily.explanation)
+}
+
+pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+
+    if norm_a == 0.0 || norm_b == 0.0 { 0.0 } else { dot / (norm_a * norm_b) }
+}
+
+/// The `limit` archived entries closest to `query`, most similar first, with
+/// their similarity.
+pub async fn search(
+    archive: &Archive,
+    llm: &dyn LlmProvider,
+    query: &str,
+    limit: usize,
+) -> Result<Vec<(f32, Daily)>> {
+    let embeddings = archive.embeddings()?;
+    if embeddings.is_empty() {
+        return Err(StorageError("No embeddings yet, build them with `daily-stoic index embed`".to_string()));
+    }
+
+    let query = llm.embed(query).await?;
+
+    // vectors from a different embedding model can't be compared, so skip them
+    let mut scored: Vec<(f32, String)> = embeddings
+        .into_iter()
+        .filter(|(_, vector)| vector.len() == query.len())
+        .map(|(date, vector)| (cosine_similarity(&query, &vector), date))
+        .collect();
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+
+    let mut results = Vec::new();
+    for (score, date) in scored.into_iter().take(limit) {
+        if let Some(daily) = archive.get(&date)? {
+            results.push((score, daily));
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,33 @@
+//! Delivering entries to people, e.g. from a daily cron job.
+
+pub mod discord;
+pub mod email;
+pub mod matrix;
+pub mod slack;
+pub mod telegram;
+
+/// Splits `text` into pieces of at most `limit` characters for services that
+/// cap message length, breaking between paragraphs, then lines, then words
+/// where possible.
+pub fn split_message(text: &str, limit: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut rest = text.trim();
+
+    while rest.chars().count() > limit {
+        let end = rest.char_indices().nth(limit).map(|(i, _)| i).unwrap_or(rest.len());
+        let head = &rest[..end];
+
+        let cut = ["\n\n", "\n", " "]
+            .iter()
+            .find_map(|sep| head.rfind(sep).filter(|&i| i > 0))
+            .unwrap_or(end);
+
+        pieces.push(rest[..cut].trim_end().to_string());
+        rest = rest[cut..].trim_start();
+    }
+
+    if !rest.is_empty() {
+        pieces.push(rest.to_string());
+    }
+    pieces
+}
@@ -0,0 +1,65 @@
+//! Posting entries to a Discord channel through a webhook.
+
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::send::split_message;
+use crate::Daily;
+use serde_json::{json, Value};
+
+// Discord's limits for an embed description, its title and footer, and a plain message
+const MAX_DESCRIPTION: usize = 4096;
+const MAX_TITLE: usize = 256;
+const MAX_FOOTER: usize = 2048;
+const MAX_CONTENT: usize = 2000;
+
+fn truncate(text: &str, limit: usize) -> String {
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let mut truncated: String = text.chars().take(limit - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// The webhook payloads for `daily`: an embed with the title, the quote (and
+/// explanation when it fits) and the attribution as footer, followed by plain
+/// messages with the explanation when it doesn't.
+pub fn payloads(daily: &Daily) -> Vec<Value> {
+    let quote = format!("*{}*", daily.quote.trim());
+    let full = format!("{}\n\n{}", quote, daily.explanation.trim());
+
+    let (description, overflow) = if full.chars().count() <= MAX_DESCRIPTION {
+        (full, Vec::new())
+    } else {
+        (truncate(&quote, MAX_DESCRIPTION), split_message(&daily.explanation, MAX_CONTENT))
+    };
+
+    let embed = json!({
+        "title": truncate(&format!("{}: {}", daily.date, daily.title), MAX_TITLE),
+        "description": description,
+        "footer": { "text": truncate(&daily.quoter, MAX_FOOTER) }
+    });
+
+    let mut payloads = vec![json!({ "embeds": [embed] })];
+    payloads.extend(overflow.into_iter().map(|content| json!({ "content": content })));
+    payloads
+}
+
+/// Posts `daily` to `webhook`, one request per payload, in order.
+pub async fn send(daily: &Daily, webhook: Option<&str>, retry: &RetryPolicy) -> Result<()> {
+    let webhook = webhook
+        .ok_or_else(|| ConfigError("No Discord webhook configured (discord.webhook or --webhook)".to_string()))?;
+
+    let client = reqwest::Client::new();
+    for payload in payloads(daily) {
+        let response = http::send_with_retry(retry, || client.post(webhook).json(&payload)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(NetworkError(format!("Discord rejected the message with status {}: {}", status, body)));
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+//! The day's entry as a multipart (HTML and plain text) email over SMTP, and
+//! plain text digests of several entries.
+
+use crate::config::EmailConfig;
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::render::{self, OutputFormat};
+use crate::Daily;
+use lettre::message::header::ContentType;
+use lettre::message::{Mailbox, MessageBuilder, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+
+fn mailbox(address: &str) -> Result<Mailbox> {
+    address.parse()
+        .map_err(|e| ConfigError(format!("Invalid email address \"{}\": {}", address, e)))
+}
+
+// a message from the `from` address in `config` to its `to` addresses
+fn builder(config: &EmailConfig, subject: String) -> Result<MessageBuilder> {
+    let from = config.from
+        .as_deref()
+        .ok_or_else(|| ConfigError("No sender configured (email.from)".to_string()))?;
+    if config.to.is_empty() {
+        return Err(ConfigError("No recipients configured (email.to)".to_string()));
+    }
+
+    let mut builder = Message::builder()
+        .from(mailbox(from)?)
+        .subject(subject);
+    for to in &config.to {
+        builder = builder.to(mailbox(to)?);
+    }
+    Ok(builder)
+}
+
+/// Builds the message for `daily` from the `from` and `to` addresses in `config`.
+pub fn message(daily: &Daily, config: &EmailConfig) -> Result<Message> {
+    let builder = builder(config, format!("The Daily Stoic, {}: {}", daily.date, daily.title))?;
+    let html = format!(
+        "<!DOCTYPE html>\n<html>\n<body style=\"font-family: Georgia, serif; max-width: 40em;\">\n{}\n</body>\n</html>",
+        render::render(daily, OutputFormat::Html)
+    );
+    builder
+        .multipart(MultiPart::alternative_plain_html(render::render(daily, OutputFormat::Markdown), html))
+        .map_err(|e| ConfigError(format!("Failed to build the email: {}", e)))
+}
+
+/// Sends `daily` through the SMTP server in `config`, using implicit TLS on
+/// port 465, no encryption when `tls` is "none" and STARTTLS otherwise.
+pub async fn send(daily: &Daily, config: &EmailConfig) -> Result<()> {
+    deliver(message(daily, config)?, config).await
+}
+
+/// Sends `text` as a plain text email with `subject`, e.g. a digest of several
+/// entries, the same way as `send`.
+pub async fn send_text(subject: &str, text: &str, config: &EmailConfig) -> Result<()> {
+    let message = builder(config, subject.to_string())?
+        .header(ContentType::TEXT_PLAIN)
+        .body(text.to_string())
+        .map_err(|e| ConfigError(format!("Failed to build the email: {}", e)))?;
+    deliver(message, config).await
+}
+
+async fn deliver(message: Message, config: &EmailConfig) -> Result<()> {
+    let host = config.smtp_host
+        .as_deref()
+        .ok_or_else(|| ConfigError("No SMTP server configured (email.smtp_host)".to_string()))?;
+
+    let mut transport = match (config.tls.as_deref(), config.smtp_port) {
+        (Some("none"), _) => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        (Some("tls"), _) | (None, Some(465)) => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+            .map_err(|e| ConfigError(format!("Invalid SMTP server \"{}\": {}", host, e)))?,
+        _ => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+            .map_err(|e| ConfigError(format!("Invalid SMTP server \"{}\": {}", host, e)))?,
+    };
+    if let Some(port) = config.smtp_port {
+        transport = transport.port(port);
+    }
+    if let (Some(user), Some(password)) = (&config.username, &config.password) {
+        transport = transport.credentials(Credentials::new(user.clone(), password.clone()));
+    }
+
+    transport.build()
+        .send(message)
+        .await
+        .map(|_| ())
+        .map_err(|e| NetworkError(format!("Failed to send the email: {}", e)))
+}
@@ -0,0 +1,115 @@
+//! Posting entries to a Matrix room with the client-server API.
+
+use crate::config::MatrixConfig;
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::render::{self, escape_html, OutputFormat};
+use crate::Daily;
+use reqwest::{Method, Url};
+use serde_json::{json, Value};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The `m.room.message` content for `daily`, markdown as the plain body and
+/// the subset of HTML Matrix clients render as the formatted one.
+pub fn content(daily: &Daily) -> Value {
+    let paragraphs: String = daily.explanation
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .map(|p| format!("<p>{}</p>", escape_html(p.trim())))
+        .collect();
+    let html = format!(
+        "<h3>{}: {}</h3><blockquote><p><em>{}</em></p><p>{}</p></blockquote>{}",
+        escape_html(&daily.date),
+        escape_html(&daily.title),
+        escape_html(daily.quote.trim()).replace('\n', "<br>"),
+        escape_html(&daily.quoter),
+        paragraphs
+    );
+
+    json!({
+        "msgtype": "m.text",
+        "body": render::render(daily, OutputFormat::Markdown),
+        "format": "org.matrix.custom.html",
+        "formatted_body": html
+    })
+}
+
+struct Matrix<'a> {
+    homeserver: Url,
+    token: &'a str,
+    client: reqwest::Client,
+    retry: &'a RetryPolicy
+}
+
+impl Matrix<'_> {
+    // `segments` percent-encoded under the client-server API root
+    fn url(&self, segments: &[&str]) -> Result<Url> {
+        let mut url = self.homeserver.clone();
+        url.path_segments_mut()
+            .map_err(|()| ConfigError(format!("Invalid Matrix homeserver url {}", self.homeserver)))?
+            .pop_if_empty()
+            .extend(["_matrix", "client", "v3"])
+            .extend(segments);
+        Ok(url)
+    }
+
+    async fn request(&self, method: Method, url: Url, body: Option<&Value>) -> Result<Value> {
+        let response = http::send_with_retry(self.retry, || {
+            let request = self.client.request(method.clone(), url.clone()).bearer_auth(self.token);
+            match body {
+                Some(body) => request.json(body),
+                None => request,
+            }
+        }).await?;
+
+        let status = response.status();
+        let json: Value = response.json()
+            .await
+            .map_err(|e| NetworkError(format!("Failed to parse the Matrix response: {}", e)))?;
+
+        if !status.is_success() {
+            let error = json["error"].as_str().unwrap_or("no error message");
+            return Err(NetworkError(format!("Matrix request failed with status {}: {}", status, error)));
+        }
+        Ok(json)
+    }
+
+    // room ids are used as is, aliases like #stoics:example.org are looked up
+    async fn room_id(&self, room: &str) -> Result<String> {
+        if !room.starts_with('#') {
+            return Ok(room.to_string());
+        }
+
+        let json = self.request(Method::GET, self.url(&["directory", "room", room])?, None).await?;
+        json["room_id"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| NetworkError(format!("Matrix did not return a room id for {}", room)))
+    }
+}
+
+/// Sends `daily` to the room in `config`, returning the new event's id.
+pub async fn send(daily: &Daily, config: &MatrixConfig, retry: &RetryPolicy) -> Result<String> {
+    let homeserver = config.homeserver
+        .as_deref()
+        .ok_or_else(|| ConfigError("No Matrix homeserver configured (matrix.homeserver)".to_string()))?;
+    let token = config.access_token
+        .as_deref()
+        .ok_or_else(|| ConfigError("No Matrix access token configured (matrix.access_token)".to_string()))?;
+    let room = config.room
+        .as_deref()
+        .ok_or_else(|| ConfigError("No Matrix room configured (matrix.room or --room)".to_string()))?;
+
+    let homeserver = Url::parse(homeserver)
+        .map_err(|e| ConfigError(format!("Invalid Matrix homeserver url \"{}\": {}", homeserver, e)))?;
+    let matrix = Matrix { homeserver, token, client: reqwest::Client::new(), retry };
+
+    let room_id = matrix.room_id(room).await?;
+    // the transaction id makes retries of the same request idempotent
+    let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_nanos();
+    let txn = format!("daily-stoic-{}", nanos);
+
+    let url = matrix.url(&["rooms", &room_id, "send", "m.room.message", &txn])?;
+    let json = matrix.request(Method::PUT, url, Some(&content(daily))).await?;
+    Ok(json["event_id"].as_str().unwrap_or_default().to_string())
+}
@@ -0,0 +1,84 @@
+//! Posting entries to Slack, through an incoming webhook or as a bot.
+
+use crate::config::SlackConfig;
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::send::split_message;
+use crate::Daily;
+use serde_json::{json, Value};
+
+const POST_MESSAGE: &str = "https://slack.com/api/chat.postMessage";
+// Slack's limits for header and section block text
+const MAX_HEADER: usize = 150;
+const MAX_SECTION: usize = 3000;
+
+// Slack's mrkdwn only needs these three escaped
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// The Block Kit blocks for `daily`: a header with the title, the quote as a
+/// block quote, the attribution as context and the explanation below.
+pub fn blocks(daily: &Daily) -> Vec<Value> {
+    let header: String = daily.title.chars().take(MAX_HEADER).collect();
+    let quote = daily.quote
+        .lines()
+        .map(|line| format!("> _{}_", escape(line.trim())))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let mut blocks = vec![
+        json!({ "type": "header", "text": { "type": "plain_text", "text": header } }),
+    ];
+    for part in split_message(&quote, MAX_SECTION) {
+        blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": part } }));
+    }
+    blocks.push(json!({
+        "type": "context",
+        "elements": [{ "type": "mrkdwn", "text": format!("{} · {}", escape(&daily.quoter), daily.date) }]
+    }));
+    blocks.push(json!({ "type": "divider" }));
+    for part in split_message(&escape(&daily.explanation), MAX_SECTION) {
+        blocks.push(json!({ "type": "section", "text": { "type": "mrkdwn", "text": part } }));
+    }
+    blocks
+}
+
+/// Posts `daily` to the configured webhook, or with the bot token to the
+/// configured channel when there is no webhook.
+pub async fn send(daily: &Daily, config: &SlackConfig, retry: &RetryPolicy) -> Result<()> {
+    // fallback for notifications and clients without Block Kit
+    let text = format!("{}: {} {}", daily.date, daily.title, daily.quoter);
+    let client = reqwest::Client::new();
+
+    if let Some(webhook) = config.webhook.as_deref() {
+        let body = json!({ "text": text, "blocks": blocks(daily) });
+        let response = http::send_with_retry(retry, || client.post(webhook).json(&body)).await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(NetworkError(format!("Slack rejected the message with status {}: {}", status, message)));
+        }
+        return Ok(());
+    }
+
+    let (Some(token), Some(channel)) = (config.token.as_deref(), config.channel.as_deref()) else {
+        return Err(ConfigError(
+            "No Slack webhook or bot token and channel configured (slack.webhook, or slack.token and slack.channel)".to_string()
+        ));
+    };
+
+    let body = json!({ "channel": channel, "text": text, "blocks": blocks(daily) });
+    let response = http::send_with_retry(retry, || client.post(POST_MESSAGE).bearer_auth(token).json(&body)).await?;
+    let json: Value = response.json()
+        .await
+        .map_err(|e| NetworkError(format!("Failed to parse the Slack response: {}", e)))?;
+
+    // the Web API answers 200 with ok: false on errors
+    if json["ok"].as_bool() != Some(true) {
+        let error = json["error"].as_str().unwrap_or("unknown error");
+        return Err(NetworkError(format!("Slack API request failed: {}", error)));
+    }
+    Ok(())
+}
@@ -0,0 +1,105 @@
+//! A minimal client for the Telegram Bot API, enough to long-poll for
+//! commands and send entries back.
+
+use crate::error::{DailyStoicError::{ConfigError, NetworkError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::render::escape_html;
+use crate::send::split_message;
+use crate::Daily;
+use serde_json::{json, Value};
+
+const API: &str = "https://api.telegram.org";
+// Telegram's limit for a message's text
+const MAX_MESSAGE: usize = 4096;
+
+/// A text message sent to the bot.
+#[derive(Clone, Debug)]
+pub struct Update {
+    pub id: i64,
+    pub chat: i64,
+    pub text: String
+}
+
+pub struct Telegram {
+    token: String,
+    client: reqwest::Client,
+    retry: RetryPolicy
+}
+
+impl Telegram {
+    pub fn new(token: Option<&str>, retry: RetryPolicy) -> Result<Self> {
+        let token = token
+            .ok_or_else(|| ConfigError("No Telegram bot token configured (telegram.token)".to_string()))?;
+        Ok(Telegram { token: token.to_string(), client: reqwest::Client::new(), retry })
+    }
+
+    async fn call(&self, method: &str, body: &Value) -> Result<Value> {
+        let url = format!("{}/bot{}/{}", API, self.token, method);
+        let response = http::send_with_retry(&self.retry, || self.client.post(&url).json(body)).await?;
+        let json: Value = response.json()
+            .await
+            .map_err(|e| NetworkError(format!("Failed to parse the Telegram response: {}", e)))?;
+
+        if json["ok"].as_bool() != Some(true) {
+            let description = json["description"].as_str().unwrap_or("no description");
+            return Err(NetworkError(format!("Telegram {} failed: {}", method, description)));
+        }
+        Ok(json["result"].clone())
+    }
+
+    /// Text messages after `offset`, waiting up to `timeout` seconds for one to arrive.
+    pub async fn get_updates(&self, offset: i64, timeout: u64) -> Result<Vec<Update>> {
+        let body = json!({ "offset": offset, "timeout": timeout, "allowed_updates": ["message"] });
+        let result = self.call("getUpdates", &body).await?;
+
+        Ok(result
+            .as_array()
+            .map(|updates| {
+                updates
+                    .iter()
+                    .filter_map(|u| Some(Update {
+                        id: u["update_id"].as_i64()?,
+                        chat: u["message"]["chat"]["id"].as_i64()?,
+                        text: u["message"]["text"].as_str()?.to_string()
+                    }))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    /// Sends HTML formatted `text` to `chat`.
+    pub async fn send_message(&self, chat: i64, text: &str) -> Result<()> {
+        let body = json!({ "chat_id": chat, "text": text, "parse_mode": "HTML" });
+        self.call("sendMessage", &body).await.map(|_| ())
+    }
+
+    /// Sends `daily` to `chat`, split over several messages if needed.
+    pub async fn send_daily(&self, chat: i64, daily: &Daily) -> Result<()> {
+        for message in messages(daily) {
+            self.send_message(chat, &message).await?;
+        }
+        Ok(())
+    }
+}
+
+/// `daily` as Telegram HTML, with the explanation in follow-up messages when
+/// the whole entry is longer than a message can be.
+pub fn messages(daily: &Daily) -> Vec<String> {
+    let head = format!(
+        "<b>{}: {}</b>\n\n<i>{}</i>\n{}",
+        escape_html(&daily.date),
+        escape_html(&daily.title),
+        escape_html(daily.quote.trim()),
+        escape_html(&daily.quoter)
+    );
+    let explanation = escape_html(daily.explanation.trim());
+
+    let full = format!("{}\n\n{}", head, explanation);
+    if full.chars().count() <= MAX_MESSAGE {
+        return vec![full];
+    }
+
+    let mut messages = vec![head];
+    messages.extend(split_message(&explanation, MAX_MESSAGE));
+    messages
+}
@@ -0,0 +1,296 @@
+//! A static website for the whole year: a page per day, an index per month
+//! and a front page with the day the site was built for.
+//!
+//! Pages link to each other with relative urls, so the site works from any
+//! path, e.g. a GitHub Pages project site. The same layout can be written as
+//! gemtext for a Gemini capsule.
+
+use crate::error::{DailyStoicError::StorageError, Result};
+use crate::render::{self, OutputFormat};
+use crate::{date, Daily};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+use tera::{Context, Tera};
+
+const TEMPLATES: [(&str, &str); 4] = [
+    ("base.html", BASE),
+    ("index.html", INDEX),
+    ("month.html", MONTH),
+    ("day.html", DAY),
+];
+
+/// What the site is served over, which decides its markup.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Protocol {
+    /// HTML pages with a stylesheet
+    #[default]
+    Http,
+    /// Gemtext pages for a Gemini capsule
+    Gemini,
+}
+
+impl FromStr for Protocol {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "http" | "https" | "web" => Ok(Protocol::Http),
+            "gemini" => Ok(Protocol::Gemini),
+            _ => Err(format!("Unknown protocol \"{}\" (expected http or gemini)", s)),
+        }
+    }
+}
+
+impl fmt::Display for Protocol {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Protocol::Http => "http",
+            Protocol::Gemini => "gemini",
+        })
+    }
+}
+
+/// A link to a day's page, relative to the site root. Urls are generated here
+/// and marked safe in the templates so slashes aren't escaped.
+#[derive(Serialize)]
+struct DayLink {
+    date: String,
+    title: String,
+    url: String
+}
+
+#[derive(Serialize)]
+struct Month {
+    name: String,
+    url: String,
+    days: Vec<DayLink>
+}
+
+// "March 5" -> ("march", "5")
+fn slug(date: &str) -> (String, String) {
+    let (month, day) = date.split_once(' ').unwrap_or((date, ""));
+    (month.to_lowercase(), day.to_string())
+}
+
+/// The page of `date` relative to the site root, e.g. "march/5.html".
+pub fn day_url(date: &str) -> String {
+    page_url(date, "html")
+}
+
+fn page_url(date: &str, extension: &str) -> String {
+    let (month, day) = slug(date);
+    format!("{}/{}.{}", month, day, extension)
+}
+
+// the months of `dailies` in calendar order with links to their days' pages
+fn months(dailies: &[&Daily], extension: &str) -> Vec<Month> {
+    // keyed by their first day
+    let mut months: BTreeMap<u32, Month> = BTreeMap::new();
+    for daily in dailies {
+        let name = daily.date.split(' ').next().unwrap_or_default();
+        months
+            .entry(date::day_of_year(&format!("{} 1", name)).unwrap_or_default())
+            .or_insert_with(|| Month {
+                name: name.to_string(),
+                url: format!("{}/index.{}", slug(&daily.date).0, extension),
+                days: Vec::new(),
+            })
+            .days
+            .push(DayLink { date: daily.date.clone(), title: daily.title.clone(), url: page_url(&daily.date, extension) });
+    }
+    months.into_values().collect()
+}
+
+fn write(out: &Path, page: &str, html: &str) -> Result<()> {
+    let path = out.join(page);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(&path, html).map_err(|e| StorageError(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Renders `dailies` into `out`, with the entry for `today` on the front page,
+/// and returns the number of pages written.
+pub fn build(dailies: &[Daily], today: &str, out: &Path) -> Result<usize> {
+    let mut tera = Tera::default();
+    tera.add_raw_templates(TEMPLATES)
+        .map_err(|e| StorageError(format!("Failed to load the site templates: {}", e)))?;
+    let render = |name: &str, context: &Context| {
+        tera.render(name, context)
+            .map_err(|e| StorageError(format!("Failed to render {}: {:?}", name, e)))
+    };
+
+    let mut dailies: Vec<&Daily> = dailies.iter().collect();
+    dailies.sort_by_key(|d| date::day_of_year(&d.date));
+
+    let months = months(&dailies, "html");
+
+    write(out, "style.css", STYLE)?;
+    let mut pages = 0;
+
+    for (i, daily) in dailies.iter().enumerate() {
+        let link = |d: &Daily| DayLink { date: d.date.clone(), title: d.title.clone(), url: day_url(&d.date) };
+
+        let mut context = Context::new();
+        context.insert("root", "../");
+        context.insert("daily", daily);
+        context.insert("month_url", &format!("{}/index.html", slug(&daily.date).0));
+        context.insert("prev", &i.checked_sub(1).map(|p| link(dailies[p])));
+        context.insert("next", &dailies.get(i + 1).map(|d| link(d)));
+
+        write(out, &day_url(&daily.date), &render("day.html", &context)?)?;
+        pages += 1;
+    }
+
+    for month in &months {
+        let mut context = Context::new();
+        context.insert("root", "../");
+        context.insert("month", month);
+        write(out, &month.url, &render("month.html", &context)?)?;
+        pages += 1;
+    }
+
+    let mut context = Context::new();
+    context.insert("root", "");
+    context.insert("daily", &dailies.iter().find(|d| d.date == today));
+    context.insert("today_url", &day_url(today));
+    context.insert("months", &months);
+    write(out, "index.html", &render("index.html", &context)?)?;
+
+    Ok(pages + 1)
+}
+
+/// Renders `dailies` into `out` as a Gemini capsule laid out like the website,
+/// with .gmi pages, and returns the number of pages written.
+pub fn build_gemini(dailies: &[Daily], today: &str, out: &Path) -> Result<usize> {
+    let mut dailies: Vec<&Daily> = dailies.iter().collect();
+    dailies.sort_by_key(|d| date::day_of_year(&d.date));
+    let months = months(&dailies, "gmi");
+    let link = |url: &str, text: &str| format!("=> {} {}", url, text);
+    let mut pages = 0;
+
+    for (i, daily) in dailies.iter().enumerate() {
+        let month = daily.date.split(' ').next().unwrap_or_default();
+        let mut lines = vec![render::render(daily, OutputFormat::Gemtext), String::new()];
+        if let Some(prev) = i.checked_sub(1).map(|p| dailies[p]) {
+            lines.push(link(&format!("../{}", page_url(&prev.date, "gmi")), &format!("← {}", prev.date)));
+        }
+        if let Some(next) = dailies.get(i + 1) {
+            lines.push(link(&format!("../{}", page_url(&next.date, "gmi")), &format!("{} →", next.date)));
+        }
+        lines.push(link("index.gmi", month));
+        lines.push(link("../index.gmi", "The Daily Stoic"));
+        write(out, &page_url(&daily.date, "gmi"), &(lines.join("\n") + "\n"))?;
+        pages += 1;
+    }
+
+    for month in &months {
+        let mut lines = vec![format!("# {}", month.name), String::new()];
+        lines.extend(month.days.iter().map(|day| link(&format!("../{}", day.url), &format!("{}: {}", day.date, day.title))));
+        lines.push(String::new());
+        lines.push(link("../index.gmi", "The Daily Stoic"));
+        write(out, &month.url, &(lines.join("\n") + "\n"))?;
+        pages += 1;
+    }
+
+    let mut lines = vec!["# The Daily Stoic".to_string(), String::new()];
+    if let Some(daily) = dailies.iter().find(|d| d.date == today) {
+        lines.push(link(&page_url(today, "gmi"), &format!("Today, {}: {}", daily.date, daily.title)));
+        lines.push(String::new());
+    }
+    lines.push("## Months".to_string());
+    lines.extend(months.iter().map(|month| link(&month.url, &month.name)));
+    write(out, "index.gmi", &(lines.join("\n") + "\n"))?;
+
+    Ok(pages + 1)
+}
+
+const BASE: &str = r#"<!DOCTYPE html>
+<html lang="en">
+<head>
+  <meta charset="utf-8">
+  <meta name="viewport" content="width=device-width, initial-scale=1">
+  <title>{% block title %}The Daily Stoic{% endblock title %}</title>
+  <link rel="stylesheet" href="{{ root | safe }}style.css">
+</head>
+<body>
+  <header><a href="{{ root | safe }}index.html">The Daily Stoic</a></header>
+  <main>
+{% block content %}{% endblock content %}
+  </main>
+</body>
+</html>
+"#;
+
+const INDEX: &str = r#"{% extends "base.html" %}
+{% block content %}
+{% if daily %}
+    <article>
+      <p class="date"><a href="{{ today_url | safe }}">{{ daily.date }}</a></p>
+      <h1>{{ daily.title }}</h1>
+      <blockquote>
+        <p>{{ daily.quote | escape | linebreaksbr | safe }}</p>
+        <footer>{{ daily.quoter }}</footer>
+      </blockquote>
+{% for paragraph in daily.explanation | split(pat="
+
+") %}{% if paragraph | trim %}
+      <p>{{ paragraph | trim }}</p>{% endif %}{% endfor %}
+    </article>
+{% endif %}
+    <nav class="months">
+      <h2>The year</h2>
+      <ul>
+{% for month in months %}        <li><a href="{{ month.url | safe }}">{{ month.name }}</a></li>
+{% endfor %}      </ul>
+    </nav>
+{% endblock content %}
+"#;
+
+const MONTH: &str = r#"{% extends "base.html" %}
+{% block title %}{{ month.name }} · The Daily Stoic{% endblock title %}
+{% block content %}
+    <h1>{{ month.name }}</h1>
+    <ol class="days">
+{% for day in month.days %}      <li><a href="{{ root | safe }}{{ day.url | safe }}">{{ day.date }}: {{ day.title }}</a></li>
+{% endfor %}    </ol>
+{% endblock content %}
+"#;
+
+const DAY: &str = r#"{% extends "base.html" %}
+{% block title %}{{ daily.date }}: {{ daily.title }} · The Daily Stoic{% endblock title %}
+{% block content %}
+    <article>
+      <p class="date"><a href="{{ root | safe }}{{ month_url | safe }}">{{ daily.date }}</a></p>
+      <h1>{{ daily.title }}</h1>
+      <blockquote>
+        <p>{{ daily.quote | escape | linebreaksbr | safe }}</p>
+        <footer>{{ daily.quoter }}</footer>
+      </blockquote>
+{% for paragraph in daily.explanation | split(pat="
+
+") %}{% if paragraph | trim %}
+      <p>{{ paragraph | trim }}</p>{% endif %}{% endfor %}
+    </article>
+    <nav class="pager">
+      {% if prev %}<a href="{{ root | safe }}{{ prev.url | safe }}">&larr; {{ prev.date }}</a>{% endif %}
+      {% if next %}<a href="{{ root | safe }}{{ next.url | safe }}">{{ next.date }} &rarr;</a>{% endif %}
+    </nav>
+{% endblock content %}
+"#;
+
+const STYLE: &str = "body { font-family: Georgia, serif; max-width: 40em; margin: 2em auto; padding: 0 1em; line-height: 1.6; color: #222; }
+header a { color: inherit; text-decoration: none; font-variant: small-caps; letter-spacing: 0.05em; }
+a { color: #6b3e26; }
+.date { color: #777; margin-bottom: 0; }
+h1 { margin-top: 0.2em; }
+blockquote { font-style: italic; border-left: 3px solid #ccc; margin-left: 0; padding-left: 1em; }
+blockquote footer { font-style: normal; font-size: 0.9em; color: #555; }
+.pager { display: flex; justify-content: space-between; margin-top: 2em; }
+.months ul { list-style: none; padding: 0; columns: 3; }
+";
@@ -0,0 +1,157 @@
+//! Where the book's text comes from: a web page, a local copy of the book or
+//! stdin.
+
+use crate::error::{DailyStoicError::{ParseError, StorageError}, Result};
+use crate::html;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// stdin can only be read once, but the body may be asked for more than once
+static STDIN: OnceLock<std::result::Result<String, String>> = OnceLock::new();
+
+/// A `source_url`, told apart by its scheme and extension.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Source {
+    /// A page fetched over HTTP and cached.
+    Web(String),
+    /// A PDF of the book, as a path or a file:// url.
+    Pdf(PathBuf),
+    /// An EPUB of the book, likewise.
+    Epub(PathBuf),
+    /// Any other local file, read as plain text or HTML.
+    File(PathBuf),
+    /// The text piped in, given as "-".
+    Stdin,
+}
+
+impl Source {
+    /// ```
+    /// use daily_stoic_rs::source::Source;
+    /// use std::path::PathBuf;
+    ///
+    /// assert_eq!(Source::parse("https://example.com/book.txt"), Source::Web("https://example.com/book.txt".to_string()));
+    /// assert_eq!(Source::parse("file:///books/daily-stoic.PDF"), Source::Pdf(PathBuf::from("/books/daily-stoic.PDF")));
+    /// assert_eq!(Source::parse("books/daily-stoic.pdf"), Source::Pdf(PathBuf::from("books/daily-stoic.pdf")));
+    /// assert_eq!(Source::parse("books/daily-stoic.epub"), Source::Epub(PathBuf::from("books/daily-stoic.epub")));
+    /// assert_eq!(Source::parse("file://dump.txt"), Source::File(PathBuf::from("dump.txt")));
+    /// assert_eq!(Source::parse("-"), Source::Stdin);
+    /// ```
+    pub fn parse(location: &str) -> Source {
+        if location == "-" {
+            return Source::Stdin;
+        }
+        // any other scheme is left to the HTTP client to reject
+        if location.contains("://") && !location.starts_with("file://") {
+            return Source::Web(location.to_string());
+        }
+        let path = Path::new(location.strip_prefix("file://").unwrap_or(location));
+        match extension(path).as_deref() {
+            Some("pdf") => Source::Pdf(path.to_path_buf()),
+            Some("epub") => Source::Epub(path.to_path_buf()),
+            _ => Source::File(path.to_path_buf()),
+        }
+    }
+
+    /// The book's text from a local copy or stdin, `None` for a web page.
+    pub async fn read_local(&self) -> Option<Result<String>> {
+        let (path, read): (PathBuf, fn(&Path) -> Result<String>) = match self {
+            Source::Web(_) => return None,
+            Source::Pdf(path) => (path.clone(), pdf_text),
+            Source::Epub(path) => (path.clone(), epub_text),
+            Source::File(path) => (path.clone(), file_text),
+            Source::Stdin => (PathBuf::new(), |_| stdin_text()),
+        };
+        // extracting a whole book takes a while, so it's kept off the runtime's threads
+        let text = tokio::task::spawn_blocking(move || read(&path))
+            .await
+            .map_err(|e| StorageError(format!("Failed to read the book: {}", e)));
+        Some(text.and_then(|text| text))
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension().map(|extension| extension.to_string_lossy().to_lowercase())
+}
+
+fn file_text(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| StorageError(format!("Failed to read {}: {}", path.display(), e)))
+}
+
+fn stdin_text() -> Result<String> {
+    STDIN
+        .get_or_init(|| {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).map(|_| text).map_err(|e| e.to_string())
+        })
+        .clone()
+        .map_err(|e| StorageError(format!("Failed to read the source from stdin: {}", e)))
+}
+
+/// The text of every page of the PDF at `path`, a line of the book per line.
+pub fn pdf_text(path: &Path) -> Result<String> {
+    let bytes = fs::read(path).map_err(|e| StorageError(format!("Failed to read {}: {}", path.display(), e)))?;
+    pdf_extract::extract_text_from_mem(&bytes)
+        .map_err(|e| ParseError(format!("Failed to extract the text of {}: {}", path.display(), e)))
+}
+
+/// The text of the chapters of the EPUB at `path` in reading order, each
+/// reduced like an HTML page.
+pub fn epub_text(path: &Path) -> Result<String> {
+    let invalid = |what: &str| ParseError(format!("{} is not a valid EPUB: {}", path.display(), what));
+    let file = File::open(path).map_err(|e| StorageError(format!("Failed to read {}: {}", path.display(), e)))?;
+    let mut zip = zip::ZipArchive::new(file).map_err(|e| invalid(&e.to_string()))?;
+    let mut read = |name: &str| -> Result<String> {
+        let mut entry = zip.by_name(name).map_err(|_| invalid(&format!("{} is missing", name)))?;
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).map_err(|e| invalid(&format!("{}: {}", name, e)))?;
+        Ok(contents)
+    };
+
+    // the container points at the package document, which lists the chapters
+    let container = read("META-INF/container.xml")?;
+    let container = roxmltree::Document::parse(&container).map_err(|e| invalid(&e.to_string()))?;
+    let opf_path = container
+        .descendants()
+        .find(|node| node.has_tag_name("rootfile"))
+        .and_then(|node| node.attribute("full-path"))
+        .ok_or_else(|| invalid("container.xml names no package document"))?
+        .to_string();
+
+    let opf = read(&opf_path)?;
+    let opf = roxmltree::Document::parse(&opf).map_err(|e| invalid(&e.to_string()))?;
+    let manifest: HashMap<&str, &str> = opf
+        .descendants()
+        .filter(|node| node.has_tag_name("item"))
+        .filter_map(|node| Some((node.attribute("id")?, node.attribute("href")?)))
+        .collect();
+    let base = opf_path.rsplit_once('/').map(|(dir, _)| dir).unwrap_or("");
+
+    let mut chapters = Vec::new();
+    for itemref in opf.descendants().filter(|node| node.has_tag_name("itemref")) {
+        let Some(href) = itemref.attribute("idref").and_then(|id| manifest.get(id)) else { continue };
+        chapters.push(html::to_text(&read(&resolve(base, href))?, None)?);
+    }
+    if chapters.is_empty() {
+        return Err(invalid("its spine lists no chapters"));
+    }
+    Ok(chapters.join("\n"))
+}
+
+// the zip entry of `href`, relative to the package document's directory
+fn resolve(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href).replace("%20", " ");
+    let mut parts: Vec<&str> = base.split('/').filter(|part| !part.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
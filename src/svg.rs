@@ -0,0 +1,149 @@
+//! A standalone SVG of an entry's date, title, quote and attribution, for web
+//! pages and print.
+
+use crate::config::ImageConfig;
+use crate::render::escape_html;
+use crate::Daily;
+
+const WIDTH: u32 = 800;
+const PADDING: u32 = 56;
+// rough average glyph width of a serif, as a fraction of the font size, for wrapping
+const CHAR_WIDTH: f32 = 0.5;
+
+/// Fonts and colors of the SVG, defaulting to the `[image]` config's look.
+#[derive(Clone, Debug)]
+pub struct SvgStyle {
+    /// CSS font-family list.
+    pub font_family: String,
+    /// One color, or two for a top to bottom gradient.
+    pub background: Vec<String>,
+    pub color: String
+}
+
+impl Default for SvgStyle {
+    fn default() -> Self {
+        SvgStyle {
+            font_family: "Georgia, 'DejaVu Serif', serif".to_string(),
+            background: vec!["#1d2433".to_string(), "#0b0d12".to_string()],
+            color: "#f2efe6".to_string()
+        }
+    }
+}
+
+impl SvgStyle {
+    /// The configured font (as a family, font files can't be referenced) and
+    /// colors; an image background falls back to the default colors.
+    pub fn from_config(config: &ImageConfig) -> Self {
+        let mut style = SvgStyle::default();
+        if let Some(font) = config.font.as_deref().filter(|f| !f.contains(['/', '\\'])) {
+            style.font_family = format!("'{}', {}", font.replace('\'', ""), style.font_family);
+        }
+        if let Some(background) = &config.background {
+            let colors: Vec<String> = background.split(',').map(|c| c.trim().to_string()).collect();
+            if colors.iter().all(|c| c.starts_with('#')) && colors.len() <= 2 {
+                style.background = colors;
+            }
+        }
+        if let Some(color) = &config.color {
+            style.color = color.clone();
+        }
+        style
+    }
+}
+
+// greedy word wrap by estimated width
+fn wrap(text: &str, font_size: f32, max_width: u32) -> Vec<String> {
+    let max_chars = (max_width as f32 / (font_size * CHAR_WIDTH)).max(10.0) as usize;
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.chars().count() + 1 + word.chars().count() > max_chars {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+// the entry's elements from `top` down, and the height they take
+fn layout(daily: &Daily, top: u32) -> (String, u32) {
+    let text_width = WIDTH - 2 * PADDING;
+    let mut y = top + PADDING;
+    let mut body = String::new();
+    let mut text = |y: u32, size: u32, attributes: &str, content: &str| {
+        body.push_str(&format!(
+            "    <text x=\"{}\" y=\"{}\" font-size=\"{}\"{}>{}</text>\n",
+            PADDING, y, size, attributes, escape_html(content)
+        ));
+    };
+
+    y += 16;
+    text(y, 16, " letter-spacing=\"2\" opacity=\"0.7\"", &daily.date.to_uppercase());
+    y += 40;
+    for line in wrap(&daily.title, 28.0, text_width) {
+        text(y, 28, " font-weight=\"bold\"", &line);
+        y += 36;
+    }
+
+    y += 28;
+    for line in wrap(&format!("“{}”", daily.quote.trim()), 24.0, text_width) {
+        text(y, 24, " font-style=\"italic\"", &line);
+        y += 34;
+    }
+
+    y += 20;
+    for line in wrap(&daily.quoter, 16.0, text_width) {
+        text(y, 16, " opacity=\"0.8\"", &line);
+        y += 24;
+    }
+
+    (body, y + PADDING - top)
+}
+
+fn document(style: &SvgStyle, height: u32, content: &str) -> String {
+    let (defs, fill) = match style.background.as_slice() {
+        [top, bottom] => (
+            format!(
+                "  <defs>\n    <linearGradient id=\"background\" x1=\"0\" y1=\"0\" x2=\"0\" y2=\"1\">\n      <stop offset=\"0\" stop-color=\"{}\"/>\n      <stop offset=\"1\" stop-color=\"{}\"/>\n    </linearGradient>\n  </defs>\n",
+                escape_html(top), escape_html(bottom)
+            ),
+            "url(#background)".to_string(),
+        ),
+        [color] => (String::new(), escape_html(color)),
+        _ => (String::new(), "none".to_string()),
+    };
+
+    format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n{}  <rect width=\"100%\" height=\"100%\" fill=\"{}\"/>\n  <g font-family=\"{}\" fill=\"{}\">\n{}  </g>\n</svg>\n",
+        defs,
+        fill,
+        escape_html(&style.font_family),
+        escape_html(&style.color),
+        content,
+        w = WIDTH,
+        h = height
+    )
+}
+
+pub fn render(daily: &Daily, style: &SvgStyle) -> String {
+    let (body, height) = layout(daily, 0);
+    document(style, height, &body)
+}
+
+/// Several entries stacked in one SVG.
+pub fn render_all(dailies: &[Daily], style: &SvgStyle) -> String {
+    let mut body = String::new();
+    let mut height = 0;
+    for daily in dailies {
+        let (entry, entry_height) = layout(daily, height);
+        body.push_str(&entry);
+        height += entry_height;
+    }
+    document(style, height, &body)
+}
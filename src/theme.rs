@@ -0,0 +1,112 @@
+//! ANSI styling of the text output on a terminal.
+
+use crate::config::ThemeConfig;
+use crate::error::{DailyStoicError::ConfigError, Result};
+
+/// Whether styling is allowed at all, following the NO_COLOR convention
+/// (<https://no-color.org>): any non-empty value turns it off.
+pub fn color_allowed() -> bool {
+    std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty())
+}
+
+/// Named looks: label, title, quote, quoter and explanation styles.
+pub const THEMES: [(&str, [&str; 5]); 5] = [
+    ("default", ["dim", "bold", "italic", "dim", ""]),
+    ("warm", ["dim yellow", "bold #e0a84e", "italic #f2e3c6", "#c08a4a", ""]),
+    ("ocean", ["dim cyan", "bold #5fb3d9", "italic #d6eef8", "#4d8fb0", ""]),
+    ("forest", ["dim green", "bold #7fb36a", "italic #e1efd6", "#6b8f5a", ""]),
+    ("plain", ["", "", "", "", ""]),
+];
+
+/// SGR parameters for one element, e.g. "1;33" for bold yellow.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Style(String);
+
+impl Style {
+    /// A spec of space separated words: bold, dim, italic, underline, a color
+    /// name (black, red, green, yellow, blue, magenta, cyan, white, optionally
+    /// prefixed with bright-) or "#rrggbb".
+    ///
+    /// ```
+    /// use daily_stoic_rs::theme::Style;
+    ///
+    /// assert_eq!(Style::parse("bold yellow").unwrap().paint("Hi"), "\x1b[1;33mHi\x1b[0m");
+    /// assert_eq!(Style::parse("#ff8000").unwrap().paint("Hi"), "\x1b[38;2;255;128;0mHi\x1b[0m");
+    /// assert_eq!(Style::parse("").unwrap().paint("Hi"), "Hi");
+    /// assert!(Style::parse("blinking").is_err());
+    /// ```
+    pub fn parse(spec: &str) -> Result<Self> {
+        let codes = spec
+            .split_whitespace()
+            .map(|word| code(&word.to_lowercase()).ok_or_else(|| ConfigError(format!("Unknown style \"{}\" in \"{}\"", word, spec))))
+            .collect::<Result<Vec<String>>>()?;
+
+        Ok(Style(codes.join(";")))
+    }
+
+    /// `text` wrapped in the escape codes, line by line so pagers keep them.
+    pub fn paint(&self, text: &str) -> String {
+        if self.0.is_empty() {
+            return text.to_string();
+        }
+        text.split('\n')
+            .map(|line| if line.is_empty() { String::new() } else { format!("\x1b[{}m{}\x1b[0m", self.0, line) })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+// the SGR code for one word of a spec
+fn code(word: &str) -> Option<String> {
+    const COLORS: [&str; 8] = ["black", "red", "green", "yellow", "blue", "magenta", "cyan", "white"];
+    let color = |name: &str| COLORS.iter().position(|c| *c == name);
+
+    match word {
+        "bold" => Some("1".to_string()),
+        "dim" => Some("2".to_string()),
+        "italic" => Some("3".to_string()),
+        "underline" => Some("4".to_string()),
+        hex if hex.starts_with('#') && hex.len() == 7 => {
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            Some(format!("38;2;{};{};{}", channel(1)?, channel(3)?, channel(5)?))
+        }
+        name => match name.strip_prefix("bright-") {
+            Some(name) => color(name).map(|i| (90 + i).to_string()),
+            None => color(name).map(|i| (30 + i).to_string()),
+        },
+    }
+}
+
+/// How each element of an entry is styled.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Theme {
+    pub label: Style,
+    pub title: Style,
+    pub quote: Style,
+    pub quoter: Style,
+    pub explanation: Style
+}
+
+impl Theme {
+    /// The named theme (default unless `config.name` is set) with any styles
+    /// `config` gives replacing its own.
+    pub fn from_config(config: &ThemeConfig) -> Result<Self> {
+        let name = config.name.as_deref().unwrap_or("default");
+        let (_, specs) = THEMES
+            .iter()
+            .find(|(theme, _)| theme.eq_ignore_ascii_case(name))
+            .ok_or_else(|| {
+                let names: Vec<&str> = THEMES.iter().map(|(name, _)| *name).collect();
+                ConfigError(format!("Unknown theme \"{}\" (expected {})", name, names.join(", ")))
+            })?;
+
+        let style = |custom: &Option<String>, default: &str| Style::parse(custom.as_deref().unwrap_or(default));
+        Ok(Theme {
+            label: style(&config.label, specs[0])?,
+            title: style(&config.title, specs[1])?,
+            quote: style(&config.quote, specs[2])?,
+            quoter: style(&config.quoter, specs[3])?,
+            explanation: style(&config.explanation, specs[4])?
+        })
+    }
+}
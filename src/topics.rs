@@ -0,0 +1,61 @@
+//! Tagging entries with recurring Stoic themes, by keyword or with the LLM.
+
+use crate::error::Result;
+use crate::llm::LlmProvider;
+use crate::Daily;
+
+/// Every theme with the word beginnings that suggest it.
+pub const TOPICS: [(&str, &[&str]); 12] = [
+    ("mortality", &["death", "die", "dying", "mortal", "dead", "grave", "memento mori"]),
+    ("anger", &["anger", "angry", "rage", "wrath", "furious", "temper"]),
+    ("discipline", &["discipline", "habit", "self-control", "restraint", "practice", "training"]),
+    ("perception", &["perception", "judgment", "opinion", "impression", "perspective", "see things"]),
+    ("control", &["in our control", "up to us", "up to you", "within our power", "dichotomy"]),
+    ("fear", &["fear", "afraid", "anxiety", "worry", "dread"]),
+    ("duty", &["duty", "obligation", "responsibility", "service", "common good"]),
+    ("virtue", &["virtue", "justice", "courage", "wisdom", "temperance", "character"]),
+    ("time", &["time", "present moment", "hours", "brevity", "short life"]),
+    ("adversity", &["adversity", "obstacle", "hardship", "misfortune", "suffering", "setback"]),
+    ("desire", &["desire", "wealth", "pleasure", "fame", "greed", "luxury"]),
+    ("acceptance", &["accept", "fate", "amor fati", "embrace", "whatever happens"]),
+];
+
+/// Themes with a keyword starting a word anywhere in the entry.
+pub fn classify(daily: &Daily) -> Vec<String> {
+    // pad and normalize punctuation so " keyword" only matches at word starts
+    let text: String = format!(" {} {} {} ", daily.title, daily.quote, daily.explanation)
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() || c == '-' { c } else { ' ' })
+        .collect();
+
+    TOPICS
+        .iter()
+        .filter(|(_, keywords)| keywords.iter().any(|k| text.contains(&format!(" {}", k))))
+        .map(|(topic, _)| topic.to_string())
+        .collect()
+}
+
+pub fn classify_prompt(daily: &Daily) -> String {
+    let topics: Vec<&str> = TOPICS.iter().map(|(topic, _)| *topic).collect();
+    format!(
+        "Which of these themes does the following meditation deal with? Themes: {}.\n\
+        Reply with the matching themes only, comma separated, most relevant first, at most three.\n\
+        Title: {}\nQuote: {}\nExplanation: {}",
+        topics.join(", "),
+        daily.title,
+        daily.quote,
+        daily.explanation
+    )
+}
+
+/// Asks `llm` for the entry's themes, keeping only known ones.
+pub async fn classify_with_llm(llm: &dyn LlmProvider, daily: &Daily) -> Result<Vec<String>> {
+    let reply = llm.complete(&classify_prompt(daily)).await?.to_lowercase();
+    Ok(TOPICS
+        .iter()
+        .map(|(topic, _)| *topic)
+        .filter(|topic| reply.contains(topic))
+        .map(str::to_string)
+        .collect())
+}
@@ -0,0 +1,260 @@
+//! Reading entries aloud with the system voice or a cloud text-to-speech API.
+
+use crate::config::TtsConfig;
+use crate::error::{DailyStoicError::{ConfigError, NetworkError, StorageError}, Result};
+use crate::http::{self, RetryPolicy};
+use crate::Daily;
+use async_trait::async_trait;
+use serde_json::json;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+const DEFAULT_OPENAI_ENDPOINT: &str = "https://api.openai.com/v1/audio/speech";
+const DEFAULT_OPENAI_MODEL: &str = "gpt-4o-mini-tts";
+const DEFAULT_OPENAI_VOICE: &str = "onyx";
+const DEFAULT_ELEVENLABS_ENDPOINT: &str = "https://api.elevenlabs.io/v1/text-to-speech";
+const DEFAULT_ELEVENLABS_MODEL: &str = "eleven_multilingual_v2";
+// "George", one of the premade voices every account has
+const DEFAULT_ELEVENLABS_VOICE: &str = "JBFqnCBsd6RMkjVDRZzb";
+
+/// A voice that turns text into audio.
+#[async_trait]
+pub trait TtsProvider: Send + Sync {
+    /// Identifies the provider and voice, e.g. "openai:onyx".
+    fn name(&self) -> String;
+
+    /// Audio file extensions it can produce, the first being the default.
+    fn formats(&self) -> &'static [&'static str];
+
+    /// `text` as audio in `format`, one of [`TtsProvider::formats`].
+    async fn synthesize(&self, text: &str, format: &str) -> Result<Vec<u8>>;
+
+    /// Reads `text` out directly where the provider can, returning `false`
+    /// when the caller should play the synthesized audio itself.
+    fn speak(&self, _text: &str) -> Result<bool> {
+        Ok(false)
+    }
+}
+
+/// What gets read: the date, title, quote, who said it and the explanation.
+pub fn script(daily: &Daily) -> String {
+    // all caps titles get spelled out as acronyms by some voices
+    let title = daily.title.to_lowercase();
+    let mut chars = title.chars();
+    let title: String = chars.next().map(|c| c.to_uppercase().chain(chars).collect()).unwrap_or_default();
+
+    let source = match &daily.citation.work {
+        Some(work) => format!("{}, {}", daily.citation.author, work),
+        None => daily.citation.author.clone(),
+    };
+
+    format!("{}. {}.\n\n{}\n\n{}.\n\n{}", daily.date, title, daily.quote, source, daily.explanation)
+}
+
+/// Selects a provider by `config.provider` (system, openai or elevenlabs,
+/// defaulting to system).
+pub fn provider_from_config(config: &TtsConfig, retry: RetryPolicy) -> Result<Box<dyn TtsProvider>> {
+    let api_key = || {
+        config.api_key.clone().ok_or_else(|| ConfigError("No text-to-speech API key configured (tts.api_key)".to_string()))
+    };
+
+    let provider: Box<dyn TtsProvider> = match config.provider.as_deref().unwrap_or("system") {
+        "system" => Box::new(System { voice: config.voice.clone() }),
+        "openai" => Box::new(OpenAi {
+            endpoint: config.endpoint.clone().unwrap_or_else(|| DEFAULT_OPENAI_ENDPOINT.to_string()),
+            api_key: api_key()?,
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_OPENAI_MODEL.to_string()),
+            voice: config.voice.clone().unwrap_or_else(|| DEFAULT_OPENAI_VOICE.to_string()),
+            retry,
+        }),
+        "elevenlabs" => Box::new(ElevenLabs {
+            endpoint: config.endpoint.clone().unwrap_or_else(|| DEFAULT_ELEVENLABS_ENDPOINT.to_string()),
+            api_key: api_key()?,
+            model: config.model.clone().unwrap_or_else(|| DEFAULT_ELEVENLABS_MODEL.to_string()),
+            voice: config.voice.clone().unwrap_or_else(|| DEFAULT_ELEVENLABS_VOICE.to_string()),
+            retry,
+        }),
+        other => return Err(ConfigError(format!(
+            "Unknown text-to-speech provider \"{}\" (expected system, openai or elevenlabs)", other
+        ))),
+    };
+
+    Ok(provider)
+}
+
+/// The operating system's voice: `say` on macOS, `espeak-ng` or `espeak` on
+/// Linux and SAPI through PowerShell on Windows.
+pub struct System {
+    pub voice: Option<String>
+}
+
+impl System {
+    // the command reading text from stdin, writing a WAV file to `out` if given
+    fn command(&self, out: Option<&Path>) -> Result<Command> {
+        if cfg!(target_os = "macos") {
+            let mut command = Command::new("say");
+            command.args(["-f", "-"]);
+            if let Some(voice) = &self.voice {
+                command.args(["-v", voice]);
+            }
+            if let Some(out) = out {
+                command.arg("-o").arg(out).args(["--file-format=WAVE", "--data-format=LEI16@22050"]);
+            }
+            Ok(command)
+        } else if cfg!(windows) {
+            let mut script = String::from(
+                "Add-Type -AssemblyName System.Speech; $s = New-Object System.Speech.Synthesis.SpeechSynthesizer;"
+            );
+            if let Some(voice) = &self.voice {
+                script.push_str(&format!(" $s.SelectVoice('{}');", voice.replace('\'', "''")));
+            }
+            if let Some(out) = out {
+                script.push_str(&format!(" $s.SetOutputToWaveFile('{}');", out.display().to_string().replace('\'', "''")));
+            }
+            script.push_str(" $s.Speak([Console]::In.ReadToEnd())");
+
+            let mut command = Command::new("powershell");
+            command.args(["-NoProfile", "-Command", &script]);
+            Ok(command)
+        } else {
+            let program = ["espeak-ng", "espeak"]
+                .into_iter()
+                .find(|program| Command::new(program).arg("--version").output().is_ok())
+                .ok_or_else(|| ConfigError("Install espeak-ng or espeak, or set tts.provider to a cloud voice".to_string()))?;
+
+            let mut command = Command::new(program);
+            command.arg("--stdin");
+            if let Some(voice) = &self.voice {
+                command.args(["-v", voice]);
+            }
+            if let Some(out) = out {
+                command.arg("-w").arg(out);
+            }
+            Ok(command)
+        }
+    }
+
+    fn run(&self, text: &str, out: Option<&Path>) -> Result<()> {
+        let mut command = self.command(out)?;
+        let program = command.get_program().to_string_lossy().to_string();
+        let error = |e: &dyn std::fmt::Display| StorageError(format!("Failed to run {}: {}", program, e));
+
+        let mut child = command.stdin(Stdio::piped()).spawn().map_err(|e| error(&e))?;
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes()).map_err(|e| error(&e))?;
+        }
+        let status = child.wait().map_err(|e| error(&e))?;
+        if !status.success() {
+            return Err(error(&status));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl TtsProvider for System {
+    fn name(&self) -> String {
+        format!("system:{}", self.voice.as_deref().unwrap_or("default"))
+    }
+
+    fn formats(&self) -> &'static [&'static str] {
+        &["wav"]
+    }
+
+    async fn synthesize(&self, text: &str, _format: &str) -> Result<Vec<u8>> {
+        let path = std::env::temp_dir().join(format!("daily-stoic-speech-{}.wav", std::process::id()));
+        let result = self
+            .run(text, Some(&path))
+            .and_then(|()| std::fs::read(&path).map_err(|e| StorageError(format!("Failed to read {}: {}", path.display(), e))));
+        let _ = std::fs::remove_file(&path);
+        result
+    }
+
+    fn speak(&self, text: &str) -> Result<bool> {
+        self.run(text, None).map(|()| true)
+    }
+}
+
+/// OpenAI's speech endpoint.
+pub struct OpenAi {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    pub voice: String,
+    pub retry: RetryPolicy
+}
+
+#[async_trait]
+impl TtsProvider for OpenAi {
+    fn name(&self) -> String {
+        format!("openai:{}", self.voice)
+    }
+
+    fn formats(&self) -> &'static [&'static str] {
+        &["mp3", "wav", "opus", "aac", "flac"]
+    }
+
+    async fn synthesize(&self, text: &str, format: &str) -> Result<Vec<u8>> {
+        let body = json!({
+            "model": self.model,
+            "voice": self.voice,
+            "input": text,
+            "response_format": format
+        });
+
+        let client = reqwest::Client::new();
+        let response = http::send_with_retry(&self.retry, || {
+            client.post(&self.endpoint).bearer_auth(&self.api_key).json(&body)
+        }).await?;
+        audio(response).await
+    }
+}
+
+/// ElevenLabs' text-to-speech endpoint.
+pub struct ElevenLabs {
+    pub endpoint: String,
+    pub api_key: String,
+    pub model: String,
+    pub voice: String,
+    pub retry: RetryPolicy
+}
+
+#[async_trait]
+impl TtsProvider for ElevenLabs {
+    fn name(&self) -> String {
+        format!("elevenlabs:{}", self.voice)
+    }
+
+    fn formats(&self) -> &'static [&'static str] {
+        &["mp3"]
+    }
+
+    async fn synthesize(&self, text: &str, _format: &str) -> Result<Vec<u8>> {
+        let body = json!({ "text": text, "model_id": self.model });
+        let url = format!("{}/{}", self.endpoint.trim_end_matches('/'), self.voice);
+
+        let client = reqwest::Client::new();
+        let response = http::send_with_retry(&self.retry, || {
+            client
+                .post(&url)
+                .header("xi-api-key", &self.api_key)
+                .header("Accept", "audio/mpeg")
+                .json(&body)
+        }).await?;
+        audio(response).await
+    }
+}
+
+async fn audio(response: reqwest::Response) -> Result<Vec<u8>> {
+    let status = response.status();
+    if !status.is_success() {
+        let text = response.text().await.unwrap_or_default();
+        return Err(NetworkError(format!("Text-to-speech request failed with status {}: {}", status, text.trim())));
+    }
+    let bytes = response
+        .bytes()
+        .await
+        .map_err(|e| NetworkError(format!("Failed to read the audio: {}", e)))?;
+    Ok(bytes.to_vec())
+}
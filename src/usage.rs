@@ -0,0 +1,205 @@
+//! A local ledger of the tokens every LLM call used, for `daily-stoic usage`
+//! and the monthly budget.
+//!
+//! Each call is a line of JSON in `usage.jsonl` next to the archive. Costs
+//! are estimated when read, from the built-in prices or `[llm.prices]`.
+
+use crate::error::{DailyStoicError::{LlmError, StorageError}, Result};
+use crate::llm::{LlmProvider, OnText, TokenUsage};
+use async_trait::async_trait;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// USD per million input and output tokens, by model name without the vendor prefix.
+const PRICES: [(&str, [f64; 2]); 8] = [
+    ("gpt-4o", [2.5, 10.0]),
+    ("gpt-4o-mini", [0.15, 0.6]),
+    ("gpt-4.1", [2.0, 8.0]),
+    ("gpt-4.1-mini", [0.4, 1.6]),
+    ("gpt-4.1-nano", [0.1, 0.4]),
+    ("claude-sonnet-4-5", [3.0, 15.0]),
+    ("claude-haiku-4-5", [1.0, 5.0]),
+    ("claude-opus-4-1", [15.0, 75.0]),
+];
+
+/// One LLM call.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Record {
+    /// Local day of the call, e.g. "2026-03-05".
+    pub date: String,
+    /// The provider's name, e.g. "openai:openai/gpt-4o".
+    pub model: String,
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64
+}
+
+impl Record {
+    /// The estimated cost in USD, if the model's price is known.
+    pub fn cost(&self, prices: &BTreeMap<String, [f64; 2]>) -> Option<f64> {
+        let [input, output] = price(&self.model, prices)?;
+        Some((self.prompt_tokens as f64 * input + self.completion_tokens as f64 * output) / 1_000_000.0)
+    }
+}
+
+/// The price of `model` (a provider name like "openai:openai/gpt-4o"), from
+/// `prices` first, matching with or without the vendor prefix. Local Ollama
+/// models are free.
+///
+/// ```
+/// use daily_stoic_rs::usage::price;
+/// use std::collections::BTreeMap;
+///
+/// let mut prices = BTreeMap::new();
+/// prices.insert("my-model".to_string(), [1.0, 2.0]);
+/// assert_eq!(price("openai:vendor/my-model", &prices), Some([1.0, 2.0]));
+/// assert_eq!(price("openai:openai/gpt-4o", &prices), Some([2.5, 10.0]));
+/// assert_eq!(price("ollama:llama3.2", &prices), Some([0.0, 0.0]));
+/// assert_eq!(price("openai:unknown", &prices), None);
+/// ```
+pub fn price(model: &str, prices: &BTreeMap<String, [f64; 2]>) -> Option<[f64; 2]> {
+    let (provider, name) = model.split_once(':').unwrap_or(("", model));
+    let bare = name.rsplit('/').next().unwrap_or(name);
+    if let Some(price) = prices.get(name).or_else(|| prices.get(bare)) {
+        return Some(*price);
+    }
+    if provider == "ollama" {
+        return Some([0.0, 0.0]);
+    }
+    PRICES.iter().find(|(known, _)| *known == bare).map(|(_, price)| *price)
+}
+
+/// The `usage.jsonl` file of records.
+#[derive(Clone)]
+pub struct Ledger {
+    path: PathBuf
+}
+
+impl Ledger {
+    pub fn new(path: &Path) -> Self {
+        Ledger { path: path.to_path_buf() }
+    }
+
+    /// `$XDG_DATA_HOME/daily-stoic/usage.jsonl` (or the platform equivalent).
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::data_dir().map(|dir| dir.join("daily-stoic").join("usage.jsonl"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Appends a call by `model` dated today.
+    pub fn record(&self, model: &str, usage: TokenUsage) -> Result<()> {
+        let record = Record {
+            date: Local::now().format("%Y-%m-%d").to_string(),
+            model: model.to_string(),
+            prompt_tokens: usage.prompt_tokens,
+            completion_tokens: usage.completion_tokens,
+        };
+        let line = serde_json::to_string(&record)
+            .map_err(|e| StorageError(format!("Failed to serialize a usage record: {}", e)))?;
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| StorageError(format!("Failed to create {}: {}", parent.display(), e)))?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(|e| StorageError(format!("Failed to open {}: {}", self.path.display(), e)))?;
+        writeln!(file, "{}", line).map_err(|e| StorageError(format!("Failed to write {}: {}", self.path.display(), e)))
+    }
+
+    /// Every record, oldest first. Lines that don't parse are skipped.
+    pub fn records(&self) -> Result<Vec<Record>> {
+        let contents = match fs::read_to_string(&self.path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(StorageError(format!("Failed to read {}: {}", self.path.display(), e))),
+        };
+        Ok(contents.lines().filter_map(|line| serde_json::from_str(line).ok()).collect())
+    }
+
+    /// The estimated USD spent in the current month, models without a price counting as free.
+    pub fn spent_this_month(&self, prices: &BTreeMap<String, [f64; 2]>) -> Result<f64> {
+        let month = Local::now().format("%Y-%m-").to_string();
+        Ok(self.records()?
+            .iter()
+            .filter(|record| record.date.starts_with(&month))
+            .filter_map(|record| record.cost(prices))
+            // sum() of no floats is -0.0, which prints as "$-0.0000"
+            .fold(0.0, |total, cost| total + cost))
+    }
+}
+
+/// Wraps a provider to record the tokens of every completion in a [`Ledger`]
+/// and to refuse calls once the month's estimated cost reaches the budget.
+pub struct Metered {
+    pub inner: Box<dyn LlmProvider>,
+    pub ledger: Ledger,
+    pub prices: BTreeMap<String, [f64; 2]>,
+    pub budget: Option<f64>
+}
+
+impl Metered {
+    fn check_budget(&self) -> Result<()> {
+        let Some(budget) = self.budget else { return Ok(()) };
+        let spent = self.ledger.spent_this_month(&self.prices)?;
+        if spent >= budget {
+            return Err(LlmError(format!(
+                "This month's LLM budget of ${:.2} is used up (${:.4} spent), raise llm.monthly_budget or use --no-llm",
+                budget, spent
+            )));
+        }
+        Ok(())
+    }
+
+    // a reply that can't be recorded is still worth using
+    fn record(&self, usage: Option<TokenUsage>) {
+        if let Some(usage) = usage
+            && let Err(e) = self.ledger.record(&self.inner.name(), usage)
+        {
+            warn!("{}", e);
+        }
+    }
+}
+
+#[async_trait]
+impl LlmProvider for Metered {
+    fn name(&self) -> String {
+        self.inner.name()
+    }
+
+    fn generates_text(&self) -> bool {
+        self.inner.generates_text()
+    }
+
+    async fn complete(&self, prompt: &str) -> Result<String> {
+        Ok(self.complete_with_usage(prompt).await?.0)
+    }
+
+    async fn complete_with_usage(&self, prompt: &str) -> Result<(String, Option<TokenUsage>)> {
+        self.check_budget()?;
+        let (text, usage) = self.inner.complete_with_usage(prompt).await?;
+        self.record(usage);
+        Ok((text, usage))
+    }
+
+    async fn complete_streaming(&self, prompt: &str, on_text: &OnText<'_>) -> Result<(String, Option<TokenUsage>)> {
+        self.check_budget()?;
+        let (text, usage) = self.inner.complete_streaming(prompt, on_text).await?;
+        self.record(usage);
+        Ok((text, usage))
+    }
+
+    async fn embed(&self, text: &str) -> Result<Vec<f32>> {
+        self.check_budget()?;
+        self.inner.embed(text).await
+    }
+}